@@ -152,6 +152,19 @@ pub(crate) unsafe fn create_connection_request(
         pubsub_subscriptions: None,
         inflight_requests_limit: None,
         lazy_connect: false,
+        sentinel: None,
+        tls_config: None,
+        credential_provider: None,
+        client_side_cache: None,
+        connections_per_node: None,
+        address_remap: None,
+        circuit_breaker_threshold: None,
+        read_hedging: None,
+        connection_error_retry: None,
+        command_routing_table: None,
+        key_prefix: None,
+        command_policy: None,
+        middlewares: Vec::new(),
     }
 }
 
@@ -167,6 +180,7 @@ impl From<&Address> for NodeAddress {
         NodeAddress {
             host: unsafe { ptr_to_str(addr.host) },
             port: addr.port,
+            unix_socket_path: None,
         }
     }
 }
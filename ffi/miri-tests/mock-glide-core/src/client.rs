@@ -1,6 +1,6 @@
 // Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
 
-pub use glide_core::client::{GlideRt, get_or_init_runtime};
+pub use glide_core::client::{GlideRt, TlsConfig, get_or_init_runtime};
 
 use crate::connection_request::ConnectionRequest;
 use redis::{Pipeline, PipelineRetryStrategy, ScanStateRC, Cmd, PushInfo, Value, ClusterScanArgs, RoutingInfo, RedisResult};
@@ -56,6 +56,7 @@ impl Client {
         _keys: &Vec<&[u8]>,
         _args: &Vec<&[u8]>,
         _routing: Option<RoutingInfo>,
+        _request_timeout: Option<u32>,
     ) -> redis::RedisResult<Value> {
         todo!()
     }
@@ -68,6 +69,14 @@ impl Client {
         todo!()
     }
 
+    pub async fn update_tls_credentials(
+        &mut self,
+        _tls_config: TlsConfig,
+        _reconnect: bool,
+    ) -> redis::RedisResult<Value> {
+        todo!()
+    }
+
     pub fn send_command<'a>(
         &'a mut self,
         _cmd: &'a Cmd,
@@ -76,10 +85,20 @@ impl Client {
         todo!()
     }
 
+    pub fn send_command_with_timeout<'a>(
+        &'a mut self,
+        _cmd: &'a Cmd,
+        _routing: Option<RoutingInfo>,
+        _request_timeout: Option<u32>,
+    ) -> redis::RedisFuture<'a, redis::Value> {
+        todo!()
+    }
+
     pub async fn cluster_scan<'a>(
         &'a mut self,
         _scan_state_cursor: &'a ScanStateRC,
         _cluster_scan_args: ClusterScanArgs,
+        _request_timeout: Option<u32>,
     ) -> RedisResult<Value> {
         todo!()
     }
@@ -8,6 +8,8 @@ pub enum RequestErrorType {
     ExecAbort = 1,
     Timeout = 2,
     Disconnect = 3,
+    Cancelled = 4,
+    CommandNotAllowed = 5,
 }
 
 pub fn error_type(_error: &RedisError) -> RequestErrorType {
@@ -6,6 +6,7 @@ pub mod command_request;
 pub mod errors;
 pub mod connection_request;
 pub mod scripts_container;
+pub mod request_statistics;
 pub mod request_type;
 
 pub use client::*;
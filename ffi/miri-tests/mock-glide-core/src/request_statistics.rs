@@ -0,0 +1,34 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use crate::request_type::RequestType;
+use redis::RedisResult;
+use std::future::Future;
+
+pub enum RequestKind {
+    Command(RequestType),
+    Transaction,
+    Pipeline,
+}
+
+pub struct RequestStatistics;
+
+impl RequestStatistics {
+    pub fn to_json(&self) -> String {
+        todo!()
+    }
+}
+
+pub fn set_request_statistics_enabled(_enabled: bool) {}
+
+pub async fn record_request<T>(
+    _kind: RequestKind,
+    request: impl Future<Output = RedisResult<T>>,
+) -> RedisResult<T> {
+    request.await
+}
+
+pub fn request_statistics() -> RequestStatistics {
+    todo!()
+}
+
+pub fn reset_request_statistics() {}
@@ -8,7 +8,11 @@ pub struct RequestType {
     _data: ()
 }
 
+#[allow(non_upper_case_globals)]
 impl RequestType {
+    pub const Scan: RequestType = RequestType { _data: () };
+    pub const EvalSha: RequestType = RequestType { _data: () };
+
     pub fn get_command(&self) -> Option<Cmd> {
         Some(Cmd)
     }
@@ -5,3 +5,17 @@ use std::marker::PhantomData;
 pub struct JoinHandle<T> {
     pub _p: PhantomData<T>,
 }
+
+impl<T> JoinHandle<T> {
+    pub fn abort(&self) {}
+
+    pub fn abort_handle(&self) -> AbortHandle {
+        AbortHandle
+    }
+}
+
+pub struct AbortHandle;
+
+impl AbortHandle {
+    pub fn abort(&self) {}
+}
@@ -2,12 +2,14 @@
 
 use glide_core::ConnectionRequest;
 use glide_core::client::Client as GlideClient;
+use glide_core::client::TlsConfig;
 use glide_core::cluster_scan_container::get_cluster_scan_cursor;
 use glide_core::command_request::SimpleRoutes;
 use glide_core::command_request::{Routes, SlotTypes};
 use glide_core::connection_request;
 use glide_core::errors::RequestErrorType;
 use glide_core::errors::{self, error_message};
+use glide_core::request_statistics::{self, RequestKind, record_request};
 use glide_core::request_type::RequestType;
 use glide_core::scripts_container;
 use glide_core::{
@@ -24,13 +26,15 @@ use redis::cluster_routing::{
 use redis::cluster_routing::{ResponsePolicy, Routable};
 use redis::{ClusterScanArgs, RedisError};
 use redis::{Cmd, Pipeline, PipelineRetryStrategy, RedisResult, Value};
+use std::collections::HashMap;
 use std::ffi::CStr;
 use std::future::Future;
 use std::mem::ManuallyDrop;
 use std::slice::from_raw_parts;
 use std::str;
 use std::str::FromStr;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
 use std::{
     ffi::{CString, c_void},
     mem,
@@ -38,6 +42,7 @@ use std::{
 };
 use tokio::runtime::Builder;
 use tokio::runtime::Runtime;
+use tokio::task::AbortHandle;
 
 #[repr(C)]
 pub struct ScriptHashBuffer {
@@ -393,6 +398,17 @@ pub struct ClientAdapter {
 struct CommandExecutionCore {
     client: GlideClient,
     client_type: ClientType,
+    /// The requests of an async client that didn't complete yet, by their request ID, so they can be cancelled.
+    pending_requests: Mutex<HashMap<usize, PendingRequest>>,
+}
+
+/// A request of an async client that didn't complete yet.
+struct PendingRequest {
+    /// The abort handle of the request's task, which is set once the task is spawned.
+    task: Option<AbortHandle>,
+    /// The kind that the request is recorded under in the request statistics, if it's recorded.
+    kind: Option<RequestKind>,
+    started: Instant,
 }
 
 impl ClientAdapter {
@@ -400,8 +416,15 @@ impl ClientAdapter {
     ///
     /// For async clients, spawns the future and returns null immediately.
     /// For sync clients, blocks on the future and returns a `CommandResult`.
+    /// `kind` is the kind that the request is recorded under in the request statistics, if it's recorded,
+    /// so a cancelled request can be recorded too.
     #[must_use]
-    fn execute_request<Fut>(&self, request_id: usize, request_future: Fut) -> *mut CommandResult
+    fn execute_request<Fut>(
+        &self,
+        request_id: usize,
+        kind: Option<RequestKind>,
+        request_future: Fut,
+    ) -> *mut CommandResult
     where
         Fut: Future<Output = RedisResult<Value>> + Send + 'static,
     {
@@ -410,9 +433,29 @@ impl ClientAdapter {
                 success_callback,
                 failure_callback,
             } => {
+                // The request is registered before it's spawned, since it's only answered if it's still registered.
+                self.core.pending_requests.lock().unwrap().insert(
+                    request_id,
+                    PendingRequest {
+                        task: None,
+                        kind,
+                        started: Instant::now(),
+                    },
+                );
+                let core = self.core.clone();
                 // Spawn the request for async client
-                self.runtime.spawn(async move {
+                let task = self.runtime.spawn(async move {
                     let result = request_future.await;
+                    // A cancelled request was already answered by `cancel_request`.
+                    if core
+                        .pending_requests
+                        .lock()
+                        .unwrap()
+                        .remove(&request_id)
+                        .is_none()
+                    {
+                        return;
+                    }
                     let _ = Self::handle_result(
                         result,
                         Some(success_callback),
@@ -420,6 +463,17 @@ impl ClientAdapter {
                         request_id,
                     );
                 });
+                match self
+                    .core
+                    .pending_requests
+                    .lock()
+                    .unwrap()
+                    .get_mut(&request_id)
+                {
+                    Some(pending_request) => pending_request.task = Some(task.abort_handle()),
+                    // The request was either cancelled while it was spawned, or it already completed.
+                    None => task.abort(),
+                }
                 std::ptr::null_mut()
             }
             ClientType::SyncClient => {
@@ -430,6 +484,42 @@ impl ClientAdapter {
         }
     }
 
+    /// Aborts a pending request of an async client, and invokes the failure callback with a
+    /// [`RequestErrorType::Cancelled`] error. Returns `false` if there's no such pending request.
+    fn cancel_request(&self, request_id: usize) -> bool {
+        let ClientType::AsyncClient {
+            failure_callback, ..
+        } = self.core.client_type
+        else {
+            return false;
+        };
+        let Some(pending_request) = self
+            .core
+            .pending_requests
+            .lock()
+            .unwrap()
+            .remove(&request_id)
+        else {
+            return false;
+        };
+        // A request whose task is still being spawned is aborted once it's spawned.
+        if let Some(task) = pending_request.task {
+            task.abort();
+        }
+        if let Some(kind) = pending_request.kind {
+            request_statistics::record_cancelled_request(kind, pending_request.started);
+        }
+        unsafe {
+            Self::send_async_custom_error(
+                failure_callback,
+                "The request was cancelled".to_string(),
+                RequestErrorType::Cancelled,
+                request_id,
+            )
+        };
+        true
+    }
+
     /// Handles the result of a command and returns a `CommandResult`.
     ///
     /// For async clients, invokes the appropriate callback and returns null.
@@ -735,6 +825,7 @@ fn create_client_internal(
     let core = Arc::new(CommandExecutionCore {
         client,
         client_type,
+        pending_requests: Mutex::new(HashMap::new()),
     });
     let client_adapter = Arc::new(ClientAdapter { runtime, core });
     // Clone client_adapter before moving it into the async block
@@ -1099,6 +1190,7 @@ fn valkey_value_to_command_response(value: Value) -> RedisResult<CommandResponse
 /// * `route_bytes_len` is the number of bytes in `route_bytes`. It must also not be greater than the max value of a signed pointer-sized integer.
 /// * `route_bytes_len` must be 0 if `route_bytes` is null.
 /// * `span_ptr` is a valid pointer to [`Arc<GlideSpan>`], a span created by [`create_otel_span`] or `0`. The span must be valid until the command is finished.
+/// * `timeout` overrides the client's request timeout for this command, in milliseconds, and is only used if `has_timeout` is true.
 /// * This function should only be called should with a `client_adapter_ptr` created by [`create_client`], before [`close_client`] was called with the pointer.
 #[unsafe(no_mangle)]
 pub unsafe extern "C-unwind" fn command(
@@ -1111,6 +1203,8 @@ pub unsafe extern "C-unwind" fn command(
     route_bytes: *const u8,
     route_bytes_len: usize,
     span_ptr: u64,
+    has_timeout: bool,
+    timeout: u32,
 ) -> *mut CommandResult {
     let client_adapter = unsafe {
         // we increment the strong count to ensure that the client is not dropped just because we turned it into an Arc.
@@ -1159,9 +1253,14 @@ pub unsafe extern "C-unwind" fn command(
 
     let child_span = create_child_span(cmd.span().as_ref(), "send_command");
     let mut client = client_adapter.core.client.clone();
-    let result = client_adapter.execute_request(request_id, async move {
-        let routing_info = get_route(route, Some(&cmd))?;
-        client.send_command(&cmd, routing_info).await
+    let kind = RequestKind::Command(command_type);
+    let result = client_adapter.execute_request(request_id, Some(kind), async move {
+        let routing_info = get_route(route, Some(&cmd), &client)?;
+        record_request(
+            kind,
+            client.send_command_with_timeout(&cmd, routing_info, has_timeout.then_some(timeout)),
+        )
+        .await
     });
     if let Ok(span) = child_span {
         span.end();
@@ -1169,6 +1268,30 @@ pub unsafe extern "C-unwind" fn command(
     result
 }
 
+/// Cancels a request that was sent by an async client, e.g. through [`command`], and didn't complete yet.
+/// The request is aborted, and the failure callback is invoked for it with a [`RequestErrorType::Cancelled`] error.
+///
+/// Returns `false` if the request already completed, or if the client isn't an async client.
+///
+/// # Safety
+///
+/// * `client_adapter_ptr` must not be `null` and must be obtained from the `ConnectionResponse` returned from [`create_client`].
+/// * `client_adapter_ptr` must be able to be safely casted to a valid [`Arc<ClientAdapter>`] via [`Arc::from_raw`]. See the safety documentation of [`std::sync::Arc::from_raw`].
+/// * `request_id` must be the request ID of a request that was sent with `client_adapter_ptr`.
+/// * This function should only be called with a `client_adapter_ptr` created by [`create_client`], before [`close_client`] was called with the pointer.
+#[unsafe(no_mangle)]
+pub unsafe extern "C-unwind" fn cancel_request(
+    client_adapter_ptr: *const c_void,
+    request_id: usize,
+) -> bool {
+    let client_adapter = unsafe {
+        // we increment the strong count to ensure that the client is not dropped just because we turned it into an Arc.
+        Arc::increment_strong_count(client_adapter_ptr);
+        Arc::from_raw(client_adapter_ptr as *mut ClientAdapter)
+    };
+    client_adapter.cancel_request(request_id)
+}
+
 /// Creates a heap-allocated `CommandResult` containing a `CommandError`.
 ///
 /// This function is used to construct an error response when a Valkey command fails,
@@ -1261,7 +1384,11 @@ fn to_c_error(err: RedisError) -> (*const c_char, RequestErrorType) {
     (c_err_str, error_type)
 }
 
-fn get_route(route: Routes, cmd: Option<&Cmd>) -> RedisResult<Option<RoutingInfo>> {
+fn get_route(
+    route: Routes,
+    cmd: Option<&Cmd>,
+    client: &GlideClient,
+) -> RedisResult<Option<RoutingInfo>> {
     use glide_core::command_request::routes::Value;
     let route = match route.value {
         Some(route) => route,
@@ -1301,7 +1428,7 @@ fn get_route(route: Routes, cmd: Option<&Cmd>) -> RedisResult<Option<RoutingInfo
         }
         Value::SlotKeyRoute(slot_key_route) => Ok(Some(RoutingInfo::SingleNode(
             SingleNodeRoutingInfo::SpecificNode(Route::new(
-                redis::cluster_topology::get_slot(slot_key_route.slot_key.as_bytes()),
+                client.key_slot(slot_key_route.slot_key.as_bytes()),
                 get_slot_addr(&slot_key_route.slot_type)?,
             )),
         ))),
@@ -1485,10 +1612,13 @@ pub unsafe extern "C-unwind" fn request_cluster_scan(
         Err(_error) => ScanStateRC::new(),
     };
     let mut client = client_adapter.core.client.clone();
-    client_adapter.execute_request(request_id, async move {
-        client
-            .cluster_scan(&scan_state_cursor, cluster_scan_args)
-            .await
+    let kind = RequestKind::Command(RequestType::Scan);
+    client_adapter.execute_request(request_id, Some(kind), async move {
+        record_request(
+            kind,
+            client.cluster_scan(&scan_state_cursor, cluster_scan_args, None),
+        )
+        .await
     })
 }
 
@@ -1550,13 +1680,80 @@ pub unsafe extern "C-unwind" fn update_connection_password(
         Some(password.to_string())
     };
     let mut client = client_adapter.core.client.clone();
-    client_adapter.execute_request(request_id, async move {
+    client_adapter.execute_request(request_id, None, async move {
         client
             .update_connection_password(password_option, immediate_auth)
             .await
     })
 }
 
+/// Allows the client to replace its TLS credentials without being recreated.
+///
+/// `client_adapter_ptr` is a pointer to a valid `GlideClusterClient` returned in the `ConnectionResponse` from [`create_client`].
+/// `request_id` is a unique identifier for a valid payload buffer which is created in the client.
+/// `root_certs` is a pointer to the PEM encoded root certificates, and `root_certs_len` is their length. An empty buffer means the platform's verifier is used.
+/// `client_cert` is a pointer to the PEM encoded client certificate, and `client_cert_len` is its length. An empty buffer means no client authentication.
+/// `client_key` is a pointer to the PEM encoded client key, and `client_key_len` is its length. Must be provided together with `client_cert`.
+/// `server_name` is a pointer to C string representation of the server name used for SNI and verification. An empty string means the node's host is used.
+/// `reconnect` is a boolean flag to indicate if the existing connections should be replaced, instead of only using the new credentials for new connections.
+///
+/// # Safety
+///
+/// * `client_adapter_ptr` must be obtained from the `ConnectionResponse` returned from [`create_client`].
+/// * `client_adapter_ptr` must be valid until `close_client` is called.
+/// * `request_id` must be valid until it is passed in a call to [`free_command_response`].
+/// * `root_certs`, `client_cert` and `client_key` must each be null or point to a buffer of at least their given length.
+/// * `server_name` must point to a valid C string.
+#[unsafe(no_mangle)]
+#[allow(clippy::too_many_arguments)]
+pub unsafe extern "C-unwind" fn update_tls_credentials(
+    client_adapter_ptr: *const c_void,
+    request_id: usize,
+    root_certs: *const u8,
+    root_certs_len: usize,
+    client_cert: *const u8,
+    client_cert_len: usize,
+    client_key: *const u8,
+    client_key_len: usize,
+    server_name: *const c_char,
+    reconnect: bool,
+) -> *mut CommandResult {
+    let client_adapter = unsafe {
+        // we increment the strong count to ensure that the client is not dropped just because we turned it into an Arc.
+        Arc::increment_strong_count(client_adapter_ptr);
+        Arc::from_raw(client_adapter_ptr as *mut ClientAdapter)
+    };
+
+    // argument conversion to be used in the async block
+    let to_vec_option = |ptr: *const u8, len: usize| {
+        if ptr.is_null() || len == 0 {
+            None
+        } else {
+            Some(unsafe { from_raw_parts(ptr, len) }.to_vec())
+        }
+    };
+    let server_name = match unsafe { CStr::from_ptr(server_name).to_str() } {
+        Ok(server_name) => server_name,
+        Err(e) => {
+            return unsafe { client_adapter.handle_redis_error(RedisError::from(e), request_id) };
+        }
+    };
+    let tls_config = TlsConfig {
+        root_certs: to_vec_option(root_certs, root_certs_len),
+        client_cert: to_vec_option(client_cert, client_cert_len),
+        client_key: to_vec_option(client_key, client_key_len),
+        server_name: if server_name.is_empty() {
+            None
+        } else {
+            Some(server_name.to_string())
+        },
+    };
+    let mut client = client_adapter.core.client.clone();
+    client_adapter.execute_request(request_id, None, async move {
+        client.update_tls_credentials(tls_config, reconnect).await
+    })
+}
+
 /// Executes a Lua script.
 ///
 /// # Parameters
@@ -1572,6 +1769,8 @@ pub unsafe extern "C-unwind" fn update_connection_password(
 /// * `args_len`: Array of lengths for each argument.
 /// * `route_bytes`: Optional array of bytes for routing information.
 /// * `route_bytes_len`: Length of the route_bytes array.
+/// * `has_timeout`: Whether `timeout` is set.
+/// * `timeout`: Overrides the client's request timeout for the invocation, in milliseconds.
 ///
 /// # Safety
 ///
@@ -1604,6 +1803,8 @@ pub unsafe extern "C-unwind" fn invoke_script(
     args_len: *const c_ulong,
     route_bytes: *const u8,
     route_bytes_len: usize,
+    has_timeout: bool,
+    timeout: u32,
 ) -> *mut CommandResult {
     let client_adapter = unsafe {
         // we increment the strong count to ensure that the client is not dropped just because we turned it into an Arc.
@@ -1652,11 +1853,20 @@ pub unsafe extern "C-unwind" fn invoke_script(
     };
 
     let mut client = client_adapter.core.client.clone();
-    client_adapter.execute_request(request_id, async move {
-        let routing_info = get_route(route, None)?;
-        client
-            .invoke_script(hash_str, &keys_vec, &args_vec, routing_info)
-            .await
+    let kind = RequestKind::Command(RequestType::EvalSha);
+    client_adapter.execute_request(request_id, Some(kind), async move {
+        let routing_info = get_route(route, None, &client)?;
+        record_request(
+            kind,
+            client.invoke_script(
+                hash_str,
+                &keys_vec,
+                &args_vec,
+                routing_info,
+                has_timeout.then_some(timeout),
+            ),
+        )
+        .await
     })
 }
 
@@ -1780,21 +1990,30 @@ pub unsafe extern "C" fn batch(
     let child_span = create_child_span(pipeline.span().as_ref(), "send_batch");
     let (routing, timeout, pipeline_retry_strategy) = unsafe { get_pipeline_options(options_ptr) };
 
-    let result = client_adapter.execute_request(callback_index, async move {
+    let kind = if pipeline.is_atomic() {
+        RequestKind::Transaction
+    } else {
+        RequestKind::Pipeline
+    };
+    let result = client_adapter.execute_request(callback_index, Some(kind), async move {
         if pipeline.is_atomic() {
-            client
-                .send_transaction(&pipeline, routing, timeout, raise_on_error)
-                .await
+            record_request(
+                kind,
+                client.send_transaction(&pipeline, routing, timeout, raise_on_error),
+            )
+            .await
         } else {
-            client
-                .send_pipeline(
+            record_request(
+                kind,
+                client.send_pipeline(
                     &pipeline,
                     routing,
                     raise_on_error,
                     timeout,
                     pipeline_retry_strategy,
-                )
-                .await
+                ),
+            )
+            .await
         }
     });
 
@@ -2149,6 +2368,31 @@ pub unsafe extern "C" fn init_open_telemetry(
     }
 }
 
+/// Returns a JSON object with the statistics of the requests of this process, per request kind and per node.
+///
+/// The object holds `commands`, with the calls, errors by type, and latency percentiles in microseconds of each request kind,
+/// e.g. `Get` or `Transaction`, and `nodes`, with the requests, MOVED and ASK redirects, reconnects and timeouts of each node,
+/// by its address. The statistics are only collected after [`set_request_statistics_enabled`] enabled them.
+/// The returned string must be freed with [`free_c_string`].
+#[unsafe(no_mangle)]
+pub extern "C" fn get_request_statistics() -> *mut c_char {
+    CString::new(request_statistics::request_statistics().to_json())
+        .unwrap_or_else(|_| CString::new("Couldn't convert the statistics to C string").unwrap())
+        .into_raw()
+}
+
+/// Enables or disables the collection of the statistics of the requests of this process. They aren't collected by default.
+#[unsafe(no_mangle)]
+pub extern "C" fn set_request_statistics_enabled(enabled: bool) {
+    request_statistics::set_request_statistics_enabled(enabled);
+}
+
+/// Resets the statistics of the requests of this process.
+#[unsafe(no_mangle)]
+pub extern "C" fn reset_request_statistics() {
+    request_statistics::reset_request_statistics();
+}
+
 /// Frees a C string.
 ///
 /// # Safety
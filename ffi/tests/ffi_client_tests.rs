@@ -1,5 +1,6 @@
 use glide_core::connection_request::{ConnectionRequest, NodeAddress, TlsMode};
 use glide_core::errors::RequestErrorType;
+use glide_core::request_statistics;
 use glide_core::request_type::RequestType;
 use glide_ffi::*;
 use lazy_static::lazy_static;
@@ -16,10 +17,12 @@ use std::sync::{
 use tokio::runtime::Runtime;
 use tokio::time::{Duration, sleep};
 
+type AsyncResult = Result<String, (String, RequestErrorType)>;
+
 pub(crate) struct AsyncMetrics {
     pub success_count: AtomicUsize,
     pub failure_count: AtomicUsize,
-    pub results: HashMap<usize, Result<String, (String, RequestErrorType)>>,
+    pub results: HashMap<usize, AsyncResult>,
 }
 
 lazy_static! {
@@ -28,6 +31,9 @@ lazy_static! {
         failure_count: AtomicUsize::new(0),
         results: HashMap::new(),
     }));
+    // Kept apart from `ASYNC_METRICS`, whose counts are asserted by a test that may run concurrently.
+    static ref CANCELLED_REQUEST_RESULTS: RwLock<HashMap<usize, AsyncResult>> =
+        RwLock::new(HashMap::new());
 }
 
 const ASYNC_WRITE_LOCK_ERR: &str = "Failed to aquire ASYNC_METRICS the write lock";
@@ -55,6 +61,29 @@ extern "C-unwind" fn failure_callback(
     metrics.failure_count.fetch_add(1, Ordering::SeqCst);
 }
 
+/// Success callback function for the async client of the request cancellation test
+extern "C-unwind" fn cancelled_request_success_callback(
+    index: usize,
+    _response_ptr: *const CommandResponse,
+) {
+    CANCELLED_REQUEST_RESULTS
+        .write()
+        .expect(ASYNC_WRITE_LOCK_ERR)
+        .insert(index, Ok(String::new()));
+}
+
+/// Failure callback function for the async client of the request cancellation test
+extern "C-unwind" fn cancelled_request_failure_callback(
+    index: usize,
+    err_msg_ptr: *const c_char,
+    error_type: RequestErrorType,
+) {
+    CANCELLED_REQUEST_RESULTS
+        .write()
+        .expect(ASYNC_WRITE_LOCK_ERR)
+        .insert(index, Err((parse_error_msg(err_msg_ptr), error_type)));
+}
+
 fn parse_string_res(response_ptr: *const CommandResponse) -> String {
     assert!(!response_ptr.is_null());
     let response: &CommandResponse = unsafe { &*response_ptr };
@@ -174,6 +203,8 @@ fn execute_command(
             route_bytes,
             route_len,
             0,
+            false,
+            0,
         )
     };
     if command_res_ptr.is_null() {
@@ -224,20 +255,12 @@ fn get_async_error(index: usize) -> (String, RequestErrorType) {
     (err_msg.to_string(), err_type.clone())
 }
 
-#[rstest]
-fn test_ffi_client_command_execution(#[values(false, true)] async_client: bool) {
-    let server = Server::new();
-    let connection_request_bytes = create_connection_request(server.port);
+/// Creates a client connected to the server on `port`, and returns its connection response.
+unsafe fn create_test_client(port: u16, client_type: ClientType) -> *const ConnectionResponse {
+    let connection_request_bytes = create_connection_request(port);
     let connection_request_len = connection_request_bytes.len();
     let connection_request_ptr = connection_request_bytes.as_ptr();
-    let client_type = Box::into_raw(Box::new(if async_client {
-        ClientType::AsyncClient {
-            success_callback: string_success_callback,
-            failure_callback,
-        }
-    } else {
-        ClientType::SyncClient
-    }));
+    let client_type = Box::into_raw(Box::new(client_type));
     unsafe {
         let response_ptr = create_client(
             connection_request_ptr,
@@ -264,8 +287,24 @@ fn test_ffi_client_command_execution(#[values(false, true)] async_client: bool)
             !response.conn_ptr.is_null() && response.connection_error_message.is_null(),
             "Connection response should be valid"
         );
+        response_ptr
+    }
+}
 
-        let client_ptr = response.conn_ptr;
+#[rstest]
+fn test_ffi_client_command_execution(#[values(false, true)] async_client: bool) {
+    let server = Server::new();
+    let client_type = if async_client {
+        ClientType::AsyncClient {
+            success_callback: string_success_callback,
+            failure_callback,
+        }
+    } else {
+        ClientType::SyncClient
+    };
+    unsafe {
+        let response_ptr = create_test_client(server.port, client_type);
+        let client_ptr = (*response_ptr).conn_ptr;
         // Good command: PING IS_WORKING
         let good_cmd_idx = 0;
         let ping_value = b"IS_WORKING";
@@ -310,3 +349,109 @@ fn test_ffi_client_command_execution(#[values(false, true)] async_client: bool)
         close_client(client_ptr);
     }
 }
+
+#[test]
+fn test_ffi_client_update_tls_credentials_without_tls_is_an_error() {
+    let server = Server::new();
+    unsafe {
+        let response_ptr = create_test_client(server.port, ClientType::SyncClient);
+        let client_ptr = (*response_ptr).conn_ptr;
+
+        let result_ptr = update_tls_credentials(
+            client_ptr,
+            0,
+            std::ptr::null(),
+            0,
+            std::ptr::null(),
+            0,
+            std::ptr::null(),
+            0,
+            c"".as_ptr(),
+            false,
+        );
+        assert!(!result_ptr.is_null());
+        let result = Box::from_raw(result_ptr);
+        assert!(result.response.is_null());
+        let (err_msg, err_type) = get_sync_error(result.command_error);
+        assert!(err_msg.contains("doesn't use TLS"), "{err_msg}");
+        assert_eq!(err_type, RequestErrorType::Unspecified);
+        free_connection_response(response_ptr as *mut ConnectionResponse);
+        close_client(client_ptr);
+    }
+}
+
+#[test]
+fn test_ffi_client_cancel_request() {
+    let server = Server::new();
+    let client_type = ClientType::AsyncClient {
+        success_callback: cancelled_request_success_callback,
+        failure_callback: cancelled_request_failure_callback,
+    };
+    unsafe {
+        let response_ptr = create_test_client(server.port, client_type);
+        let client_ptr = (*response_ptr).conn_ptr;
+
+        request_statistics::reset_request_statistics();
+        request_statistics::set_request_statistics_enabled(true);
+
+        // BLPOP on an empty list with no timeout blocks until the request is cancelled.
+        let request_idx = 0;
+        let args: [&[u8]; 2] = [b"cancelled-list", b"0"];
+        let args_ptrs = args.map(|arg| arg.as_ptr() as usize);
+        let args_lens = args.map(|arg| arg.len() as c_ulong);
+        let command_res_ptr = command(
+            client_ptr,
+            request_idx,
+            RequestType::BLPop,
+            args.len() as c_ulong,
+            args_ptrs.as_ptr(),
+            args_lens.as_ptr(),
+            std::ptr::null(),
+            0,
+            0,
+            false,
+            0,
+        );
+        assert!(command_res_ptr.is_null()); // result should be returned through callback
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(
+            CANCELLED_REQUEST_RESULTS
+                .read()
+                .expect(ASYNC_READ_LOCK_ERR)
+                .get(&request_idx)
+                .is_none()
+        );
+
+        assert!(cancel_request(client_ptr, request_idx));
+        let (err_msg, err_type) = CANCELLED_REQUEST_RESULTS
+            .read()
+            .expect(ASYNC_READ_LOCK_ERR)
+            .get(&request_idx)
+            .expect("Couldn't find the relevant idx in the map")
+            .clone()
+            .unwrap_err();
+        assert_eq!(err_msg, "The request was cancelled");
+        assert_eq!(err_type, RequestErrorType::Cancelled);
+        request_statistics::set_request_statistics_enabled(false);
+        let blpop = &request_statistics::request_statistics().commands["BLPop"];
+        assert_eq!(blpop.calls, 1);
+        assert_eq!(blpop.errors.get("Cancelled"), Some(&1));
+
+        // The aborted request is never answered again, and can't be cancelled twice.
+        CANCELLED_REQUEST_RESULTS
+            .write()
+            .expect(ASYNC_WRITE_LOCK_ERR)
+            .remove(&request_idx);
+        assert!(!cancel_request(client_ptr, request_idx));
+        std::thread::sleep(Duration::from_millis(100));
+        assert!(
+            CANCELLED_REQUEST_RESULTS
+                .read()
+                .expect(ASYNC_READ_LOCK_ERR)
+                .get(&request_idx)
+                .is_none()
+        );
+        free_connection_response(response_ptr as *mut ConnectionResponse);
+        close_client(client_ptr);
+    }
+}
@@ -3,8 +3,8 @@
 use criterion::{Criterion, criterion_group, criterion_main};
 use futures::future::join_all;
 use redis::{
-    AsyncCommands, ConnectionAddr, ConnectionInfo, GlideConnectionOptions, RedisConnectionInfo,
-    RedisResult, Value,
+    AsyncCommands, ConnectionAddr, ConnectionInfo, GlideConnectionOptions, ProtocolVersion,
+    PubSubSubscriptionInfo, PubSubSubscriptionKind, RedisConnectionInfo, RedisResult, Value,
     aio::{ConnectionLike, ConnectionManager, MultiplexedConnection},
     cluster::ClusterClientBuilder,
     cluster_async::ClusterConnection,
@@ -131,6 +131,71 @@ fn cluster_connection_benchmark(
     });
 }
 
+// A connection configuration whose handshake includes every optional step.
+fn handshake_connection_info(address: ConnectionAddr) -> ConnectionInfo {
+    let mut pubsub_subscriptions = PubSubSubscriptionInfo::new();
+    pubsub_subscriptions.insert(
+        PubSubSubscriptionKind::Exact,
+        (0..10)
+            .map(|index| format!("handshake-benchmark-{index}").into_bytes())
+            .collect(),
+    );
+    ConnectionInfo {
+        addr: address,
+        redis: RedisConnectionInfo {
+            db: 1,
+            protocol: ProtocolVersion::RESP3,
+            client_name: Some("handshake-benchmark".to_string()),
+            pubsub_subscriptions: Some(pubsub_subscriptions),
+            ..Default::default()
+        },
+    }
+}
+
+fn handshake_benchmark(c: &mut Criterion, address: ConnectionAddr, group: &str) {
+    let runtime = Builder::new_current_thread().enable_all().build().unwrap();
+    let client = redis::Client::open(handshake_connection_info(address)).unwrap();
+    let connection_options = GlideConnectionOptions {
+        discover_az: true,
+        ..Default::default()
+    };
+    let mut group = c.benchmark_group(group);
+    group.significance_level(0.1).sample_size(100);
+    group.bench_function("multiplexer-connection establishment", |b| {
+        b.to_async(&runtime).iter(|| async {
+            client
+                .get_multiplexed_tokio_connection(connection_options.clone())
+                .await
+                .unwrap()
+        });
+    });
+
+    // Compares the handshake when each of its commands is sent in its own round trip, and when all are pipelined.
+    // The handshake is repeated over an established connection, so only its round trips are measured.
+    let connection_info = client.get_connection_info().redis.clone();
+    let connection = runtime
+        .block_on(client.get_multiplexed_tokio_connection(GlideConnectionOptions::default()))
+        .unwrap();
+    for (sequential, name) in [
+        (true, "multiplexer-sequential handshake"),
+        (false, "multiplexer-pipelined handshake"),
+    ] {
+        group.bench_function(name, |b| {
+            b.to_async(&runtime).iter(|| async {
+                let mut connection = connection.clone();
+                redis::testing::setup_connection(
+                    &connection_info,
+                    &mut connection,
+                    true,
+                    sequential,
+                )
+                .await
+                .unwrap()
+            });
+        });
+    }
+}
+
 fn local_benchmark<F: FnOnce(&mut Criterion, ConnectionAddr, &str)>(c: &mut Criterion, f: F) {
     f(
         c,
@@ -166,6 +231,11 @@ fn connection_manager_benchmarks(c: &mut Criterion) {
     local_benchmark(c, connection_manager_benchmark)
 }
 
+fn handshake_benchmarks(c: &mut Criterion) {
+    remote_benchmark(c, handshake_benchmark);
+    local_benchmark(c, handshake_benchmark)
+}
+
 fn cluster_connection_benchmarks(c: &mut Criterion) {
     let Ok(address) = env::var("CLUSTER_HOST").map(|host| ConnectionAddr::TcpTls {
         host,
@@ -183,6 +253,7 @@ criterion_group!(
     benches,
     connection_manager_benchmarks,
     multiplexer_benchmarks,
+    handshake_benchmarks,
     cluster_connection_benchmarks
 );
 
@@ -66,7 +66,7 @@ where
             pubsub: false,
             protocol: connection_info.protocol,
         };
-        setup_connection(connection_info, &mut rv, false).await?;
+        setup_connection(connection_info, &mut rv, false, None).await?;
         Ok(rv)
     }
 
@@ -268,6 +268,14 @@ where
         // always false for AsyncRead + AsyncWrite (cant do better)
         false
     }
+
+    fn get_protocol(&self) -> ProtocolVersion {
+        self.protocol
+    }
+
+    fn set_protocol(&mut self, protocol: ProtocolVersion) {
+        self.protocol = protocol;
+    }
 }
 
 /// Represents a `PubSub` connection.
@@ -1,14 +1,15 @@
 //! Adds async IO support to redis.
 use crate::cmd::{cmd, Cmd};
 use crate::connection::{
-    get_resp3_hello_command_error, PubSubSubscriptionKind, RedisConnectionInfo,
+    get_resp3_hello_command_error, is_resp3_unsupported, PubSubSubscriptionKind,
+    RedisConnectionInfo,
 };
 use crate::pipeline::PipelineRetryStrategy;
 use crate::types::{
     ErrorKind, FromRedisValue, InfoDict, ProtocolVersion, RedisError, RedisFuture, RedisResult,
     Value,
 };
-use crate::PushKind;
+use crate::{ClientTrackingMode, PushKind};
 use ::tokio::io::{AsyncRead, AsyncWrite};
 use async_trait::async_trait;
 use futures_util::Future;
@@ -85,6 +86,12 @@ pub trait ConnectionLike {
     /// Returns the state of the connection
     fn is_closed(&self) -> bool;
 
+    /// Returns the number of requests that were sent over the connection and are still waiting for a response.
+    /// Used to pick the least loaded connection, when several connections lead to the same node.
+    fn pending_requests(&self) -> usize {
+        0
+    }
+
     /// Get the connection availibility zone
     fn get_az(&self) -> Option<String> {
         None
@@ -92,6 +99,15 @@ pub trait ConnectionLike {
 
     /// Set the connection availibility zone
     fn set_az(&mut self, _az: Option<String>) {}
+
+    /// Returns the protocol that the connection uses.
+    /// If the client was configured with [`ProtocolVersion::Auto`], this is the protocol that was negotiated with the server.
+    fn get_protocol(&self) -> ProtocolVersion {
+        ProtocolVersion::default()
+    }
+
+    /// Set the protocol that was negotiated with the server during the connection's handshake.
+    fn set_protocol(&mut self, _protocol: ProtocolVersion) {}
 }
 
 /// Implements ability to notify about disconnection events
@@ -113,201 +129,383 @@ impl Clone for Box<dyn DisconnectNotifier> {
     }
 }
 
-// Helper function to extract and update availability zone from INFO command
-async fn update_az_from_info<C>(con: &mut C) -> RedisResult<()>
-where
-    C: ConnectionLike,
-{
-    let info_res = con.req_packed_command(&cmd("INFO")).await;
+/// Provides the credentials that new connections authenticate with, for credentials that expire and are rotated.
+#[async_trait]
+pub trait ConnectionCredentialsProvider: Send + Sync {
+    /// Returns the username, if it should replace the configured one, and the password to authenticate with.
+    /// Called before every connection attempt.
+    async fn connection_credentials(&self) -> RedisResult<(Option<String>, String)>;
+}
 
-    match info_res {
-        Ok(value) => {
-            let info_dict: InfoDict = FromRedisValue::from_redis_value(&value)?;
-            if let Some(node_az) = info_dict.get::<String>("availability_zone") {
-                con.set_az(Some(node_az));
-            }
-            Ok(())
-        }
-        Err(e) => {
-            // Handle the error case for the INFO command
-            Err(RedisError::from((
-                ErrorKind::ResponseError,
-                "Failed to execute INFO command. ",
-                format!("{e:?}"),
-            )))
-        }
-    }
+// A command of the connection's handshake. Used to attribute each reply of the pipelined handshake to the command that produced it.
+#[derive(Debug, Clone, PartialEq)]
+enum HandshakeStep {
+    Hello,
+    Auth,
+    Select,
+    SetName,
+    Tracking,
+    Info,
+    SetInfo,
+    Subscribe(PubSubSubscriptionKind, Vec<u8>),
 }
 
-// Initial setup for every connection.
-async fn setup_connection<C>(
+// Returns the handshake's commands, in the order in which they should be sent.
+// If the configuration is invalid, the commands before the invalid step are returned alongside the error,
+// so errors that the server returns for these commands are still reported first.
+fn handshake_steps(
     connection_info: &RedisConnectionInfo,
-    con: &mut C,
-    // This parameter is set to 'true' if ReadFromReplica strategy is set to AZAffinity or AZAffinityReplicasAndPrimary.
-    // An INFO command will be triggered in the connection's setup to update the 'availability_zone' property.
+    protocol: ProtocolVersion,
     discover_az: bool,
-) -> RedisResult<()>
-where
-    C: ConnectionLike,
-{
-    if connection_info.protocol != ProtocolVersion::RESP2 {
-        let hello_cmd = resp3_hello(connection_info);
-        let val: RedisResult<Value> = hello_cmd.query_async(con).await;
-        if let Err(err) = val {
-            return Err(get_resp3_hello_command_error(err));
-        }
+    client_tracking: Option<ClientTrackingMode>,
+) -> (Vec<(HandshakeStep, Cmd)>, Option<RedisError>) {
+    let mut steps = Vec::new();
+    if protocol != ProtocolVersion::RESP2 {
+        steps.push((HandshakeStep::Hello, resp3_hello(connection_info)));
     } else if let Some(password) = &connection_info.password {
         let mut command = cmd("AUTH");
         if let Some(username) = &connection_info.username {
             command.arg(username);
         }
-        match command.arg(password).query_async(con).await {
-            Ok(Value::Okay) => (),
-            Err(e) => {
-                let err_msg = e.detail().ok_or((
-                    ErrorKind::AuthenticationFailed,
-                    "Password authentication failed",
-                ))?;
-
-                if !err_msg.contains("wrong number of arguments for 'auth' command") {
-                    fail!((
-                        ErrorKind::AuthenticationFailed,
-                        "Password authentication failed",
-                    ));
-                }
-
-                let mut command = cmd("AUTH");
-                match command.arg(password).query_async(con).await {
-                    Ok(Value::Okay) => (),
-                    _ => {
-                        fail!((
-                            ErrorKind::AuthenticationFailed,
-                            "Password authentication failed"
-                        ));
-                    }
-                }
-            }
-            _ => {
-                fail!((
-                    ErrorKind::AuthenticationFailed,
-                    "Password authentication failed"
-                ));
-            }
-        }
+        command.arg(password);
+        steps.push((HandshakeStep::Auth, command));
     }
 
     if connection_info.db != 0 {
-        match cmd("SELECT").arg(connection_info.db).query_async(con).await {
-            Ok(Value::Okay) => (),
-            _ => fail!((
-                ErrorKind::ResponseError,
-                "Redis server refused to switch database"
-            )),
-        }
+        let mut command = cmd("SELECT");
+        command.arg(connection_info.db);
+        steps.push((HandshakeStep::Select, command));
     }
 
     if let Some(client_name) = &connection_info.client_name {
-        match cmd("CLIENT")
-            .arg("SETNAME")
-            .arg(client_name)
-            .query_async(con)
-            .await
-        {
-            Ok(Value::Okay) => {}
-            _ => fail!((
-                ErrorKind::ResponseError,
-                "Redis server refused to set client name"
-            )),
+        let mut command = cmd("CLIENT");
+        command.arg("SETNAME").arg(client_name);
+        steps.push((HandshakeStep::SetName, command));
+    }
+
+    if let Some(tracking_mode) = client_tracking {
+        if protocol == ProtocolVersion::RESP2 {
+            return (
+                steps,
+                Some(RedisError::from((
+                    ErrorKind::InvalidClientConfig,
+                    "Client tracking requires the RESP3 protocol",
+                ))),
+            );
         }
+        let mut command = cmd("CLIENT");
+        command.arg("TRACKING").arg("ON");
+        if tracking_mode == ClientTrackingMode::Broadcast {
+            command.arg("BCAST");
+        }
+        steps.push((HandshakeStep::Tracking, command));
     }
 
     if discover_az {
-        update_az_from_info(con).await?;
+        steps.push((HandshakeStep::Info, cmd("INFO")));
     }
 
-    // result is ignored, as per the command's instructions.
-    // https://redis.io/commands/client-setinfo/
-    let _: RedisResult<()> = crate::connection::client_set_info_pipeline()
-        .query_async(con)
-        .await;
+    for command in crate::connection::client_set_info_pipeline().cmd_iter() {
+        steps.push((HandshakeStep::SetInfo, Cmd::clone(command)));
+    }
 
     // resubscribe
-    if connection_info.protocol != ProtocolVersion::RESP3 {
-        return Ok(());
+    let has_subscriptions = connection_info
+        .pubsub_subscriptions
+        .iter()
+        .flatten()
+        .any(|(_, channels_patterns)| !channels_patterns.is_empty());
+    if protocol == ProtocolVersion::RESP2 && has_subscriptions {
+        return (
+            steps,
+            Some(RedisError::from((
+                ErrorKind::InvalidClientConfig,
+                "Subscriptions require the RESP3 protocol",
+            ))),
+        );
     }
-    static KIND_TO_COMMAND: [(PubSubSubscriptionKind, &str); 3] = [
-        (PubSubSubscriptionKind::Exact, "SUBSCRIBE"),
-        (PubSubSubscriptionKind::Pattern, "PSUBSCRIBE"),
-        (PubSubSubscriptionKind::Sharded, "SSUBSCRIBE"),
-    ];
-
-    if connection_info.pubsub_subscriptions.is_none() {
-        return Ok(());
+    if protocol != ProtocolVersion::RESP2 {
+        static KIND_TO_COMMAND: [(PubSubSubscriptionKind, &str); 3] = [
+            (PubSubSubscriptionKind::Exact, "SUBSCRIBE"),
+            (PubSubSubscriptionKind::Pattern, "PSUBSCRIBE"),
+            (PubSubSubscriptionKind::Sharded, "SSUBSCRIBE"),
+        ];
+        for (subscription_kind, channels_patterns) in
+            connection_info.pubsub_subscriptions.iter().flatten()
+        {
+            for channel_pattern in channels_patterns.iter() {
+                let mut subscribe_command =
+                    cmd(KIND_TO_COMMAND[Into::<usize>::into(*subscription_kind)].1);
+                subscribe_command.arg(channel_pattern);
+                steps.push((
+                    HandshakeStep::Subscribe(*subscription_kind, channel_pattern.clone()),
+                    subscribe_command,
+                ));
+            }
+        }
     }
 
-    for (subscription_kind, channels_patterns) in
-        connection_info.pubsub_subscriptions.as_ref().unwrap()
-    {
-        for channel_pattern in channels_patterns.iter() {
-            let mut subscribe_command =
-                cmd(KIND_TO_COMMAND[Into::<usize>::into(*subscription_kind)].1);
-            subscribe_command.arg(channel_pattern);
-
-            // This is a quite intricate code - Per RESP3, subscriptions commands do not return anything.
-            // Instead, push messages will be pushed for each channel. Thus, this is not a typycal request-response pattern.
-            // The act of pushing is asyncronous with the regard to the subscription command, and might be delayed for some time after the server state was already updated.
-            // (i.e. the behaviour is implementation defined).
-            // We will assume the configured time out is enough for the server to push the notifications.
-            match subscribe_command.query_async(con).await {
-                Ok(Value::Push { kind, data }) => {
-                    match *subscription_kind {
-                        PubSubSubscriptionKind::Exact => {
-                            if kind != PushKind::Subscribe
-                                || Value::BulkString(channel_pattern.clone()) != data[0]
-                            {
-                                fail!((
-                                    ErrorKind::ResponseError,
-                                    // TODO: Consider printing the exact command
-                                    "Failed to restore Exact subscription channels"
-                                ));
-                            }
-                        }
-                        PubSubSubscriptionKind::Pattern => {
-                            if kind != PushKind::PSubscribe
-                                || Value::BulkString(channel_pattern.clone()) != data[0]
-                            {
-                                fail!((
-                                    ErrorKind::ResponseError,
-                                    // TODO: Consider printing the exact command
-                                    "Failed to restore Pattern subscription channels"
-                                ));
-                            }
-                        }
-                        PubSubSubscriptionKind::Sharded => {
-                            if kind != PushKind::SSubscribe
-                                || Value::BulkString(channel_pattern.clone()) != data[0]
-                            {
-                                fail!((
-                                    ErrorKind::ResponseError,
-                                    // TODO: Consider printing the exact command
-                                    "Failed to restore Sharded subscription channels"
-                                ));
-                            }
-                        }
-                    }
+    (steps, None)
+}
+
+fn authentication_failed() -> RedisError {
+    RedisError::from((
+        ErrorKind::AuthenticationFailed,
+        "Password authentication failed",
+    ))
+}
+
+// Returns true if HELLO failed because the server doesn't support RESP3.
+fn is_hello_rejected(reply: &Value) -> bool {
+    matches!(reply, Value::ServerError(err) if is_resp3_unsupported(Some(err.err_code()), err.details()))
+}
+
+// Returns true if AUTH failed because the server doesn't accept a username, i.e. servers older than Redis 6.
+fn is_auth_without_username_required(reply: &Value) -> bool {
+    matches!(reply, Value::ServerError(err) if err
+        .details()
+        .is_some_and(|detail| detail.contains("wrong number of arguments for 'auth' command")))
+}
+
+// Checks the reply of a single handshake step, and applies its result to the connection.
+fn check_handshake_reply<C>(con: &mut C, step: &HandshakeStep, reply: Value) -> RedisResult<()>
+where
+    C: ConnectionLike,
+{
+    match step {
+        HandshakeStep::Hello => {
+            reply
+                .extract_error()
+                .map_err(get_resp3_hello_command_error)?;
+        }
+        HandshakeStep::Auth => {
+            if reply != Value::Okay {
+                return Err(authentication_failed());
+            }
+        }
+        HandshakeStep::Select => {
+            if reply != Value::Okay {
+                fail!((
+                    ErrorKind::ResponseError,
+                    "Redis server refused to switch database"
+                ));
+            }
+        }
+        HandshakeStep::SetName => {
+            if reply != Value::Okay {
+                fail!((
+                    ErrorKind::ResponseError,
+                    "Redis server refused to set client name"
+                ));
+            }
+        }
+        HandshakeStep::Tracking => {
+            if reply != Value::Okay {
+                fail!((
+                    ErrorKind::ResponseError,
+                    "Redis server refused to enable client tracking"
+                ));
+            }
+        }
+        HandshakeStep::Info => match reply.extract_error() {
+            Ok(value) => {
+                let info_dict: InfoDict = FromRedisValue::from_redis_value(&value)?;
+                if let Some(node_az) = info_dict.get::<String>("availability_zone") {
+                    con.set_az(Some(node_az));
                 }
-                _ => {
-                    fail!((
-                        ErrorKind::ResponseError,
-                        // TODO: Consider printing the exact command
-                        "Failed to receive subscription notification while restoring subscription channels"
-                    ));
+            }
+            Err(e) => {
+                return Err(RedisError::from((
+                    ErrorKind::ResponseError,
+                    "Failed to execute INFO command. ",
+                    format!("{e:?}"),
+                )));
+            }
+        },
+        // result is ignored, as per the command's instructions.
+        // https://redis.io/commands/client-setinfo/
+        HandshakeStep::SetInfo => {}
+        // This is a quite intricate code - Per RESP3, subscriptions commands do not return anything.
+        // Instead, push messages will be pushed for each channel. Thus, this is not a typycal request-response pattern.
+        // The act of pushing is asyncronous with the regard to the subscription command, and might be delayed for some time after the server state was already updated.
+        // (i.e. the behaviour is implementation defined).
+        // We will assume the configured time out is enough for the server to push the notifications.
+        HandshakeStep::Subscribe(subscription_kind, channel_pattern) => {
+            let Value::Push { kind, data } = reply else {
+                fail!((
+                    ErrorKind::ResponseError,
+                    // TODO: Consider printing the exact command
+                    "Failed to receive subscription notification while restoring subscription channels"
+                ));
+            };
+            let (expected_kind, error_message) = match subscription_kind {
+                PubSubSubscriptionKind::Exact => (
+                    PushKind::Subscribe,
+                    "Failed to restore Exact subscription channels",
+                ),
+                PubSubSubscriptionKind::Pattern => (
+                    PushKind::PSubscribe,
+                    "Failed to restore Pattern subscription channels",
+                ),
+                PubSubSubscriptionKind::Sharded => (
+                    PushKind::SSubscribe,
+                    "Failed to restore Sharded subscription channels",
+                ),
+            };
+            if kind != expected_kind
+                || data.first() != Some(&Value::BulkString(channel_pattern.clone()))
+            {
+                // TODO: Consider printing the exact command
+                fail!((ErrorKind::ResponseError, error_message));
+            }
+        }
+    }
+    Ok(())
+}
+
+// How the commands of the connection handshake are sent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) enum HandshakeMode {
+    // All of the commands are sent in a single pipeline.
+    Pipelined,
+    // Each command is sent in its own round trip, as connections were set up before the handshake was pipelined.
+    // Only used to benchmark the pipelined handshake against.
+    #[cfg_attr(not(feature = "bench"), allow(dead_code))]
+    Sequential,
+}
+
+// Sends the given handshake commands, and returns their replies. Errors returned by the server are returned as replies.
+async fn send_handshake_commands<C>(
+    con: &mut C,
+    steps: &[(HandshakeStep, Cmd)],
+    mode: HandshakeMode,
+) -> RedisResult<Vec<Value>>
+where
+    C: ConnectionLike,
+{
+    match mode {
+        HandshakeMode::Pipelined => {
+            let mut pipeline = crate::pipe();
+            for (_, command) in steps {
+                pipeline.add_command(command.clone());
+            }
+            con.req_packed_commands(&pipeline, 0, steps.len(), None)
+                .await
+        }
+        HandshakeMode::Sequential => {
+            let mut replies = Vec::with_capacity(steps.len());
+            for (_, command) in steps {
+                let reply = match con.req_packed_command(command).await {
+                    Ok(reply) => reply,
+                    Err(err) if err.is_io_error() => return Err(err),
+                    Err(err) => Value::ServerError(err.into()),
+                };
+                replies.push(reply);
+            }
+            Ok(replies)
+        }
+    }
+}
+
+// Initial setup for every connection.
+// All of the handshake's commands are sent in a single pipeline, so the connection is ready after a single round trip.
+// The replies are then checked in order, so the first failing step is reported, as if the commands were sent one by one.
+// If the protocol is `Auto` and the server rejects HELLO, the handshake is sent again over RESP2.
+async fn setup_connection<C>(
+    connection_info: &RedisConnectionInfo,
+    con: &mut C,
+    // This parameter is set to 'true' if ReadFromReplica strategy is set to AZAffinity or AZAffinityReplicasAndPrimary.
+    // An INFO command will be triggered in the connection's setup to update the 'availability_zone' property.
+    discover_az: bool,
+    // If set, `CLIENT TRACKING` is enabled on the connection with the given mode. Requires RESP3.
+    client_tracking: Option<ClientTrackingMode>,
+) -> RedisResult<()>
+where
+    C: ConnectionLike,
+{
+    setup_connection_with_mode(
+        connection_info,
+        con,
+        discover_az,
+        client_tracking,
+        HandshakeMode::Pipelined,
+    )
+    .await
+}
+
+// Runs the connection handshake over `con`, sending its commands as set by `mode`.
+pub(crate) async fn setup_connection_with_mode<C>(
+    connection_info: &RedisConnectionInfo,
+    con: &mut C,
+    discover_az: bool,
+    client_tracking: Option<ClientTrackingMode>,
+    mode: HandshakeMode,
+) -> RedisResult<()>
+where
+    C: ConnectionLike,
+{
+    let mut protocol = connection_info.protocol;
+    let (mut steps, mut config_error) =
+        handshake_steps(connection_info, protocol, discover_az, client_tracking);
+
+    while !steps.is_empty() {
+        // Until the server accepted RESP3, the subscriptions are held back, since subscribing over RESP2
+        // would leave the connection in subscriber mode.
+        let batch_len = if protocol == ProtocolVersion::Auto {
+            steps
+                .iter()
+                .position(|(step, _)| matches!(step, HandshakeStep::Subscribe(..)))
+                .unwrap_or(steps.len())
+        } else {
+            steps.len()
+        };
+        let replies = send_handshake_commands(con, &steps[..batch_len], mode).await?;
+        if replies.len() != batch_len {
+            fail!((
+                ErrorKind::ResponseError,
+                "Received an unexpected number of replies to the connection handshake"
+            ));
+        }
+
+        let mut processed = batch_len;
+        for (index, reply) in replies.into_iter().enumerate() {
+            let step = &steps[index].0;
+            if *step == HandshakeStep::Hello
+                && protocol == ProtocolVersion::Auto
+                && is_hello_rejected(&reply)
+            {
+                // The server doesn't support RESP3, so the handshake restarts over RESP2.
+                // The replies to the following commands are discarded, since they might have been rejected for lack of authentication.
+                protocol = ProtocolVersion::RESP2;
+                (steps, config_error) =
+                    handshake_steps(connection_info, protocol, discover_az, client_tracking);
+                processed = 0;
+                break;
+            }
+            if *step == HandshakeStep::Auth && is_auth_without_username_required(&reply) {
+                // The server doesn't support usernames, so authenticate with the password only.
+                // The following commands were rejected since the connection wasn't authenticated, so they're sent again.
+                let mut command = cmd("AUTH");
+                command.arg(connection_info.password.as_ref());
+                match command.query_async(con).await {
+                    Ok(Value::Okay) => processed = index + 1,
+                    _ => return Err(authentication_failed()),
                 }
+                break;
+            }
+            check_handshake_reply(con, step, reply)?;
+            if *step == HandshakeStep::Hello {
+                protocol = ProtocolVersion::RESP3;
             }
         }
+        steps.drain(..processed);
     }
+    con.set_protocol(protocol);
 
-    Ok(())
+    match config_error {
+        Some(err) => Err(err),
+        None => Ok(()),
+    }
 }
 
 mod connection;
@@ -322,3 +520,324 @@ pub use connection_manager::*;
 mod runtime;
 use crate::commands::resp3_hello;
 pub(super) use runtime::*;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{ServerError, ServerErrorKind};
+    use std::collections::{HashSet, VecDeque};
+    // The crate, rather than the `aio::tokio` module.
+    use ::tokio;
+
+    // Answers each handshake pipeline with the next scripted batch of replies, and records the size of the pipelines.
+    struct ScriptedConnection {
+        replies: VecDeque<Vec<Value>>,
+        sent_batches: Vec<usize>,
+        protocol: ProtocolVersion,
+    }
+
+    impl ScriptedConnection {
+        fn new(replies: Vec<Vec<Value>>) -> Self {
+            Self {
+                replies: replies.into(),
+                sent_batches: Vec::new(),
+                protocol: ProtocolVersion::default(),
+            }
+        }
+    }
+
+    impl ConnectionLike for ScriptedConnection {
+        fn req_packed_command<'a>(&'a mut self, _cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+            self.sent_batches.push(1);
+            let mut replies = self.replies.pop_front().expect("unexpected command");
+            assert_eq!(replies.len(), 1);
+            let reply = replies.remove(0);
+            Box::pin(async move { reply.extract_error() })
+        }
+
+        fn req_packed_commands<'a>(
+            &'a mut self,
+            _cmd: &'a crate::Pipeline,
+            _offset: usize,
+            count: usize,
+            _pipeline_retry_strategy: Option<PipelineRetryStrategy>,
+        ) -> RedisFuture<'a, Vec<Value>> {
+            self.sent_batches.push(count);
+            let replies = self.replies.pop_front().expect("unexpected pipeline");
+            Box::pin(async move { Ok(replies) })
+        }
+
+        fn get_db(&self) -> i64 {
+            0
+        }
+
+        fn is_closed(&self) -> bool {
+            false
+        }
+
+        fn get_protocol(&self) -> ProtocolVersion {
+            self.protocol
+        }
+
+        fn set_protocol(&mut self, protocol: ProtocolVersion) {
+            self.protocol = protocol;
+        }
+    }
+
+    fn server_error(detail: &str) -> Value {
+        Value::ServerError(ServerError::KnownError {
+            kind: ServerErrorKind::ResponseError,
+            detail: Some(detail.to_string()),
+        })
+    }
+
+    fn step_names(steps: &[(HandshakeStep, Cmd)]) -> Vec<HandshakeStep> {
+        steps.iter().map(|(step, _)| step.clone()).collect()
+    }
+
+    #[test]
+    fn handshake_steps_are_ordered_like_the_setup_commands() {
+        let connection_info = RedisConnectionInfo {
+            db: 2,
+            protocol: ProtocolVersion::RESP3,
+            client_name: Some("name".to_string()),
+            pubsub_subscriptions: Some(
+                [(
+                    PubSubSubscriptionKind::Exact,
+                    HashSet::from([b"channel".to_vec()]),
+                )]
+                .into(),
+            ),
+            ..Default::default()
+        };
+
+        let (steps, config_error) = handshake_steps(
+            &connection_info,
+            connection_info.protocol,
+            true,
+            Some(ClientTrackingMode::Default),
+        );
+
+        assert!(config_error.is_none());
+        assert_eq!(
+            step_names(&steps),
+            vec![
+                HandshakeStep::Hello,
+                HandshakeStep::Select,
+                HandshakeStep::SetName,
+                HandshakeStep::Tracking,
+                HandshakeStep::Info,
+                HandshakeStep::SetInfo,
+                HandshakeStep::SetInfo,
+                HandshakeStep::Subscribe(PubSubSubscriptionKind::Exact, b"channel".to_vec()),
+            ]
+        );
+    }
+
+    #[test]
+    fn handshake_steps_authenticate_with_auth_over_resp2() {
+        let connection_info = RedisConnectionInfo {
+            protocol: ProtocolVersion::RESP2,
+            username: Some("user".to_string()),
+            password: Some("password".to_string()),
+            ..Default::default()
+        };
+
+        let (steps, config_error) =
+            handshake_steps(&connection_info, connection_info.protocol, false, None);
+
+        assert!(config_error.is_none());
+        assert_eq!(steps[0].0, HandshakeStep::Auth);
+        assert_eq!(
+            steps[0].1.get_packed_command(),
+            cmd("AUTH").arg("user").arg("password").get_packed_command()
+        );
+    }
+
+    #[test]
+    fn handshake_steps_stop_before_client_tracking_without_resp3() {
+        let connection_info = RedisConnectionInfo {
+            protocol: ProtocolVersion::RESP2,
+            client_name: Some("name".to_string()),
+            ..Default::default()
+        };
+
+        let (steps, config_error) = handshake_steps(
+            &connection_info,
+            connection_info.protocol,
+            true,
+            Some(ClientTrackingMode::Default),
+        );
+
+        assert_eq!(step_names(&steps), vec![HandshakeStep::SetName]);
+        assert_eq!(
+            config_error.map(|err| err.kind()),
+            Some(ErrorKind::InvalidClientConfig)
+        );
+    }
+
+    #[tokio::test]
+    async fn auto_protocol_falls_back_to_resp2_when_hello_is_rejected() {
+        let connection_info = RedisConnectionInfo {
+            protocol: ProtocolVersion::Auto,
+            password: Some("password".to_string()),
+            client_name: Some("name".to_string()),
+            ..Default::default()
+        };
+        let noauth = Value::ServerError(ServerError::ExtensionError {
+            code: "NOAUTH".to_string(),
+            detail: Some("Authentication required.".to_string()),
+        });
+        let mut con = ScriptedConnection::new(vec![
+            // HELLO, CLIENT SETNAME, 2 * CLIENT SETINFO
+            vec![
+                server_error("unknown command 'HELLO'"),
+                noauth.clone(),
+                noauth.clone(),
+                noauth,
+            ],
+            // AUTH, CLIENT SETNAME, 2 * CLIENT SETINFO
+            vec![Value::Okay, Value::Okay, Value::Okay, Value::Okay],
+        ]);
+
+        setup_connection(&connection_info, &mut con, false, None)
+            .await
+            .unwrap();
+
+        assert_eq!(con.sent_batches, vec![4, 4]);
+        assert_eq!(con.get_protocol(), ProtocolVersion::RESP2);
+    }
+
+    #[tokio::test]
+    async fn auto_protocol_rejects_subscriptions_when_hello_is_rejected() {
+        let connection_info = RedisConnectionInfo {
+            protocol: ProtocolVersion::Auto,
+            pubsub_subscriptions: Some(
+                [(
+                    PubSubSubscriptionKind::Exact,
+                    HashSet::from([b"channel".to_vec()]),
+                )]
+                .into(),
+            ),
+            ..Default::default()
+        };
+        let mut con = ScriptedConnection::new(vec![
+            // HELLO, 2 * CLIENT SETINFO. The subscription is held back until RESP3 is confirmed.
+            vec![
+                server_error("unknown command 'HELLO'"),
+                Value::Okay,
+                Value::Okay,
+            ],
+            // 2 * CLIENT SETINFO
+            vec![Value::Okay, Value::Okay],
+        ]);
+
+        let result = setup_connection(&connection_info, &mut con, false, None).await;
+
+        assert_eq!(
+            result.map_err(|err| err.kind()),
+            Err(ErrorKind::InvalidClientConfig)
+        );
+        assert_eq!(con.sent_batches, vec![3, 2]);
+    }
+
+    #[tokio::test]
+    async fn auto_protocol_does_not_fall_back_on_other_hello_errors() {
+        let connection_info = RedisConnectionInfo {
+            protocol: ProtocolVersion::Auto,
+            ..Default::default()
+        };
+        let mut con = ScriptedConnection::new(vec![vec![
+            server_error("Protocol version is not supported"),
+            Value::Okay,
+            Value::Okay,
+        ]]);
+
+        let result = setup_connection(&connection_info, &mut con, false, None).await;
+
+        assert!(result.is_err());
+        assert_eq!(con.sent_batches, vec![3]);
+    }
+
+    #[tokio::test]
+    async fn auto_protocol_uses_resp3_when_hello_is_accepted() {
+        let connection_info = RedisConnectionInfo {
+            protocol: ProtocolVersion::Auto,
+            pubsub_subscriptions: Some(
+                [(
+                    PubSubSubscriptionKind::Exact,
+                    HashSet::from([b"channel".to_vec()]),
+                )]
+                .into(),
+            ),
+            ..Default::default()
+        };
+        let mut con = ScriptedConnection::new(vec![
+            // HELLO, 2 * CLIENT SETINFO
+            vec![Value::Map(vec![]), Value::Okay, Value::Okay],
+            // SUBSCRIBE
+            vec![Value::Push {
+                kind: PushKind::Subscribe,
+                data: vec![Value::BulkString(b"channel".to_vec()), Value::Int(1)],
+            }],
+        ]);
+
+        setup_connection(&connection_info, &mut con, false, None)
+            .await
+            .unwrap();
+
+        assert_eq!(con.sent_batches, vec![3, 1]);
+        assert_eq!(con.get_protocol(), ProtocolVersion::RESP3);
+    }
+
+    #[tokio::test]
+    async fn auto_protocol_does_not_fall_back_on_wrong_credentials() {
+        let connection_info = RedisConnectionInfo {
+            protocol: ProtocolVersion::Auto,
+            password: Some("password".to_string()),
+            ..Default::default()
+        };
+        let wrongpass = Value::ServerError(ServerError::ExtensionError {
+            code: "WRONGPASS".to_string(),
+            detail: Some("invalid username-password pair".to_string()),
+        });
+        let mut con = ScriptedConnection::new(vec![vec![wrongpass, Value::Okay, Value::Okay]]);
+
+        let result = setup_connection(&connection_info, &mut con, false, None).await;
+
+        assert!(result.is_err());
+        assert_eq!(con.sent_batches, vec![3]);
+    }
+
+    #[tokio::test]
+    async fn sequential_handshake_sends_each_command_in_its_own_round_trip() {
+        let connection_info = RedisConnectionInfo {
+            protocol: ProtocolVersion::RESP2,
+            password: Some("password".to_string()),
+            db: 1,
+            ..Default::default()
+        };
+        let mut con = ScriptedConnection::new(vec![
+            // AUTH, SELECT, 2 * CLIENT SETINFO
+            vec![Value::Okay],
+            vec![server_error("DB index is out of range")],
+            vec![Value::Okay],
+            vec![Value::Okay],
+        ]);
+
+        let result = setup_connection_with_mode(
+            &connection_info,
+            &mut con,
+            false,
+            None,
+            HandshakeMode::Sequential,
+        )
+        .await;
+
+        assert_eq!(
+            result.map_err(|err| err.kind()),
+            Err(ErrorKind::ResponseError)
+        );
+        assert_eq!(con.sent_batches, vec![1, 1, 1, 1]);
+    }
+}
@@ -25,7 +25,7 @@ use std::collections::VecDeque;
 use std::fmt;
 use std::fmt::Debug;
 use std::pin::Pin;
-use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
 use std::sync::Arc;
 use std::task::{self, Poll};
 use std::time::Duration;
@@ -87,6 +87,24 @@ pub(crate) struct Pipeline<SinkItem> {
     sender: mpsc::Sender<PipelineMessage<SinkItem>>,
     push_manager: Arc<ArcSwap<PushManager>>,
     is_stream_closed: Arc<AtomicBool>,
+    // The number of requests that were sent through the pipeline and are still waiting for a response.
+    pending_requests: Arc<AtomicUsize>,
+}
+
+// Decrements the pending requests counter when a request is done, including when its future is dropped.
+struct PendingRequestGuard(Arc<AtomicUsize>);
+
+impl PendingRequestGuard {
+    fn new(pending_requests: &Arc<AtomicUsize>) -> Self {
+        pending_requests.fetch_add(1, Ordering::Relaxed);
+        Self(pending_requests.clone())
+    }
+}
+
+impl Drop for PendingRequestGuard {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
 }
 
 impl<SinkItem> Debug for Pipeline<SinkItem>
@@ -360,6 +378,7 @@ where
                 sender,
                 push_manager,
                 is_stream_closed,
+                pending_requests: Arc::new(AtomicUsize::new(0)),
             },
             f,
         )
@@ -378,6 +397,7 @@ where
         timeout: Duration,
         is_atomic: bool,
     ) -> Result<Value, RedisError> {
+        let _pending_request = PendingRequestGuard::new(&self.pending_requests);
         let (sender, receiver) = oneshot::channel();
 
         self.sender
@@ -423,6 +443,11 @@ where
     pub fn is_closed(&self) -> bool {
         self.is_stream_closed.load(Ordering::Relaxed)
     }
+
+    /// Returns the number of requests that are waiting for a response.
+    fn pending_requests(&self) -> usize {
+        self.pending_requests.load(Ordering::Relaxed)
+    }
 }
 
 /// A connection object which can be cloned, allowing requests to be be sent concurrently
@@ -506,6 +531,7 @@ impl MultiplexedConnection {
                 &connection_info.redis,
                 &mut con,
                 glide_connection_options.discover_az,
+                glide_connection_options.client_tracking,
             );
 
             futures_util::pin_mut!(auth);
@@ -729,6 +755,10 @@ impl ConnectionLike for MultiplexedConnection {
         self.pipeline.is_closed()
     }
 
+    fn pending_requests(&self) -> usize {
+        self.pipeline.pending_requests()
+    }
+
     /// Get the node's availability zone
     fn get_az(&self) -> Option<String> {
         self.availability_zone.clone()
@@ -738,6 +768,14 @@ impl ConnectionLike for MultiplexedConnection {
     fn set_az(&mut self, az: Option<String>) {
         self.availability_zone = az;
     }
+
+    fn get_protocol(&self) -> ProtocolVersion {
+        self.protocol
+    }
+
+    fn set_protocol(&mut self, protocol: ProtocolVersion) {
+        self.protocol = protocol;
+    }
 }
 impl MultiplexedConnection {
     /// Subscribes to a new channel.
@@ -0,0 +1,489 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use crate::types::{ErrorKind, RedisError, RedisResult};
+use logger_core::{log_info, log_warn};
+use std::cell::Cell;
+use std::fmt;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU32, AtomicU64, AtomicU8, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use telemetrylib::{GlideOpenTelemetry, Telemetry};
+
+const CLOSED: u8 = 0;
+const OPEN: u8 = 1;
+const HALF_OPEN: u8 = 2;
+/// Half-open, with the single trial request in flight.
+const HALF_OPEN_TRIAL: u8 = 3;
+
+thread_local! {
+    // Set while dropping requests whose caller timed out.
+    static DROPPING_TIMED_OUT_REQUESTS: Cell<bool> = const { Cell::new(false) };
+}
+
+/// How long a breaker stays open before it lets a trial request through.
+pub const DEFAULT_CIRCUIT_BREAKER_OPEN_DURATION: Duration = Duration::from_secs(5);
+
+/// The state of a [`CircuitBreaker`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests are sent to the node.
+    Closed,
+    /// The node is considered unresponsive, and requests to it fail fast.
+    Open,
+    /// A single trial request is let through, to test whether the node recovered.
+    HalfOpen,
+}
+
+impl fmt::Display for CircuitState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            CircuitState::Closed => "closed",
+            CircuitState::Open => "open",
+            CircuitState::HalfOpen => "half-open",
+        })
+    }
+}
+
+/// Stops sending requests to an unresponsive node, so they fail fast instead of waiting out the request timeout.
+///
+/// The breaker opens after a number of consecutive timeouts or IO errors, and requests to the node then fail
+/// with [`ErrorKind::CircuitBreakerOpen`]. Once the breaker was open for its open duration, or when the periodic
+/// connection checks move it to half-open, it lets a single trial request through: the breaker closes if it
+/// succeeds, and opens again if it fails.
+#[derive(Debug)]
+pub struct CircuitBreaker {
+    address: String,
+    failure_threshold: u32,
+    open_duration: Duration,
+    state: AtomicU8,
+    consecutive_failures: AtomicU32,
+    created_at: Instant,
+    /// When the breaker last opened, in milliseconds since `created_at`.
+    opened_at_millis: AtomicU64,
+}
+
+impl CircuitBreaker {
+    /// Creates a closed breaker for the node in `address`, which opens after `failure_threshold` consecutive failures,
+    /// and stays open for [`DEFAULT_CIRCUIT_BREAKER_OPEN_DURATION`].
+    pub fn new(address: impl Into<String>, failure_threshold: u32) -> Self {
+        Self::with_open_duration(
+            address,
+            failure_threshold,
+            DEFAULT_CIRCUIT_BREAKER_OPEN_DURATION,
+        )
+    }
+
+    /// Creates a closed breaker for the node in `address`, which opens after `failure_threshold` consecutive failures,
+    /// and stays open for `open_duration` before it lets a trial request through.
+    pub fn with_open_duration(
+        address: impl Into<String>,
+        failure_threshold: u32,
+        open_duration: Duration,
+    ) -> Self {
+        Self {
+            address: address.into(),
+            failure_threshold: failure_threshold.max(1),
+            open_duration,
+            state: AtomicU8::new(CLOSED),
+            consecutive_failures: AtomicU32::new(0),
+            created_at: Instant::now(),
+            opened_at_millis: AtomicU64::new(0),
+        }
+    }
+
+    /// Returns the number of consecutive failures after which the breaker opens.
+    pub fn failure_threshold(&self) -> u32 {
+        self.failure_threshold
+    }
+
+    /// Returns the current state of the breaker.
+    pub fn state(&self) -> CircuitState {
+        match self.state.load(Ordering::Acquire) {
+            CLOSED => CircuitState::Closed,
+            OPEN => CircuitState::Open,
+            _ => CircuitState::HalfOpen,
+        }
+    }
+
+    /// Starts a request to the node, or fails with [`ErrorKind::CircuitBreakerOpen`] if requests to the node should fail fast.
+    ///
+    /// The outcome of the request should be reported through [`CircuitBreakerAttempt::complete`]. An attempt that is
+    /// dropped before it completes counts as a failure if the request timed out, see [`with_request_timeout`], and
+    /// otherwise, e.g. if the caller cancelled the request, doesn't affect the breaker.
+    pub fn try_acquire(self: &Arc<Self>) -> RedisResult<CircuitBreakerAttempt> {
+        let allowed = match self.state.load(Ordering::Acquire) {
+            CLOSED => true,
+            HALF_OPEN => self.start_trial(HALF_OPEN),
+            OPEN if self.open_duration_elapsed() => {
+                let started = self.start_trial(OPEN);
+                if started {
+                    self.report_transition(CircuitState::HalfOpen);
+                }
+                started
+            }
+            _ => false,
+        };
+        if !allowed {
+            return Err(RedisError::from((
+                ErrorKind::CircuitBreakerOpen,
+                "Requests to the node fail fast, since it didn't respond to recent requests",
+                self.address.clone(),
+            )));
+        }
+        Ok(CircuitBreakerAttempt {
+            breaker: self.clone(),
+            completed: false,
+        })
+    }
+
+    /// Moves an open breaker to half-open, so the next request tests whether the node recovered.
+    /// A half-open breaker whose trial request was never completed is reset, so another trial can be made.
+    pub fn half_open(&self) {
+        let previous = self
+            .state
+            .fetch_update(Ordering::AcqRel, Ordering::Acquire, |state| {
+                matches!(state, OPEN | HALF_OPEN_TRIAL).then_some(HALF_OPEN)
+            });
+        if previous == Ok(OPEN) {
+            self.report_transition(CircuitState::HalfOpen);
+        }
+    }
+
+    fn start_trial(&self, state: u8) -> bool {
+        self.state
+            .compare_exchange(state, HALF_OPEN_TRIAL, Ordering::AcqRel, Ordering::Acquire)
+            .is_ok()
+    }
+
+    fn elapsed_millis(&self) -> u64 {
+        u64::try_from(self.created_at.elapsed().as_millis()).unwrap_or(u64::MAX)
+    }
+
+    fn open_duration_elapsed(&self) -> bool {
+        let opened_at = self.opened_at_millis.load(Ordering::Acquire);
+        self.elapsed_millis().saturating_sub(opened_at) >= self.open_duration.as_millis() as u64
+    }
+
+    // Lets another trial request through, after the trial request was dropped without an outcome.
+    fn cancel_trial(&self) {
+        let _ = self.state.compare_exchange(
+            HALF_OPEN_TRIAL,
+            HALF_OPEN,
+            Ordering::AcqRel,
+            Ordering::Acquire,
+        );
+    }
+
+    fn record_success(&self) {
+        self.consecutive_failures.store(0, Ordering::Release);
+        if self.state.swap(CLOSED, Ordering::AcqRel) != CLOSED {
+            self.report_transition(CircuitState::Closed);
+        }
+    }
+
+    fn record_failure(&self) {
+        let failures = self
+            .consecutive_failures
+            .fetch_add(1, Ordering::AcqRel)
+            .saturating_add(1);
+        let opened = match self.state.load(Ordering::Acquire) {
+            // The trial request failed, so the node is still unresponsive.
+            HALF_OPEN | HALF_OPEN_TRIAL => self.state.swap(OPEN, Ordering::AcqRel) != OPEN,
+            CLOSED if failures >= self.failure_threshold => self
+                .state
+                .compare_exchange(CLOSED, OPEN, Ordering::AcqRel, Ordering::Acquire)
+                .is_ok(),
+            _ => false,
+        };
+        if opened {
+            self.opened_at_millis
+                .store(self.elapsed_millis(), Ordering::Release);
+            self.report_transition(CircuitState::Open);
+        }
+    }
+
+    fn report_transition(&self, state: CircuitState) {
+        let message = format!("Circuit breaker of node {} is {state}", self.address);
+        match state {
+            CircuitState::Open => log_warn("circuit breaker", message),
+            CircuitState::Closed | CircuitState::HalfOpen => log_info("circuit breaker", message),
+        }
+        let _ = GlideOpenTelemetry::record_circuit_breaker_transition(&state.to_string());
+    }
+}
+
+// Timeouts and IO errors indicate that the node is unresponsive, while any response from the node,
+// including an error response, indicates that it's reachable.
+fn is_node_failure(error: &RedisError) -> bool {
+    error.is_timeout()
+        || error.is_io_error()
+        || error.is_connection_dropped()
+        || error.is_connection_refusal()
+}
+
+/// A request to a node that is tracked by the node's [`CircuitBreaker`].
+#[derive(Debug)]
+pub struct CircuitBreakerAttempt {
+    breaker: Arc<CircuitBreaker>,
+    completed: bool,
+}
+
+impl CircuitBreakerAttempt {
+    /// Reports the outcome of the request to the breaker.
+    pub fn complete<T>(mut self, result: &RedisResult<T>) {
+        self.completed = true;
+        match result {
+            Err(error) if is_node_failure(error) => self.breaker.record_failure(),
+            _ => self.breaker.record_success(),
+        }
+    }
+
+    /// Discards the attempt without reporting an outcome to the breaker, e.g. when a hedged request was answered first.
+    pub fn abandon(self) {}
+}
+
+impl Drop for CircuitBreakerAttempt {
+    fn drop(&mut self) {
+        if self.completed {
+            return;
+        }
+        if DROPPING_TIMED_OUT_REQUESTS.with(Cell::get) {
+            self.breaker.record_failure();
+        } else {
+            self.breaker.cancel_trial();
+        }
+    }
+}
+
+/// A request to a node, whose outcome is recorded in the node's statistics and reported to the node's
+/// [`CircuitBreaker`], if the request is tracked by one.
+///
+/// A request that is dropped before it completes is recorded as a timeout if it timed out, see [`with_request_timeout`],
+/// and otherwise isn't recorded.
+#[derive(Debug)]
+pub struct NodeRequest {
+    /// The node's address, or `None` if statistics are disabled or the request was already recorded.
+    address: Option<String>,
+    attempt: Option<CircuitBreakerAttempt>,
+}
+
+impl NodeRequest {
+    /// Starts a request to the node in `address`, which is tracked by `attempt` if the node has a breaker.
+    pub fn new(address: &str, attempt: Option<CircuitBreakerAttempt>) -> Self {
+        Self {
+            address: Telemetry::statistics_enabled().then(|| address.to_string()),
+            attempt,
+        }
+    }
+
+    /// Records the outcome of the request, and reports it to the breaker.
+    pub fn complete<T>(mut self, result: &RedisResult<T>) {
+        if let Some(address) = self.address.take() {
+            Telemetry::record_node_result(
+                &address,
+                result.as_ref().is_err_and(|err| err.is_timeout()),
+            );
+        }
+        if let Some(attempt) = self.attempt.take() {
+            attempt.complete(result);
+        }
+    }
+
+    /// Discards the request without recording it, e.g. when a hedged request was answered first.
+    pub fn abandon(mut self) {
+        self.address = None;
+        if let Some(attempt) = self.attempt.take() {
+            attempt.abandon();
+        }
+    }
+}
+
+impl Drop for NodeRequest {
+    fn drop(&mut self) {
+        if let Some(address) = self.address.take() {
+            if DROPPING_TIMED_OUT_REQUESTS.with(Cell::get) {
+                Telemetry::record_node_result(&address, true);
+            }
+        }
+    }
+}
+
+/// Awaits `future` for up to `duration`, and returns `None` if it didn't complete in time.
+///
+/// The requests that are dropped when `future` times out count as failures of their nodes' circuit breakers,
+/// and as timeouts in their nodes' statistics, since the nodes didn't respond in time.
+pub async fn with_request_timeout<F: Future>(duration: Duration, future: F) -> Option<F::Output> {
+    let mut future = Box::pin(future);
+    match tokio::time::timeout(duration, &mut future).await {
+        Ok(output) => Some(output),
+        Err(_) => {
+            drop_timed_out_requests(|| drop(future));
+            None
+        }
+    }
+}
+
+// Runs `drop`, counting the attempts and node requests that it drops as timed out.
+pub(crate) fn drop_timed_out_requests(drop_requests: impl FnOnce()) {
+    let dropping = DROPPING_TIMED_OUT_REQUESTS.with(|flag| flag.replace(true));
+    drop_requests();
+    DROPPING_TIMED_OUT_REQUESTS.with(|flag| flag.set(dropping));
+}
+
+/// Tells a request that is sent by another task, e.g. the cluster connection's task, that its caller timed out.
+///
+/// The caller holds the guard, and the request holds the flag that the guard raises if it's dropped because of a timeout.
+pub(crate) fn timed_out_flag() -> (TimedOutGuard, TimedOutFlag) {
+    let flag = Arc::new(AtomicBool::new(false));
+    (TimedOutGuard(flag.clone()), TimedOutFlag(flag))
+}
+
+/// The caller's side of [`timed_out_flag`].
+pub(crate) struct TimedOutGuard(Arc<AtomicBool>);
+
+impl Drop for TimedOutGuard {
+    fn drop(&mut self) {
+        if DROPPING_TIMED_OUT_REQUESTS.with(Cell::get) {
+            self.0.store(true, Ordering::Release);
+        }
+    }
+}
+
+/// The request's side of [`timed_out_flag`]. The default flag is never raised.
+#[derive(Debug, Default)]
+pub(crate) struct TimedOutFlag(Arc<AtomicBool>);
+
+impl TimedOutFlag {
+    /// Returns true if the caller stopped waiting for the request because it timed out.
+    pub(crate) fn is_raised(&self) -> bool {
+        self.0.load(Ordering::Acquire)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::future;
+    use std::io;
+
+    fn timeout_result() -> RedisResult<()> {
+        Err(io::Error::from(io::ErrorKind::TimedOut).into())
+    }
+
+    fn fail(breaker: &Arc<CircuitBreaker>) {
+        breaker.try_acquire().unwrap().complete(&timeout_result());
+    }
+
+    #[test]
+    fn test_circuit_breaker_opens_after_consecutive_failures() {
+        let breaker = Arc::new(CircuitBreaker::new("node:6379", 3));
+        fail(&breaker);
+        fail(&breaker);
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        fail(&breaker);
+        assert_eq!(breaker.state(), CircuitState::Open);
+        let err = breaker.try_acquire().unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::CircuitBreakerOpen);
+        assert_eq!(err.detail(), Some("node:6379"));
+    }
+
+    #[test]
+    fn test_circuit_breaker_resets_the_failures_on_responses() {
+        let breaker = Arc::new(CircuitBreaker::new("node:6379", 2));
+        fail(&breaker);
+        // An error response shows that the node is responsive.
+        let error_response: RedisResult<()> = Err((ErrorKind::ResponseError, "WRONGTYPE").into());
+        breaker.try_acquire().unwrap().complete(&error_response);
+        fail(&breaker);
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_circuit_breaker_ignores_dropped_and_abandoned_attempts() {
+        let breaker = Arc::new(CircuitBreaker::new("node:6379", 1));
+        drop(breaker.try_acquire().unwrap());
+        breaker.try_acquire().unwrap().abandon();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+
+        // A trial request that was dropped lets another trial through.
+        fail(&breaker);
+        breaker.half_open();
+        drop(breaker.try_acquire().unwrap());
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        assert!(breaker.try_acquire().is_ok());
+    }
+
+    #[tokio::test]
+    async fn test_circuit_breaker_counts_timed_out_attempts_as_failures() {
+        let breaker = Arc::new(CircuitBreaker::new("node:6379", 2));
+        for _ in 0..2 {
+            let attempt = breaker.try_acquire().unwrap();
+            let request = with_request_timeout(Duration::from_millis(1), async move {
+                future::pending::<()>().await;
+                attempt.complete(&Ok(()));
+            });
+            assert_eq!(request.await, None);
+        }
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        // The caller of a request that is sent by another task is told that it timed out.
+        let (guard, flag) = timed_out_flag();
+        let (other_guard, other_flag) = timed_out_flag();
+        drop_timed_out_requests(|| drop(guard));
+        drop(other_guard);
+        assert!(flag.is_raised());
+        assert!(!other_flag.is_raised());
+    }
+
+    #[test]
+    fn test_circuit_breaker_lets_a_trial_through_after_the_open_duration() {
+        let breaker = Arc::new(CircuitBreaker::new("node:6379", 1));
+        fail(&breaker);
+        assert!(breaker.try_acquire().is_err());
+
+        let breaker = Arc::new(CircuitBreaker::with_open_duration(
+            "node:6379",
+            1,
+            Duration::ZERO,
+        ));
+        fail(&breaker);
+        assert_eq!(breaker.state(), CircuitState::Open);
+        let trial = breaker.try_acquire().unwrap();
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+        assert!(breaker.try_acquire().is_err());
+        trial.complete(&Ok(()));
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_circuit_breaker_half_open_lets_a_single_trial_through() {
+        let breaker = Arc::new(CircuitBreaker::new("node:6379", 1));
+        fail(&breaker);
+        breaker.half_open();
+        assert_eq!(breaker.state(), CircuitState::HalfOpen);
+
+        let trial = breaker.try_acquire().unwrap();
+        assert!(breaker.try_acquire().is_err());
+        trial.complete(&Ok(()));
+        assert_eq!(breaker.state(), CircuitState::Closed);
+        assert!(breaker.try_acquire().is_ok());
+    }
+
+    #[test]
+    fn test_circuit_breaker_reopens_when_the_trial_fails() {
+        let breaker = Arc::new(CircuitBreaker::new("node:6379", 5));
+        for _ in 0..5 {
+            fail(&breaker);
+        }
+        breaker.half_open();
+        fail(&breaker);
+        assert_eq!(breaker.state(), CircuitState::Open);
+
+        // A closed breaker isn't affected by the periodic checks.
+        let breaker = Arc::new(CircuitBreaker::new("node:6379", 5));
+        breaker.half_open();
+        assert_eq!(breaker.state(), CircuitState::Closed);
+    }
+}
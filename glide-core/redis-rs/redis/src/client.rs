@@ -1,7 +1,7 @@
 use std::time::Duration;
 
 #[cfg(feature = "aio")]
-use crate::aio::DisconnectNotifier;
+use crate::aio::{ConnectionCredentialsProvider, DisconnectNotifier};
 
 use crate::{
     connection::{connect, Connection, ConnectionInfo, ConnectionLike, IntoConnectionInfo},
@@ -15,6 +15,8 @@ use std::net::IpAddr;
 use std::net::SocketAddr;
 #[cfg(feature = "aio")]
 use std::pin::Pin;
+#[cfg(feature = "aio")]
+use std::sync::Arc;
 use tokio::sync::mpsc;
 
 use crate::tls::{inner_build_with_tls, TlsCertificates};
@@ -78,6 +80,16 @@ impl Client {
     }
 }
 
+/// The mode of server-assisted client side caching, which is enabled on a connection with `CLIENT TRACKING`.
+/// Invalidation messages are sent as RESP3 pushes, so tracking requires the RESP3 protocol.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ClientTrackingMode {
+    /// The server remembers the keys read by the connection, and sends invalidations only for these keys.
+    Default,
+    /// The server sends invalidations for every modified key, regardless of the keys read by the connection.
+    Broadcast,
+}
+
 /// Glide-specific connection options
 #[derive(Clone, Default)]
 pub struct GlideConnectionOptions {
@@ -96,6 +108,11 @@ pub struct GlideConnectionOptions {
     pub connection_timeout: Option<Duration>,
     /// Retry strategy configuration for reconnect attempts.
     pub connection_retry_strategy: Option<RetryStrategy>,
+    /// If set, `CLIENT TRACKING` is enabled in the connection's setup, with the given mode.
+    pub client_tracking: Option<ClientTrackingMode>,
+    #[cfg(feature = "aio")]
+    /// If set, the credentials are fetched from the provider before connecting, replacing the ones in the connection info.
+    pub credentials_provider: Option<Arc<dyn ConnectionCredentialsProvider>>,
 }
 
 /// To enable async support you need to enable the feature: `tokio-comp`
@@ -427,9 +444,17 @@ impl Client {
     where
         T: crate::aio::RedisRuntime,
     {
+        let mut connection_info = self.connection_info.clone();
+        if let Some(provider) = glide_connection_options.credentials_provider.as_ref() {
+            let (username, password) = provider.connection_credentials().await?;
+            if username.is_some() {
+                connection_info.redis.username = username;
+            }
+            connection_info.redis.password = Some(password);
+        }
         let (con, ip) = self.get_simple_async_connection::<T>(socket_addr).await?;
         crate::aio::MultiplexedConnection::new_with_response_timeout(
-            &self.connection_info,
+            &connection_info,
             con,
             response_timeout,
             glide_connection_options,
@@ -509,6 +534,7 @@ impl Client {
     ///                 client_key: client_key_vec,
     ///             }),
     ///             root_cert: Some(root_cert_vec),
+    ///             server_name: None,
     ///         }
     ///     )
     ///     .expect("Unable to build client");
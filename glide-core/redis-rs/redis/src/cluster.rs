@@ -43,6 +43,7 @@ use crate::cluster_routing::{
 };
 use crate::cluster_slotmap::SlotMap;
 use crate::cluster_topology::parse_and_count_slots;
+pub use crate::cluster_topology::AddressRemap;
 use crate::cmd::{cmd, Cmd};
 use crate::connection::{
     connect, Connection, ConnectionAddr, ConnectionInfo, ConnectionLike, RedisConnectionInfo,
@@ -382,9 +383,12 @@ where
                 ErrorKind::ClientError,
                 "can't parse node address",
             )))?;
-            match parse_and_count_slots(&value, self.cluster_params.tls, addr).map(|slots_data| {
-                SlotMap::new(slots_data.1, self.cluster_params.read_from_replicas.clone())
-            }) {
+            match parse_and_count_slots(&value, self.cluster_params.tls, addr).map(
+                |(_, mut slots)| {
+                    self.cluster_params.address_remap.apply_to_slots(&mut slots);
+                    SlotMap::new(slots, self.cluster_params.read_from_replicas.clone())
+                },
+            ) {
                 Ok(new_slots) => {
                     result = Ok(new_slots);
                     break;
@@ -709,6 +713,7 @@ where
                         Redirect::Moved(addr) => (addr, false),
                         Redirect::Ask(addr, should_exec_asking) => (addr, should_exec_asking),
                     };
+                    let addr = self.cluster_params.address_remap.apply(addr);
                     let conn = self.get_connection_by_addr(&mut connections, &addr)?;
                     if is_asking {
                         // if we are in asking mode we want to feed a single
@@ -1027,6 +1032,13 @@ pub(crate) fn slot_cmd() -> Cmd {
     cmd
 }
 
+#[cfg(feature = "cluster-async")]
+pub(crate) fn shards_cmd() -> Cmd {
+    let mut cmd = Cmd::new();
+    cmd.arg("CLUSTER").arg("SHARDS");
+    cmd
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
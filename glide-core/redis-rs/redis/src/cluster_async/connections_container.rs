@@ -1,7 +1,9 @@
+use crate::aio::ConnectionLike;
 use crate::cluster_async::ConnectionFuture;
 use crate::cluster_routing::{Route, ShardAddrs, SlotAddr};
 use crate::cluster_slotmap::{ReadFromReplicaStrategy, SlotMap, SlotMapValue};
 use crate::cluster_topology::TopologyHash;
+use crate::{CircuitBreaker, LatencyTracker};
 use dashmap::DashMap;
 use futures::FutureExt;
 use rand::seq::IteratorRandom;
@@ -9,6 +11,7 @@ use std::collections::HashMap;
 use std::net::IpAddr;
 use std::sync::atomic::Ordering;
 use std::sync::Arc;
+use std::time::Duration;
 use telemetrylib::Telemetry;
 
 use tracing::debug;
@@ -21,11 +24,7 @@ macro_rules! count_connections {
     ($conn_map:expr) => {{
         let mut count = 0usize;
         for a in $conn_map {
-            count = count.saturating_add(if a.management_connection.is_some() {
-                2
-            } else {
-                1
-            });
+            count = count.saturating_add(a.connections_count());
         }
         count
     }};
@@ -78,10 +77,34 @@ impl<Connection> From<ConnectionDetails<Connection>>
     }
 }
 
+/// Reports how many requests are waiting for a response on a connection.
+/// Used to pick the least loaded user connection, when a node has more than one.
+pub trait ConnectionLoad {
+    /// Returns the number of requests that were sent over the connection and are still waiting for a response.
+    fn pending_requests(&self) -> usize;
+}
+
+impl<C> ConnectionLoad for ConnectionFuture<C>
+where
+    C: ConnectionLike + Clone,
+{
+    fn pending_requests(&self) -> usize {
+        // A connection that is still being established is only used if there's no other option.
+        self.peek()
+            .map_or(usize::MAX, |conn| conn.pending_requests())
+    }
+}
+
 #[derive(Clone, Eq, PartialEq, Debug)]
 pub struct ClusterNode<Connection> {
     pub user_connection: ConnectionDetails<Connection>,
     pub management_connection: Option<ConnectionDetails<Connection>>,
+    /// Additional user connections, created when more than one connection per node is configured.
+    /// The last of them is reserved for blocking commands, and the rest share the load with `user_connection`.
+    pub additional_user_connections: Vec<ConnectionDetails<Connection>>,
+    /// Connection dedicated to blocking commands, such as BLPOP, when the node has no additional user connection
+    /// to reserve for them. Created on first use.
+    pub blocking_connection: Option<ConnectionDetails<Connection>>,
 }
 
 impl<Connection> ClusterNode<Connection>
@@ -95,23 +118,52 @@ where
         Self {
             user_connection,
             management_connection,
+            additional_user_connections: Vec::new(),
+            blocking_connection: None,
         }
     }
 
+    /// Sets the additional user connections of the node.
+    pub fn with_additional_user_connections(
+        mut self,
+        additional_user_connections: Vec<ConnectionDetails<Connection>>,
+    ) -> Self {
+        self.additional_user_connections = additional_user_connections;
+        self
+    }
+}
+
+impl<Connection> ClusterNode<Connection> {
     /// Return the number of underlying connections managed by this instance of ClusterNode
     pub fn connections_count(&self) -> usize {
-        if self.management_connection.is_some() {
-            2
-        } else {
-            1
-        }
+        1 + usize::from(self.management_connection.is_some())
+            + self.additional_user_connections.len()
+            + usize::from(self.blocking_connection.is_some())
+    }
+}
+
+impl<Connection> ClusterNode<Connection>
+where
+    Connection: Clone + ConnectionLoad,
+{
+    /// Returns the user connection with the fewest pending requests, excluding the connection reserved for blocking commands.
+    pub(crate) fn user_connection(&self) -> Connection {
+        let Some((_, shared_connections)) = self.additional_user_connections.split_last() else {
+            return self.user_connection.conn.clone();
+        };
+        std::iter::once(&self.user_connection)
+            .chain(shared_connections)
+            .min_by_key(|connection| connection.conn.pending_requests())
+            .unwrap_or(&self.user_connection)
+            .conn
+            .clone()
     }
 
     pub(crate) fn get_connection(&self, conn_type: &ConnectionType) -> Connection {
         match conn_type {
-            ConnectionType::User => self.user_connection.conn.clone(),
+            ConnectionType::User => self.user_connection(),
             ConnectionType::PreferManagement => self.management_connection.as_ref().map_or_else(
-                || self.user_connection.conn.clone(),
+                || self.user_connection(),
                 |management_conn| management_conn.conn.clone(),
             ),
         }
@@ -296,6 +348,10 @@ pub(crate) struct ConnectionsContainer<Connection> {
     read_from_replica_strategy: ReadFromReplicaStrategy,
     topology_hash: TopologyHash,
     pub(crate) refresh_conn_state: RefreshConnectionStates,
+    /// Round-trip time moving averages of the nodes, used by the `LowestLatency` read strategy.
+    pub(crate) node_latencies: DashMap<String, LatencyTracker>,
+    /// Circuit breakers of the nodes, which are kept across reconnections to the nodes.
+    pub(crate) node_circuit_breakers: DashMap<String, Arc<CircuitBreaker>>,
 }
 
 impl<Connection> Drop for ConnectionsContainer<Connection> {
@@ -313,15 +369,23 @@ impl<Connection> Default for ConnectionsContainer<Connection> {
             read_from_replica_strategy: ReadFromReplicaStrategy::AlwaysFromPrimary,
             topology_hash: 0,
             refresh_conn_state: Default::default(),
+            node_latencies: Default::default(),
+            node_circuit_breakers: Default::default(),
         }
     }
 }
 
+impl<Connection> ConnectionsContainer<Connection> {
+    pub(crate) fn connection_map(&self) -> &DashMap<String, ClusterNode<Connection>> {
+        &self.connection_map
+    }
+}
+
 pub(crate) type ConnectionAndAddress<Connection> = (String, Connection);
 
 impl<Connection> ConnectionsContainer<Connection>
 where
-    Connection: Clone,
+    Connection: Clone + ConnectionLoad,
 {
     pub(crate) fn new(
         slot_map: SlotMap,
@@ -341,6 +405,8 @@ where
             read_from_replica_strategy,
             topology_hash,
             refresh_conn_state: Default::default(),
+            node_latencies: Default::default(),
+            node_circuit_breakers: Default::default(),
         }
     }
 
@@ -366,11 +432,29 @@ where
         Telemetry::incr_total_connections(conn_count_after.saturating_sub(conn_count_before));
     }
 
-    /// Returns the availability zone associated with the connection in address
+    /// Returns the availability zone associated with the connection in address.
+    /// If the connection didn't discover its availability zone, the zone reported in the topology is used.
     pub(crate) fn az_for_address(&self, address: &str) -> Option<String> {
         self.connection_map
             .get(address)
             .map(|item| item.value().user_connection.az.clone())?
+            .or_else(|| {
+                self.slot_map
+                    .node_metadata(address)?
+                    .availability_zone
+                    .clone()
+            })
+    }
+
+    /// Returns the connection of a replica that can serve reads, i.e. one that isn't loading or failed.
+    fn healthy_replica_connection(
+        &self,
+        replica: &str,
+    ) -> Option<ConnectionAndAddress<Connection>> {
+        if !self.slot_map.is_node_healthy(replica) {
+            return None;
+        }
+        self.connection_for_address(replica)
     }
 
     /// Returns true if the address represents a known primary node.
@@ -393,7 +477,8 @@ where
                 return self.connection_for_address(addrs.primary().as_str());
             }
             let index = (initial_index + check_count) % addrs.replicas().len();
-            if let Some(connection) = self.connection_for_address(addrs.replicas()[index].as_str())
+            if let Some(connection) =
+                self.healthy_replica_connection(addrs.replicas()[index].as_str())
             {
                 let _ = slot_map_value.last_used_replica.compare_exchange_weak(
                     initial_index,
@@ -447,6 +532,9 @@ where
             // Calculate index based on initial index and check count.
             let index = (initial_index + retries) % addrs.replicas().len();
             let replica = &addrs.replicas()[index];
+            if !self.slot_map.is_node_healthy(replica) {
+                continue;
+            }
 
             // Check if this replica’s availability zone matches the user’s availability zone.
             if let Some((address, connection_details)) =
@@ -480,6 +568,88 @@ where
         self.round_robin_read_from_replica(slot_map_value)
     }
 
+    /// Adds a round-trip time sample to the moving average of the node in `address`.
+    pub(crate) fn record_latency(&self, address: &str, round_trip_time: Duration) {
+        if let Some(tracker) = self.node_latencies.get(address) {
+            tracker.record(round_trip_time);
+            return;
+        }
+        self.node_latencies
+            .entry(address.to_string())
+            .or_default()
+            .record(round_trip_time);
+    }
+
+    /// Returns the circuit breaker of the node in `address`, which is created with `failure_threshold` on first use.
+    pub(crate) fn circuit_breaker(
+        &self,
+        address: &str,
+        failure_threshold: u32,
+    ) -> Arc<CircuitBreaker> {
+        if let Some(breaker) = self.node_circuit_breakers.get(address) {
+            return breaker.clone();
+        }
+        self.node_circuit_breakers
+            .entry(address.to_string())
+            .or_insert_with(|| Arc::new(CircuitBreaker::new(address, failure_threshold)))
+            .clone()
+    }
+
+    /// Moves the open circuit breakers to half-open, so the next request to each of their nodes tests whether it recovered.
+    pub(crate) fn half_open_circuit_breakers(&self) {
+        for breaker in self.node_circuit_breakers.iter() {
+            breaker.half_open();
+        }
+    }
+
+    /// Returns the connection of the replica with the lowest measured round-trip time.
+    /// Replicas without latency samples are ignored, unless no replica was measured yet,
+    /// in which case the replicas are selected in a round robin manner. Falls back to the primary if needed.
+    fn lowest_latency_read_from_replica(
+        &self,
+        slot_map_value: &SlotMapValue,
+    ) -> Option<ConnectionAndAddress<Connection>> {
+        let fastest_replica = slot_map_value
+            .addrs
+            .replicas()
+            .iter()
+            .filter_map(|replica| {
+                let latency = self.node_latencies.get(replica.as_str())?.average()?;
+                let connection = self.healthy_replica_connection(replica.as_str())?;
+                Some((latency, connection))
+            })
+            .min_by_key(|(latency, _)| *latency);
+
+        match fastest_replica {
+            Some((_, connection)) => Some(connection),
+            None => self.round_robin_read_from_replica(slot_map_value),
+        }
+    }
+
+    /// Returns the connection of another node that serves the slot of `route`, to hedge a read that was sent to `address`.
+    /// Healthy replicas are preferred, starting from the replica after the last used one, with a fallback to the primary.
+    pub(crate) fn hedge_connection_for_route(
+        &self,
+        route: &Route,
+        address: &str,
+    ) -> Option<ConnectionAndAddress<Connection>> {
+        let slot_map_value = self.slot_map.slot_value_for_route(route)?;
+        let addrs = &slot_map_value.addrs;
+        let replicas = addrs.replicas();
+        let initial_index = slot_map_value.last_used_replica.load(Ordering::Relaxed);
+        (1..=replicas.len())
+            .map(|offset| replicas[(initial_index + offset) % replicas.len()].as_str())
+            .filter(|replica| *replica != address)
+            .find_map(|replica| self.healthy_replica_connection(replica))
+            .or_else(|| {
+                let primary = addrs.primary();
+                if primary.as_str() == address {
+                    return None;
+                }
+                self.connection_for_address(primary.as_str())
+            })
+    }
+
     fn lookup_route(&self, route: &Route) -> Option<ConnectionAndAddress<Connection>> {
         let slot_map_value = self.slot_map.slot_value_for_route(route)?;
         let addrs = &slot_map_value.addrs;
@@ -508,6 +678,9 @@ where
                         slot_map_value,
                         az.to_string(),
                     ),
+                ReadFromReplicaStrategy::LowestLatency => {
+                    self.lowest_latency_read_from_replica(slot_map_value)
+                }
             },
             // when the user strategy per command is replica_preffered
             SlotAddr::ReplicaRequired => match &self.read_from_replica_strategy {
@@ -521,6 +694,9 @@ where
                         slot_map_value,
                         az.to_string(),
                     ),
+                ReadFromReplicaStrategy::LowestLatency => {
+                    self.lowest_latency_read_from_replica(slot_map_value)
+                }
                 _ => self.round_robin_read_from_replica(slot_map_value),
             },
         }
@@ -589,7 +765,7 @@ where
     ) -> impl Iterator<Item = ConnectionAndAddress<Connection>> + '_ {
         self.connection_map.iter().map(move |item| {
             let (node, address) = (item.key(), item.value());
-            (node.clone(), address.user_connection.conn.clone())
+            (node.clone(), address.user_connection())
         })
     }
 
@@ -614,17 +790,49 @@ where
     ) -> Option<ConnectionAndAddress<Connection>> {
         self.connection_map.get(address).map(|item| {
             let (address, conn) = (item.key(), item.value());
-            (address.clone(), conn.user_connection.conn.clone())
+            (address.clone(), conn.user_connection())
+        })
+    }
+
+    /// Returns the connection for blocking commands in the node at `address`: the connection reserved in the node's pool,
+    /// or else the dedicated connection, if it was already created.
+    pub(crate) fn blocking_connection_for_address(&self, address: &str) -> Option<Connection> {
+        self.connection_map.get(address).and_then(|item| {
+            let node = item.value();
+            node.additional_user_connections
+                .last()
+                .or(node.blocking_connection.as_ref())
+                .map(|connection| connection.conn.clone())
         })
     }
 
+    /// Sets the connection dedicated to blocking commands in the node at `address`, replacing the previous one.
+    /// Returns false if there's no node at `address`, e.g. if it was removed while the connection was created.
+    pub(crate) fn set_blocking_connection_for_address(
+        &self,
+        address: &str,
+        connection: ConnectionDetails<Connection>,
+    ) -> bool {
+        let Some(mut node) = self.connection_map.get_mut(address) else {
+            return false;
+        };
+        if node.blocking_connection.replace(connection).is_none() {
+            Telemetry::incr_total_connections(1);
+        }
+        true
+    }
+
     pub(crate) fn connection_details_for_address(
         &self,
         address: &str,
     ) -> Option<ConnectionAndAddress<ConnectionDetails<Connection>>> {
         self.connection_map.get(address).map(|item| {
-            let (address, conn) = (item.key(), item.value());
-            (address.clone(), conn.user_connection.clone())
+            let (address, node) = (item.key(), item.value());
+            let connection_details = ConnectionDetails {
+                conn: node.user_connection(),
+                ..node.user_connection.clone()
+            };
+            (address.clone(), connection_details)
         })
     }
 
@@ -666,6 +874,7 @@ where
     }
 
     pub(crate) fn remove_node(&self, address: &String) -> Option<ClusterNode<Connection>> {
+        self.node_latencies.remove(address);
         if let Some((_key, old_conn)) = self.connection_map.remove(address) {
             Telemetry::decr_total_connections(old_conn.connections_count());
             Some(old_conn)
@@ -678,10 +887,6 @@ where
         self.connection_map.len()
     }
 
-    pub(crate) fn connection_map(&self) -> &DashMap<String, ClusterNode<Connection>> {
-        &self.connection_map
-    }
-
     pub(crate) fn get_current_topology_hash(&self) -> TopologyHash {
         self.topology_hash
     }
@@ -697,6 +902,7 @@ mod tests {
     use std::collections::HashSet;
 
     use crate::cluster_routing::Slot;
+    use crate::cluster_slotmap::{NodeHealth, NodeMetadata};
 
     use super::*;
     impl<Connection> ClusterNode<Connection>
@@ -709,9 +915,18 @@ mod tests {
             Self {
                 user_connection: (user_connection, ip, az).into(),
                 management_connection: None,
+                additional_user_connections: Vec::new(),
+                blocking_connection: None,
             }
         }
     }
+
+    // The load of a test connection is its value.
+    impl ConnectionLoad for usize {
+        fn pending_requests(&self) -> usize {
+            *self
+        }
+    }
     fn remove_nodes(container: &ConnectionsContainer<usize>, addresses: &[&str]) {
         for address in addresses {
             container.remove_node(&(*address).into());
@@ -818,6 +1033,8 @@ mod tests {
                 .unwrap_or(ReadFromReplicaStrategy::AZAffinity("use-1a".to_string())),
             topology_hash: 0,
             refresh_conn_state: Default::default(),
+            node_latencies: Default::default(),
+            node_circuit_breakers: Default::default(),
         }
     }
 
@@ -875,6 +1092,8 @@ mod tests {
             read_from_replica_strategy: strategy,
             topology_hash: 0,
             refresh_conn_state: Default::default(),
+            node_latencies: Default::default(),
+            node_circuit_breakers: Default::default(),
         }
     }
 
@@ -990,6 +1209,133 @@ mod tests {
         );
     }
 
+    fn set_nodes_health(container: &mut ConnectionsContainer<usize>, nodes: &[(&str, NodeHealth)]) {
+        let nodes_metadata = nodes
+            .iter()
+            .map(|(address, health)| {
+                (
+                    address.to_string(),
+                    NodeMetadata {
+                        health: *health,
+                        ..Default::default()
+                    },
+                )
+            })
+            .collect();
+        let slot_map = std::mem::take(&mut container.slot_map);
+        container.slot_map = slot_map.with_nodes_metadata(nodes_metadata);
+    }
+
+    #[test]
+    fn get_replica_connection_for_replica_route_skips_unhealthy_replicas() {
+        let mut container = create_container();
+        set_nodes_health(
+            &mut container,
+            &[
+                ("replica3-1", NodeHealth::Loading),
+                ("replica3-2", NodeHealth::Online),
+            ],
+        );
+
+        for _ in 0..4 {
+            assert_eq!(
+                32,
+                container
+                    .connection_for_route(&Route::new(2001, SlotAddr::ReplicaOptional))
+                    .unwrap()
+                    .1
+            );
+        }
+    }
+
+    #[test]
+    fn get_primary_connection_for_replica_route_if_all_replicas_are_unhealthy() {
+        let mut container = create_container();
+        set_nodes_health(
+            &mut container,
+            &[
+                ("replica3-1", NodeHealth::Loading),
+                ("replica3-2", NodeHealth::Failed),
+            ],
+        );
+
+        assert_eq!(
+            3,
+            container
+                .connection_for_route(&Route::new(2001, SlotAddr::ReplicaOptional))
+                .unwrap()
+                .1
+        );
+    }
+
+    #[test]
+    fn get_hedge_connection_for_route_returns_another_replica() {
+        let container = create_container();
+        let route = Route::new(2001, SlotAddr::ReplicaOptional);
+
+        assert_eq!(
+            32,
+            container
+                .hedge_connection_for_route(&route, "replica3-1")
+                .unwrap()
+                .1
+        );
+        assert_eq!(
+            31,
+            container
+                .hedge_connection_for_route(&route, "replica3-2")
+                .unwrap()
+                .1
+        );
+    }
+
+    #[test]
+    fn get_hedge_connection_for_route_falls_back_to_primary() {
+        let mut container = create_container();
+        set_nodes_health(&mut container, &[("replica3-2", NodeHealth::Loading)]);
+
+        assert_eq!(
+            3,
+            container
+                .hedge_connection_for_route(
+                    &Route::new(2001, SlotAddr::ReplicaOptional),
+                    "replica3-1"
+                )
+                .unwrap()
+                .1
+        );
+        assert_eq!(
+            2,
+            container
+                .hedge_connection_for_route(
+                    &Route::new(1002, SlotAddr::ReplicaOptional),
+                    "replica2-1"
+                )
+                .unwrap()
+                .1
+        );
+        // A slot without replicas has no other node to hedge with.
+        assert!(container
+            .hedge_connection_for_route(&Route::new(500, SlotAddr::ReplicaOptional), "primary1")
+            .is_none());
+    }
+
+    #[test]
+    fn get_connection_for_az_affinity_route_skips_unhealthy_replicas_in_client_az() {
+        let mut container = create_container_with_az_strategy(false, None);
+        set_nodes_health(&mut container, &[("replica3-1", NodeHealth::Failed)]);
+
+        for _ in 0..4 {
+            assert_eq!(
+                33,
+                container
+                    .connection_for_route(&Route::new(2001, SlotAddr::ReplicaOptional))
+                    .unwrap()
+                    .1
+            );
+        }
+    }
+
     #[test]
     fn get_replica_connection_for_replica_route_if_some_but_not_all_replicas_were_removed() {
         let container = create_container();
@@ -1268,6 +1614,73 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn get_connection_for_lowest_latency_route() {
+        let container =
+            create_container_with_strategy(ReadFromReplicaStrategy::LowestLatency, false);
+
+        // No latencies were measured, round robin between the replicas
+        assert!(one_of(
+            container.connection_for_route(&Route::new(2001, SlotAddr::ReplicaOptional)),
+            &[31, 32],
+        ));
+
+        container.record_latency("replica3-1", Duration::from_micros(900));
+        container.record_latency("replica3-2", Duration::from_micros(300));
+        container.record_latency("primary3", Duration::from_micros(100));
+        for _ in 0..3 {
+            assert_eq!(
+                32,
+                container
+                    .connection_for_route(&Route::new(2001, SlotAddr::ReplicaOptional))
+                    .unwrap()
+                    .1
+            );
+        }
+
+        // Write commands are still routed to the primary
+        assert_eq!(
+            3,
+            container
+                .connection_for_route(&Route::new(2001, SlotAddr::Master))
+                .unwrap()
+                .1
+        );
+
+        // The fastest replica became slower than the other replica
+        for _ in 0..20 {
+            container.record_latency("replica3-2", Duration::from_micros(2000));
+        }
+        assert_eq!(
+            31,
+            container
+                .connection_for_route(&Route::new(2001, SlotAddr::ReplicaOptional))
+                .unwrap()
+                .1
+        );
+
+        // Removing the fastest replica also removes its latency
+        remove_nodes(&container, &["replica3-1"]);
+        assert!(container.node_latencies.get("replica3-1").is_none());
+        assert_eq!(
+            32,
+            container
+                .connection_for_route(&Route::new(2001, SlotAddr::ReplicaOptional))
+                .unwrap()
+                .1
+        );
+
+        // No replicas are left, fall back to the primary
+        remove_nodes(&container, &["replica3-2"]);
+        assert_eq!(
+            3,
+            container
+                .connection_for_route(&Route::new(2001, SlotAddr::ReplicaOptional))
+                .unwrap()
+                .1
+        );
+    }
+
     #[test]
     fn get_connection_by_address() {
         let container = create_container();
@@ -1569,4 +1982,46 @@ mod tests {
         new_addresses.sort();
         assert_eq!(current_addresses, new_addresses);
     }
+
+    #[test]
+    fn user_connection_picks_the_least_loaded_connection() {
+        let container = create_container();
+        let node =
+            ClusterNode::new((5, None, None).into(), None).with_additional_user_connections(vec![
+                (3, None, None).into(),
+                (7, None, None).into(),
+                (1, None, None).into(),
+            ]);
+        container.replace_or_add_connection_for_address("primary1", node);
+
+        // The last additional connection is reserved for blocking commands, so it's skipped although it's the least loaded.
+        assert_eq!(
+            container.connection_for_address("primary1"),
+            Some(("primary1".to_string(), 3))
+        );
+        assert_eq!(
+            container.blocking_connection_for_address("primary1"),
+            Some(1)
+        );
+        assert_eq!(container.blocking_connection_for_address("primary2"), None);
+    }
+
+    #[test]
+    fn blocking_connection_is_kept_apart_from_user_connections() {
+        let container = create_container();
+        assert_eq!(container.blocking_connection_for_address("primary1"), None);
+
+        assert!(container.set_blocking_connection_for_address("primary1", (0, None, None).into()));
+        assert!(!container.set_blocking_connection_for_address("unknown", (0, None, None).into()));
+
+        // The blocking connection isn't loaded at all, but it isn't used for other requests.
+        assert_eq!(
+            container.blocking_connection_for_address("primary1"),
+            Some(0)
+        );
+        assert_eq!(
+            container.connection_for_address("primary1"),
+            Some(("primary1".to_string(), 1))
+        );
+    }
 }
@@ -11,6 +11,7 @@ use crate::{
     ErrorKind, RedisError, RedisResult,
 };
 use std::net::SocketAddr;
+use std::time::Duration;
 
 use futures::prelude::*;
 use futures_util::{future::BoxFuture, join};
@@ -34,7 +35,7 @@ pub enum RefreshConnectionType {
 
 fn failed_management_connection<C>(
     addr: &str,
-    user_conn: ConnectionDetails<ConnectionFuture<C>>,
+    node: AsyncClusterNode<C>,
     err: RedisError,
 ) -> ConnectAndCheckResult<C>
 where
@@ -45,7 +46,10 @@ where
         addr, err
     );
     ConnectAndCheckResult::ManagementConnectionFailed {
-        node: AsyncClusterNode::new(user_conn, None),
+        node: ClusterNode {
+            management_connection: None,
+            ..node
+        },
         err,
     }
 }
@@ -93,6 +97,7 @@ where
 fn create_async_node<C>(
     user_conn: ConnectionDetails<C>,
     management_conn: Option<ConnectionDetails<C>>,
+    additional_user_conns: Vec<ConnectionDetails<C>>,
 ) -> AsyncClusterNode<C>
 where
     C: ConnectionLike + Connect + Send + Sync + 'static + Clone,
@@ -101,6 +106,83 @@ where
         user_conn.into_future(),
         management_conn.map(|conn| conn.into_future()),
     )
+    .with_additional_user_connections(
+        additional_user_conns
+            .into_iter()
+            .map(|conn| conn.into_future())
+            .collect(),
+    )
+}
+
+/// Creates the user connections to the node beyond the first one, according to `params.connections_per_node`.
+/// These connections don't hold pubsub subscriptions. Connections that fail are skipped, so the node keeps working with fewer connections.
+async fn create_additional_user_connections<C>(
+    addr: &str,
+    params: &ClusterParams,
+    socket_addr: Option<SocketAddr>,
+    glide_connection_options: &GlideConnectionOptions,
+) -> Vec<ConnectionDetails<C>>
+where
+    C: ConnectionLike + Connect + Send + Sync + 'static + Clone,
+{
+    let mut params = params.clone();
+    params.pubsub_subscriptions = None;
+    let connections = future::join_all((1..params.connections_per_node).map(|_| {
+        create_and_setup_user_connection(
+            addr,
+            params.clone(),
+            socket_addr,
+            glide_connection_options.clone(),
+        )
+    }))
+    .await;
+    connections
+        .into_iter()
+        .filter_map(|result| match result {
+            Ok(connection) => Some(connection),
+            Err(err) => {
+                warn!(
+                    "Failed to create an additional user connection for node `{:?}`. Error: `{:?}`",
+                    addr, err
+                );
+                None
+            }
+        })
+        .collect()
+}
+
+/// Creates the connection dedicated to blocking commands in the node at `addr`.
+/// The connection doesn't hold pubsub subscriptions. Failed attempts are retried after each of the `backoff_durations`.
+pub(crate) async fn create_blocking_connection<C>(
+    addr: &str,
+    params: &ClusterParams,
+    glide_connection_options: GlideConnectionOptions,
+    mut backoff_durations: impl Iterator<Item = Duration>,
+) -> RedisResult<ConnectionDetails<C>>
+where
+    C: ConnectionLike + Connect + Send + Sync + 'static + Clone,
+{
+    let mut params = params.clone();
+    params.pubsub_subscriptions = None;
+    loop {
+        let result = create_and_setup_user_connection(
+            addr,
+            params.clone(),
+            None,
+            glide_connection_options.clone(),
+        )
+        .await;
+        match (result, backoff_durations.next()) {
+            (Err(err), Some(backoff_duration)) => {
+                warn!(
+                    "Failed to create the blocking connection for node `{:?}`, retrying in {:?}. Error: `{:?}`",
+                    addr, backoff_duration, err
+                );
+                tokio::time::sleep(backoff_duration).await;
+            }
+            (result, _) => return result,
+        }
+    }
 }
 
 pub(crate) async fn connect_and_check_all_connections<C>(
@@ -112,7 +194,7 @@ pub(crate) async fn connect_and_check_all_connections<C>(
 where
     C: ConnectionLike + Connect + Send + Sync + 'static + Clone,
 {
-    match future::join(
+    match future::join3(
         // User connection
         create_connection(
             addr,
@@ -127,12 +209,14 @@ where
             params.clone(),
             socket_addr,
             true,
-            glide_connection_options,
+            glide_connection_options.clone(),
         ),
+        // Additional user connections
+        create_additional_user_connections(addr, &params, socket_addr, &glide_connection_options),
     )
     .await
     {
-        (Ok(conn_1), Ok(conn_2)) => {
+        (Ok(conn_1), Ok(conn_2), additional_user_conns) => {
             // Both connections were successfully established
             let mut user_conn: ConnectionDetails<C> = conn_1;
             let mut management_conn: ConnectionDetails<C> = conn_2;
@@ -143,18 +227,28 @@ where
                 Ok(_) => ConnectAndCheckResult::Success(create_async_node(
                     user_conn,
                     Some(management_conn),
+                    additional_user_conns,
                 )),
-                Err(err) => failed_management_connection(addr, user_conn.into_future(), err),
+                Err(err) => failed_management_connection(
+                    addr,
+                    create_async_node(user_conn, None, additional_user_conns),
+                    err,
+                ),
             }
         }
-        (Ok(mut connection), Err(err)) | (Err(err), Ok(mut connection)) => {
+        (Ok(mut connection), Err(err), additional_user_conns)
+        | (Err(err), Ok(mut connection), additional_user_conns) => {
             // Only a single connection was successfully established. Use it for the user connection
             match setup_user_connection(&mut connection, params).await {
-                Ok(_) => failed_management_connection(addr, connection.into_future(), err),
+                Ok(_) => failed_management_connection(
+                    addr,
+                    create_async_node(connection, None, additional_user_conns),
+                    err,
+                ),
                 Err(err) => err.into(),
             }
         }
-        (Err(err_1), Err(err_2)) => {
+        (Err(err_1), Err(err_2), _) => {
             // Neither of the connections succeeded.
             RedisError::from((
                 ErrorKind::IoError,
@@ -193,20 +287,22 @@ where
             discover_az,
             connection_timeout: Some(params.connection_timeout),
             connection_retry_strategy: None,
+            client_tracking: None,
+            credentials_provider: params.credentials_provider.clone(),
         },
     )
     .await
     {
-        Err(conn_err) => failed_management_connection(addr, prev_node.user_connection, conn_err),
+        Err(conn_err) => failed_management_connection(addr, prev_node, conn_err),
 
         Ok(mut connection) => {
             if let Err(err) = setup_management_connection(&mut connection.conn).await {
-                return failed_management_connection(addr, prev_node.user_connection, err);
+                return failed_management_connection(addr, prev_node, err);
             }
 
             ConnectAndCheckResult::Success(ClusterNode {
-                user_connection: prev_node.user_connection,
                 management_connection: Some(connection.into_future()),
+                ..prev_node
             })
         }
     }
@@ -279,19 +375,34 @@ where
 {
     match conn_type {
         RefreshConnectionType::OnlyUserConnection => {
-            let user_conn = match create_and_setup_user_connection(
-                addr,
-                params.clone(),
-                socket_addr,
-                glide_connection_options,
+            let (user_conn, additional_user_conns) = future::join(
+                create_and_setup_user_connection(
+                    addr,
+                    params.clone(),
+                    socket_addr,
+                    glide_connection_options.clone(),
+                ),
+                create_additional_user_connections(
+                    addr,
+                    &params,
+                    socket_addr,
+                    &glide_connection_options,
+                ),
             )
-            .await
-            {
+            .await;
+            let user_conn = match user_conn {
                 Ok(tuple) => tuple,
                 Err(err) => return err.into(),
             };
             let management_conn = node.and_then(|node| node.management_connection);
-            AsyncClusterNode::new(user_conn.into_future(), management_conn).into()
+            AsyncClusterNode::new(user_conn.into_future(), management_conn)
+                .with_additional_user_connections(
+                    additional_user_conns
+                        .into_iter()
+                        .map(|conn| conn.into_future())
+                        .collect(),
+                )
+                .into()
         }
         RefreshConnectionType::OnlyManagementConnection => {
             // Refreshing only the management connection requires the node to exist alongside a user connection. Otherwise, refresh all connections.
@@ -462,8 +573,13 @@ where
             if !check_user_connection {
                 return false;
             }
-            let conn = node.user_connection.conn.clone();
-            check(conn, timeout, "user").await
+            let user_conns = std::iter::once(&node.user_connection)
+                .chain(node.additional_user_connections.iter())
+                .map(|conn| check(conn.conn.clone(), timeout, "user"));
+            future::join_all(user_conns)
+                .await
+                .into_iter()
+                .any(|failed| failed)
         },
     );
 
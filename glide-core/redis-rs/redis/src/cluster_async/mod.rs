@@ -57,11 +57,11 @@ use std::{
     net::{IpAddr, SocketAddr},
     pin::Pin,
     sync::{
-        atomic::{self, AtomicUsize, Ordering},
+        atomic::{self, AtomicBool, AtomicUsize, Ordering},
         Arc, Mutex,
     },
     task::{self, Poll},
-    time::SystemTime,
+    time::{Instant, SystemTime},
 };
 use strum_macros::Display;
 #[cfg(feature = "tokio-comp")]
@@ -73,19 +73,21 @@ use telemetrylib::{GlideOpenTelemetry, Telemetry};
 
 use crate::{
     aio::{get_socket_addrs, ConnectionLike, MultiplexedConnection, Runtime},
-    cluster::slot_cmd,
+    circuit_breaker::{self, TimedOutFlag},
+    cluster::{shards_cmd, slot_cmd},
     cluster_async::connections_logic::{
         get_host_and_port_from_addr, get_or_create_conn, ConnectionFuture, RefreshConnectionType,
     },
     cluster_client::{ClusterParams, RetryParams},
     cluster_routing::{
-        self, MultipleNodeRoutingInfo, Redirect, ResponsePolicy, Route, SingleNodeRoutingInfo,
-        SlotAddr,
+        self, ConnectionErrorRetryPolicy, MultipleNodeRoutingInfo, Redirect, ResponsePolicy, Route,
+        SingleNodeRoutingInfo, SlotAddr,
     },
-    connection::{PubSubSubscriptionInfo, PubSubSubscriptionKind},
+    connection::{PubSubChannelOrPattern, PubSubSubscriptionInfo, PubSubSubscriptionKind},
     push_manager::PushInfo,
-    Cmd, ConnectionInfo, ErrorKind, IntoConnectionInfo, RedisError, RedisFuture, RedisResult,
-    Value,
+    tls::TlsConnParams,
+    CircuitBreakerAttempt, Cmd, ConnectionInfo, ErrorKind, IntoConnectionInfo, NodeRequest,
+    ReadHedging, RedisError, RedisFuture, RedisResult, Value,
 };
 use futures::stream::{FuturesUnordered, StreamExt};
 use std::time::Duration;
@@ -107,7 +109,7 @@ use std::sync::RwLock as StdRwLock;
 use tokio::sync::{
     mpsc,
     oneshot::{self, Receiver},
-    RwLock as TokioRwLock,
+    Mutex as TokioMutex, RwLock as TokioRwLock,
 };
 use tracing::{debug, info, trace, warn};
 
@@ -218,6 +220,7 @@ where
             .send(Message {
                 cmd: CmdArg::ClusterScan { cluster_scan_args },
                 sender,
+                timed_out: TimedOutFlag::default(),
             })
             .await
             .map_err(|e| {
@@ -236,7 +239,9 @@ where
             })
             .map(|response| match response {
                 Response::ClusterScanResult(new_scan_state_ref, key) => (new_scan_state_ref, key),
-                Response::Single(_) | Response::Multiple(_) => unreachable!(),
+                Response::Single(_) | Response::Multiple(_) | Response::Subscriptions { .. } => {
+                    unreachable!()
+                }
             })
     }
 
@@ -248,6 +253,7 @@ where
     ) -> RedisResult<Value> {
         trace!("route_command");
         let (sender, receiver) = oneshot::channel();
+        let (_timed_out_guard, timed_out) = circuit_breaker::timed_out_flag();
         self.0
             .send(Message {
                 cmd: CmdArg::Cmd {
@@ -255,6 +261,7 @@ where
                     routing: routing.into(),
                 },
                 sender,
+                timed_out,
             })
             .await
             .map_err(|e| {
@@ -275,7 +282,11 @@ where
             })
             .map(|response| match response {
                 Response::Single(value) => value,
-                Response::ClusterScanResult(..) | Response::Multiple(_) => unreachable!(),
+                Response::ClusterScanResult(..)
+                | Response::Multiple(_)
+                | Response::Subscriptions { .. } => {
+                    unreachable!()
+                }
             })
     }
 
@@ -293,6 +304,7 @@ where
         pipeline_retry_strategy: Option<PipelineRetryStrategy>,
     ) -> RedisResult<Vec<Value>> {
         let (sender, receiver) = oneshot::channel();
+        let (_timed_out_guard, timed_out) = circuit_breaker::timed_out_flag();
         self.0
             .send(Message {
                 cmd: CmdArg::Pipeline {
@@ -304,6 +316,7 @@ where
                     pipeline_retry_strategy: pipeline_retry_strategy.unwrap_or_default(),
                 },
                 sender,
+                timed_out,
             })
             .await
             .map_err(|err| {
@@ -320,7 +333,11 @@ where
             })
             .map(|response| match response {
                 Response::Multiple(values) => values,
-                Response::ClusterScanResult(..) | Response::Single(_) => unreachable!(),
+                Response::ClusterScanResult(..)
+                | Response::Single(_)
+                | Response::Subscriptions { .. } => {
+                    unreachable!()
+                }
             })
     }
     /// Update the password used to authenticate with all cluster servers
@@ -332,6 +349,79 @@ where
             .await
     }
 
+    /// Update the TLS parameters used to connect to all cluster servers.
+    /// The new parameters are used by every connection created from now on.
+    /// If `reconnect` is true, the existing connections are also replaced in the background. Each existing
+    /// connection keeps serving requests until its replacement is established, and is kept if the replacement fails.
+    pub async fn update_tls_params(
+        &mut self,
+        tls_params: TlsConnParams,
+        reconnect: bool,
+    ) -> RedisResult<Value> {
+        self.route_operation_request(Operation::UpdateTlsParams {
+            tls_params,
+            reconnect,
+        })
+        .await
+    }
+
+    /// Subscribes to the given channels or patterns at runtime. Requires RESP3.
+    /// The subscriptions are added to the desired subscriptions state, so they are re-established after reconnects and slot migrations.
+    pub async fn subscribe(
+        &mut self,
+        kind: PubSubSubscriptionKind,
+        channels_or_patterns: Vec<PubSubChannelOrPattern>,
+    ) -> RedisResult<Value> {
+        self.route_operation_request(Operation::Subscribe {
+            kind,
+            channels_or_patterns,
+        })
+        .await
+    }
+
+    /// Unsubscribes from the given channels or patterns at runtime, and removes them from the desired subscriptions state.
+    pub async fn unsubscribe(
+        &mut self,
+        kind: PubSubSubscriptionKind,
+        channels_or_patterns: Vec<PubSubChannelOrPattern>,
+    ) -> RedisResult<Value> {
+        self.route_operation_request(Operation::Unsubscribe {
+            kind,
+            channels_or_patterns,
+        })
+        .await
+    }
+
+    /// Returns the desired subscriptions, followed by the subscriptions that are currently assigned to a connected node.
+    pub async fn get_subscriptions(
+        &mut self,
+    ) -> RedisResult<(PubSubSubscriptionInfo, PubSubSubscriptionInfo)> {
+        let (sender, receiver) = oneshot::channel();
+        self.0
+            .send(Message {
+                cmd: CmdArg::OperationRequest(Operation::GetSubscriptions),
+                sender,
+                timed_out: TimedOutFlag::default(),
+            })
+            .await
+            .map_err(|_| RedisError::from(io::Error::from(io::ErrorKind::BrokenPipe)))?;
+
+        receiver
+            .await
+            .unwrap_or_else(|err| {
+                Err(RedisError::from(io::Error::new(
+                    io::ErrorKind::BrokenPipe,
+                    err.to_string(),
+                )))
+            })
+            .map(|response| match response {
+                Response::Subscriptions { desired, current } => (desired, current),
+                Response::Single(_) | Response::ClusterScanResult(..) | Response::Multiple(_) => {
+                    unreachable!()
+                }
+            })
+    }
+
     /// Get the username used to authenticate with all cluster servers
     pub async fn get_username(&mut self) -> RedisResult<Value> {
         self.route_operation_request(Operation::GetUsername).await
@@ -347,6 +437,7 @@ where
             .send(Message {
                 cmd: CmdArg::OperationRequest(operation_request),
                 sender,
+                timed_out: TimedOutFlag::default(),
             })
             .await
             .map_err(|_| RedisError::from(io::Error::from(io::ErrorKind::BrokenPipe)))?;
@@ -361,7 +452,11 @@ where
             })
             .map(|response| match response {
                 Response::Single(values) => values,
-                Response::ClusterScanResult(..) | Response::Multiple(_) => unreachable!(),
+                Response::ClusterScanResult(..)
+                | Response::Multiple(_)
+                | Response::Subscriptions { .. } => {
+                    unreachable!()
+                }
             })
     }
 }
@@ -413,6 +508,10 @@ pub(crate) struct InnerCore<C> {
     subscriptions_by_address: TokioRwLock<HashMap<String, PubSubSubscriptionInfo>>,
     unassigned_subscriptions: TokioRwLock<PubSubSubscriptionInfo>,
     glide_connection_options: GlideConnectionOptions,
+    /// Set once a node rejected `CLUSTER SHARDS`, so the topology is only queried through `CLUSTER SLOTS` from then on.
+    cluster_shards_unsupported: AtomicBool,
+    /// Held while the connection dedicated to blocking commands is created in the node at each address.
+    blocking_connection_locks: DashMap<String, Arc<TokioMutex<()>>>,
 }
 
 pub(crate) type Core<C> = Arc<InnerCore<C>>;
@@ -444,6 +543,46 @@ where
             .map_err(|_| RedisError::from((ErrorKind::ClientError, MUTEX_WRITE_ERR)))
     }
 
+    /// Starts a request to the node in `address`, which is tracked by the node's circuit breaker if breakers are enabled.
+    /// Fails with `CircuitBreakerOpen` if requests to the node should fail fast.
+    fn circuit_breaker_attempt(&self, address: &str) -> RedisResult<Option<CircuitBreakerAttempt>> {
+        let Some(failure_threshold) =
+            self.get_cluster_param(|params| params.circuit_breaker_threshold)?
+        else {
+            return Ok(None);
+        };
+        self.conn_lock
+            .read()
+            .expect(MUTEX_READ_ERR)
+            .circuit_breaker(address, failure_threshold)
+            .try_acquire()
+            .map(Some)
+    }
+
+    /// Returns the hedging of reads if `cmd` is a read to `route` that may be hedged, i.e. one that may be served by replicas.
+    fn read_hedging_for_route(&self, route: &Route, cmd: &Cmd) -> Option<Arc<ReadHedging>> {
+        let params = self.cluster_params.read().expect(MUTEX_READ_ERR);
+        let read_hedging = params.read_hedging.as_ref()?;
+        let served_by_replicas = match route.slot_addr() {
+            SlotAddr::Master => false,
+            SlotAddr::ReplicaOptional => !matches!(
+                params.read_from_replicas,
+                crate::cluster_slotmap::ReadFromReplicaStrategy::AlwaysFromPrimary
+            ),
+            SlotAddr::ReplicaRequired => true,
+        };
+        (served_by_replicas && cluster_routing::is_readonly(cmd)).then(|| read_hedging.clone())
+    }
+
+    /// Translates an address announced by the cluster, e.g. in a redirect, into an address that is reachable by the client.
+    fn reachable_address(&self, address: String) -> String {
+        self.cluster_params
+            .read()
+            .expect(MUTEX_READ_ERR)
+            .address_remap
+            .apply(address)
+    }
+
     // return epoch of node
     pub(crate) async fn address_epoch(&self, node_address: &str) -> Result<u64, RedisError> {
         let command = cmd("CLUSTER").arg("INFO").to_owned();
@@ -655,7 +794,20 @@ enum CmdArg<C> {
 #[derive(Clone)]
 enum Operation {
     UpdateConnectionPassword(Option<String>),
+    UpdateTlsParams {
+        tls_params: TlsConnParams,
+        reconnect: bool,
+    },
     GetUsername,
+    Subscribe {
+        kind: PubSubSubscriptionKind,
+        channels_or_patterns: Vec<PubSubChannelOrPattern>,
+    },
+    Unsubscribe {
+        kind: PubSubSubscriptionKind,
+        channels_or_patterns: Vec<PubSubChannelOrPattern>,
+    },
+    GetSubscriptions,
 }
 
 fn boxed_sleep(duration: Duration) -> BoxFuture<'static, ()> {
@@ -667,6 +819,10 @@ pub(crate) enum Response {
     Single(Value),
     ClusterScanResult(ScanStateRC, Vec<Value>),
     Multiple(Vec<Value>),
+    Subscriptions {
+        desired: PubSubSubscriptionInfo,
+        current: PubSubSubscriptionInfo,
+    },
 }
 
 #[derive(Debug)]
@@ -706,6 +862,7 @@ impl RedirectNode {
 struct Message<C: Sized> {
     cmd: CmdArg<C>,
     sender: oneshot::Sender<RedisResult<Response>>,
+    timed_out: TimedOutFlag,
 }
 
 enum RecoverFuture {
@@ -837,13 +994,41 @@ pin_project! {
 
 struct PendingRequest<C> {
     retry: u32,
+    /// The number of times that the request was retried after its connection dropped mid-request.
+    connection_error_retries: u32,
     sender: oneshot::Sender<RedisResult<Response>>,
+    /// Raised if the caller stopped waiting for the response because the request timed out.
+    timed_out: TimedOutFlag,
     info: RequestInfo<C>,
 }
 
+impl<C> PendingRequest<C> {
+    /// Returns true, and consumes a retry of the request's budget, if the request may be sent again after `err`
+    /// dropped its connection mid-request.
+    fn retry_on_connection_error(
+        &mut self,
+        policy: &ConnectionErrorRetryPolicy,
+        err: &RedisError,
+    ) -> bool {
+        if !err.is_connection_dropped() || self.connection_error_retries >= policy.max_retries() {
+            return false;
+        }
+        // Only single commands are retried, since a pipeline may have been partially executed.
+        let CmdArg::Cmd { cmd, .. } = &self.info.cmd else {
+            return false;
+        };
+        if !policy.is_idempotent(&**cmd) {
+            return false;
+        }
+        self.connection_error_retries += 1;
+        true
+    }
+}
+
 pin_project! {
     struct Request<C> {
         retry_params: RetryParams,
+        connection_error_retry: Option<Arc<ConnectionErrorRetryPolicy>>,
         request: Option<PendingRequest<C>>,
         #[pin]
         future: RequestState<BoxFuture<'static, OperationResult>>,
@@ -884,7 +1069,14 @@ impl<C> Future for Request<C> {
         let mut this = self.as_mut().project();
         // If the sender is closed, the caller is no longer waiting for the reply, and it is ambiguous
         // whether they expect the side-effect of the request to happen or not.
-        if this.request.is_none() || this.request.as_ref().unwrap().sender.is_closed() {
+        // Polling for the closing also wakes the request when the caller stops waiting, so it's dropped promptly.
+        let Some(request) = this.request.as_mut() else {
+            return Poll::Ready(Next::Done);
+        };
+        if request.sender.poll_closed(cx).is_ready() {
+            if request.timed_out.is_raised() {
+                circuit_breaker::drop_timed_out_requests(|| this.future.set(RequestState::None));
+            }
             return Poll::Ready(Next::Done);
         }
         let future = match this.future.as_mut().project() {
@@ -920,6 +1112,15 @@ impl<C> Future for Request<C> {
                 Next::Done.into()
             }
             Err((target, err)) => {
+                if let OperationTarget::Node { address } = &target {
+                    match err.retry_method() {
+                        RetryMethod::MovedRedirect => {
+                            Telemetry::record_node_moved_redirect(address)
+                        }
+                        RetryMethod::AskRedirect => Telemetry::record_node_ask_redirect(address),
+                        _ => {}
+                    }
+                }
                 let request = this.request.as_mut().unwrap();
                 // TODO - would be nice if we didn't need to repeat this code twice, with & without retries.
                 if request.retry >= this.retry_params.number_of_retries {
@@ -1037,7 +1238,10 @@ impl<C> Future for Request<C> {
                         request.info.reset_routing();
                         warn!("disconnected from {:?}", address);
                         let should_retry =
-                            matches!(err.retry_method(), RetryMethod::ReconnectAndRetry);
+                            matches!(err.retry_method(), RetryMethod::ReconnectAndRetry)
+                                || this.connection_error_retry.as_ref().is_some_and(|policy| {
+                                    request.retry_on_connection_error(policy, &err)
+                                });
                         Next::Reconnect {
                             request: should_retry.then_some(request),
                             target: address,
@@ -1116,6 +1320,8 @@ where
             discover_az,
             connection_timeout: Some(cluster_params.connection_timeout),
             connection_retry_strategy: Some(connection_retry_strategy),
+            client_tracking: cluster_params.client_tracking,
+            credentials_provider: cluster_params.credentials_provider.clone(),
         };
 
         let connections = Self::create_initial_connections(
@@ -1147,6 +1353,8 @@ where
             ),
             subscriptions_by_address: TokioRwLock::new(Default::default()),
             glide_connection_options,
+            cluster_shards_unsupported: AtomicBool::new(false),
+            blocking_connection_locks: DashMap::new(),
         });
         let mut connection = ClusterConnInner {
             inner,
@@ -1337,6 +1545,12 @@ where
     // The function does not discover the topology from the cluster and assumes the cached topology is valid.
     // In addition, the validation is done by peeking at the state of the underlying transport w/o overhead of additional commands to server.
     async fn validate_all_user_connections(inner: Arc<InnerCore<C>>) {
+        // Let a trial request through to the nodes that failed fast, to test whether they recovered.
+        inner
+            .conn_lock
+            .read()
+            .expect(MUTEX_READ_ERR)
+            .half_open_circuit_breakers();
         let mut all_valid_conns = HashMap::new();
         // prep connections and clean out these w/o assigned slots, as we might have established connections to unwanted hosts
         let mut nodes_to_delete = Vec::new();
@@ -1363,12 +1577,15 @@ where
 
         // identify nodes with closed connection
         let mut addrs_to_refresh = HashSet::new();
+        let mut open_conns = Vec::with_capacity(all_valid_conns.len());
         for (addr, con_fut) in &all_valid_conns {
             let con = con_fut.clone().await;
             // connection object might be present despite the transport being closed
             if con.is_closed() {
                 // transport is closed, need to refresh
                 addrs_to_refresh.insert(addr.clone());
+            } else {
+                open_conns.push((addr.clone(), con));
             }
         }
 
@@ -1390,6 +1607,36 @@ where
             )
             .await;
         }
+
+        if matches!(
+            inner.get_cluster_param(|params| params.read_from_replicas.clone()),
+            Ok(crate::cluster_slotmap::ReadFromReplicaStrategy::LowestLatency)
+        ) {
+            Self::measure_nodes_latency(inner, open_conns).await;
+        }
+    }
+
+    // Measures the round-trip time of a PING to each of the given nodes, and updates the nodes' latency moving averages.
+    // Nodes that fail to respond within the connection timeout are skipped, since the validation will handle broken connections.
+    async fn measure_nodes_latency(inner: Arc<InnerCore<C>>, connections: Vec<(String, C)>) {
+        let connection_timeout = inner
+            .get_cluster_param(|params| params.connection_timeout)
+            .expect(MUTEX_READ_ERR);
+        let measurements = connections.into_iter().map(|(addr, mut con)| async move {
+            let start = Instant::now();
+            match tokio::time::timeout(connection_timeout, con.req_packed_command(&cmd("PING")))
+                .await
+            {
+                Ok(Ok(_)) => Some((addr, start.elapsed())),
+                _ => None,
+            }
+        });
+        let measurements = futures::future::join_all(measurements).await;
+
+        let connections_container = inner.conn_lock.read().expect(MUTEX_READ_ERR);
+        for (addr, round_trip_time) in measurements.into_iter().flatten() {
+            connections_container.record_latency(&addr, round_trip_time);
+        }
     }
 
     // Creates refresh tasks and await on the tasks' notifier.
@@ -1457,6 +1704,8 @@ where
                 .read()
                 .expect(MUTEX_READ_ERR)
                 .remove_node(&address);
+            // Connecting to a node that the client wasn't connected to isn't a reconnection.
+            let is_reconnect = node_option.is_some();
 
             if !check_existing_conn {
                 node_option = None;
@@ -1502,6 +1751,9 @@ where
 
                     match node_result {
                         Ok(_) => {
+                            if is_reconnect {
+                                Telemetry::record_node_reconnect(&address_clone_for_task);
+                            }
                             break;
                         }
                         Err(ref err) => {
@@ -1580,6 +1832,65 @@ where
         notifiers
     }
 
+    // Replaces the connections to all known nodes with new connections, created with the current cluster params.
+    // Unlike `refresh_and_update_connections`, an existing node is only replaced once its new connections are established,
+    // so requests keep being served during the replacement. Nodes that fail to reconnect keep their existing connections.
+    async fn replace_all_connections(inner: Arc<InnerCore<C>>) {
+        let addresses: Vec<String> = inner
+            .conn_lock
+            .read()
+            .expect(MUTEX_READ_ERR)
+            .connection_map()
+            .iter()
+            .map(|item| item.key().clone())
+            .collect();
+        debug!("Replacing the connections to {:?}", addresses);
+
+        futures::future::join_all(addresses.into_iter().map(|address| {
+            let inner = inner.clone();
+            async move {
+                let mut cluster_params = inner
+                    .cluster_params
+                    .read()
+                    .expect(MUTEX_READ_ERR)
+                    .clone();
+                cluster_params.pubsub_subscriptions = inner
+                    .subscriptions_by_address
+                    .read()
+                    .await
+                    .get(&address)
+                    .cloned();
+
+                match connect_and_check(
+                    &address,
+                    cluster_params,
+                    None,
+                    RefreshConnectionType::AllConnections,
+                    None,
+                    inner.glide_connection_options.clone(),
+                )
+                .await
+                .get_node()
+                {
+                    Ok(node) => {
+                        inner
+                            .conn_lock
+                            .read()
+                            .expect(MUTEX_READ_ERR)
+                            .replace_or_add_connection_for_address(&address, node);
+                    }
+                    Err(err) => {
+                        warn!(
+                            "Failed to replace the connection to node {}, keeping the existing connection. Error: `{:?}`",
+                            address, err
+                        );
+                    }
+                }
+            }
+        }))
+        .await;
+    }
+
     fn spawn_refresh_slots_task(
         inner: Arc<InnerCore<C>>,
         policy: &RefreshPolicy,
@@ -1617,7 +1928,9 @@ where
         // Helper: extract a single Value from a Response::Single
         let extract_result = |response| match response {
             Response::Single(value) => value,
-            Response::Multiple(_) | Response::ClusterScanResult(_, _) => unreachable!(
+            Response::Multiple(_)
+            | Response::ClusterScanResult(_, _)
+            | Response::Subscriptions { .. } => unreachable!(
                 "aggregate_results only handles `Response::Single` for multi-node commands"
             ),
         };
@@ -2010,11 +2323,49 @@ where
         }
         in_progress.store(false, Ordering::Relaxed);
 
+        if res.is_ok() {
+            // Spawned, so a slow or unsupported `COMMAND` doesn't delay the resubscription and the recovery.
+            tokio::spawn(Self::refresh_command_routes(inner.clone()));
+        }
         Self::refresh_pubsub_subscriptions(inner).await;
 
         res
     }
 
+    /// Discovers the routes of the commands from the `COMMAND` reply of a random node, if the command routing table
+    /// discovers routes and they weren't discovered for the current topology yet.
+    /// On failure the previous routes are kept, and the discovery is retried on the next slot refresh.
+    async fn refresh_command_routes(inner: Arc<InnerCore<C>>) {
+        let Some(table) = inner
+            .get_cluster_param(|params| params.command_routing_table.clone())
+            .expect(MUTEX_READ_ERR)
+        else {
+            return;
+        };
+        let (topology_hash, random_conn) = {
+            let conns_read_guard = inner.conn_lock.read().expect(MUTEX_READ_ERR);
+            (
+                conns_read_guard.get_current_topology_hash(),
+                conns_read_guard.random_connections(1, ConnectionType::PreferManagement),
+            )
+        };
+        if !table.should_discover(topology_hash) {
+            return;
+        }
+        let Some((address, conn)) = random_conn.and_then(|conns| conns.into_iter().next()) else {
+            return;
+        };
+        let mut conn = conn.await;
+        let result = conn
+            .req_packed_command(&crate::cmd::cmd("COMMAND"))
+            .await
+            .and_then(|reply| table.load_command_info(&reply, topology_hash));
+        match result {
+            Ok(()) => debug!("Discovered the command routes from {address}"),
+            Err(err) => warn!("Failed to discover the command routes from {address}: {err}"),
+        }
+    }
+
     /// Determines if the cluster topology has changed and refreshes slots and subscriptions if needed.
     /// Returns `RedisResult` with `true` if changes were detected and slots were refreshed,
     /// or `false` if no changes were found. Raises an error if refreshing the topology fails.
@@ -2076,10 +2427,28 @@ where
         }
     }
 
+    // Returns true if the connections use RESP2, either since it was configured, or since a node rejected RESP3
+    // when the protocol was negotiated with it.
+    fn uses_resp2(inner: &InnerCore<C>) -> bool {
+        match inner.cluster_params.read().expect(MUTEX_READ_ERR).protocol {
+            crate::types::ProtocolVersion::RESP2 => true,
+            crate::types::ProtocolVersion::RESP3 => false,
+            crate::types::ProtocolVersion::Auto => inner
+                .conn_lock
+                .read()
+                .expect(MUTEX_READ_ERR)
+                .connection_map()
+                .iter()
+                .any(|node| {
+                    node.user_connection.conn.peek().is_some_and(|conn| {
+                        conn.get_protocol() == crate::types::ProtocolVersion::RESP2
+                    })
+                }),
+        }
+    }
+
     async fn refresh_pubsub_subscriptions(inner: Arc<InnerCore<C>>) {
-        if inner.cluster_params.read().expect(MUTEX_READ_ERR).protocol
-            != crate::types::ProtocolVersion::RESP3
-        {
+        if Self::uses_resp2(&inner) {
             return;
         }
 
@@ -2162,6 +2531,179 @@ where
         }
     }
 
+    fn check_resp3_for_subscriptions(core: &Core<C>) -> Result<(), (OperationTarget, RedisError)> {
+        if Self::uses_resp2(core) {
+            return Err((
+                OperationTarget::FatalError,
+                RedisError::from((
+                    ErrorKind::InvalidClientConfig,
+                    "RESP3 is required for runtime subscriptions",
+                )),
+            ));
+        }
+        Ok(())
+    }
+
+    // Assigns the channels that aren't subscribed yet to the nodes that serve their slots, and subscribes to them on these nodes' connections.
+    // Channels whose slots aren't served by a known node are left unassigned, for `refresh_pubsub_subscriptions` to assign them later.
+    async fn add_subscriptions(
+        inner: Arc<InnerCore<C>>,
+        kind: PubSubSubscriptionKind,
+        channels_or_patterns: Vec<PubSubChannelOrPattern>,
+    ) -> RedisResult<()> {
+        let mut subs_to_send: HashMap<String, Vec<PubSubChannelOrPattern>> = HashMap::new();
+        {
+            let mut subs_by_address_guard = inner.subscriptions_by_address.write().await;
+            let mut unassigned_subs_guard = inner.unassigned_subscriptions.write().await;
+            let conns_read_guard = inner.conn_lock.read().expect(MUTEX_READ_ERR);
+            for channel_pattern in channels_or_patterns {
+                let already_subscribed = unassigned_subs_guard
+                    .get(&kind)
+                    .is_some_and(|channels_patterns| channels_patterns.contains(&channel_pattern))
+                    || subs_by_address_guard.values().any(|address_subs| {
+                        address_subs.get(&kind).is_some_and(|channels_patterns| {
+                            channels_patterns.contains(&channel_pattern)
+                        })
+                    });
+                if already_subscribed {
+                    continue;
+                }
+                let slot = get_slot(&channel_pattern);
+                match conns_read_guard.connection_for_route(&Route::new(slot, SlotAddr::Master)) {
+                    Some((address, _)) => {
+                        subs_by_address_guard
+                            .entry(address.clone())
+                            .or_default()
+                            .entry(kind)
+                            .or_default()
+                            .insert(channel_pattern.clone());
+                        subs_to_send
+                            .entry(address)
+                            .or_default()
+                            .push(channel_pattern);
+                    }
+                    None => {
+                        unassigned_subs_guard
+                            .entry(kind)
+                            .or_default()
+                            .insert(channel_pattern);
+                    }
+                }
+            }
+        }
+
+        for (address, channels_patterns) in subs_to_send {
+            Self::send_subscription_commands(
+                &inner,
+                address,
+                kind.subscribe_command(),
+                channels_patterns,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    // Removes the channels from the subscriptions state, and unsubscribes from them on the connections of the nodes they were assigned to.
+    async fn remove_subscriptions(
+        inner: Arc<InnerCore<C>>,
+        kind: PubSubSubscriptionKind,
+        channels_or_patterns: Vec<PubSubChannelOrPattern>,
+    ) -> RedisResult<()> {
+        let mut subs_to_send: HashMap<String, Vec<PubSubChannelOrPattern>> = HashMap::new();
+        {
+            let mut subs_by_address_guard = inner.subscriptions_by_address.write().await;
+            let mut unassigned_subs_guard = inner.unassigned_subscriptions.write().await;
+            subs_by_address_guard.retain(|address, address_subs| {
+                if let Some(channels_patterns) = address_subs.get_mut(&kind) {
+                    for channel_pattern in &channels_or_patterns {
+                        if channels_patterns.remove(channel_pattern) {
+                            subs_to_send
+                                .entry(address.clone())
+                                .or_default()
+                                .push(channel_pattern.clone());
+                        }
+                    }
+                    if channels_patterns.is_empty() {
+                        address_subs.remove(&kind);
+                    }
+                }
+                !address_subs.is_empty()
+            });
+            if let Some(channels_patterns) = unassigned_subs_guard.get_mut(&kind) {
+                channels_patterns
+                    .retain(|channel_pattern| !channels_or_patterns.contains(channel_pattern));
+                if channels_patterns.is_empty() {
+                    unassigned_subs_guard.remove(&kind);
+                }
+            }
+        }
+
+        for (address, channels_patterns) in subs_to_send {
+            Self::send_subscription_commands(
+                &inner,
+                address,
+                kind.unsubscribe_command(),
+                channels_patterns,
+            )
+            .await?;
+        }
+        Ok(())
+    }
+
+    // Sends a subscription command per channel or pattern to the user connection of the node at `address`, which holds the node's subscriptions.
+    // If the node has no connection, there's nothing to send, since a new connection is set up with the node's current subscriptions.
+    async fn send_subscription_commands(
+        inner: &InnerCore<C>,
+        address: String,
+        command_name: &str,
+        channels_or_patterns: Vec<PubSubChannelOrPattern>,
+    ) -> RedisResult<()> {
+        let connection = inner
+            .conn_lock
+            .read()
+            .expect(MUTEX_READ_ERR)
+            .connection_for_address(&address);
+        let Some((_, connection)) = connection else {
+            return Ok(());
+        };
+        let mut connection = connection.await;
+        for channel_pattern in channels_or_patterns {
+            let mut command = cmd(command_name);
+            command.arg(channel_pattern);
+            connection.req_packed_command(&command).await?;
+        }
+        Ok(())
+    }
+
+    // Returns the desired subscriptions, and the subscriptions assigned to nodes that currently have a connection.
+    async fn get_subscriptions(
+        inner: Arc<InnerCore<C>>,
+    ) -> (PubSubSubscriptionInfo, PubSubSubscriptionInfo) {
+        let subs_by_address_guard = inner.subscriptions_by_address.read().await;
+        let unassigned_subs_guard = inner.unassigned_subscriptions.read().await;
+        let conns_read_guard = inner.conn_lock.read().expect(MUTEX_READ_ERR);
+
+        let mut desired = unassigned_subs_guard.clone();
+        let mut current = PubSubSubscriptionInfo::new();
+        for (address, address_subs) in subs_by_address_guard.iter() {
+            let connected = conns_read_guard.connection_for_address(address).is_some();
+            for (kind, channels_patterns) in address_subs {
+                desired
+                    .entry(*kind)
+                    .or_default()
+                    .extend(channels_patterns.iter().cloned());
+                if connected {
+                    current
+                        .entry(*kind)
+                        .or_default()
+                        .extend(channels_patterns.iter().cloned());
+                }
+            }
+        }
+        (desired, current)
+    }
+
     /// Queries log2n nodes (where n represents the number of cluster nodes) to determine whether their
     /// topology view differs from the one currently stored in the connection manager.
     /// Returns true if change was detected, otherwise false.
@@ -2299,12 +2841,21 @@ where
         let read_from_replicas = inner
             .get_cluster_param(|params| params.read_from_replicas.clone())
             .expect(MUTEX_READ_ERR);
+        // Keep the measured latencies and the circuit breakers of the nodes that are still part of the topology
+        let node_latencies = mem::take(&mut write_guard.node_latencies);
+        let node_circuit_breakers = mem::take(&mut write_guard.node_circuit_breakers);
         *write_guard = ConnectionsContainer::new(
             new_slots,
             new_connections,
             read_from_replicas,
             topology_hash,
         );
+        node_latencies.retain(|addr, _| write_guard.connection_map().contains_key(addr));
+        write_guard.node_latencies = node_latencies;
+        // Nodes that are reconnecting aren't in the connection map, so the circuit breakers are kept by the topology.
+        let node_addresses = write_guard.slot_map.all_node_addresses();
+        node_circuit_breakers.retain(|addr, _| node_addresses.contains(addr));
+        write_guard.node_circuit_breakers = node_circuit_breakers;
         Ok(())
     }
 
@@ -2333,6 +2884,7 @@ where
         slot: u16,
         new_primary: Arc<String>,
     ) -> RedisResult<()> {
+        let new_primary = Arc::new(inner.reachable_address(new_primary.to_string()));
         let curr_shard_addrs = inner
             .conn_lock
             .read()
@@ -2414,7 +2966,9 @@ where
                             (Some(address.clone()), receiver),
                             Some(PendingRequest {
                                 retry: 0,
+                                connection_error_retries: 0,
                                 sender,
+                                timed_out: TimedOutFlag::default(),
                                 info: RequestInfo {
                                     cmd: CmdArg::Cmd {
                                         cmd,
@@ -2511,27 +3065,164 @@ where
         };
         trace!("route request to single node");
 
+        if let InternalSingleNodeRouting::SpecificNode(route) = &routing {
+            if let Some(read_hedging) = core.read_hedging_for_route(route, &cmd) {
+                return Self::try_hedged_cmd_request(cmd, *route, read_hedging, core).await;
+            }
+        }
+
         // if we reached this point, we're sending the command only to single node, and we need to find the
         // right connection to the node.
-        let (address, mut conn) = Self::get_connection(routing, core, Some(cmd.clone()))
+        let (address, mut conn) = Self::get_connection(routing, core.clone(), Some(cmd.clone()))
             .await
             .map_err(|err| (OperationTarget::NotFound, err))?;
-        conn.req_packed_command(&cmd)
-            .await
+        // Blocking commands legitimately wait for long periods, so they don't indicate whether the node is responsive.
+        let attempt = if cluster_routing::is_blocking(cmd.as_ref()) {
+            None
+        } else {
+            core.circuit_breaker_attempt(&address)
+                .map_err(|err| (address.clone().into(), err))?
+        };
+        let node_request = NodeRequest::new(&address, attempt);
+        let result = conn.req_packed_command(&cmd).await;
+        node_request.complete(&result);
+        result
+            .map(Response::Single)
+            .map_err(|err| (address.into(), err))
+    }
+
+    /// Sends a read to the node that serves `route`, and if it isn't answered within the hedging delay, also to another node
+    /// that serves the slot. The first response is returned, and the other request is dropped.
+    async fn try_hedged_cmd_request(
+        cmd: Arc<Cmd>,
+        route: Route,
+        read_hedging: Arc<ReadHedging>,
+        core: Core<C>,
+    ) -> OperationResult {
+        let (address, mut conn) = Self::get_connection(
+            InternalSingleNodeRouting::SpecificNode(route),
+            core.clone(),
+            Some(cmd.clone()),
+        )
+        .await
+        .map_err(|err| (OperationTarget::NotFound, err))?;
+        let attempt = core
+            .circuit_breaker_attempt(&address)
+            .map_err(|err| (address.clone().into(), err))?;
+        let node_request = NodeRequest::new(&address, attempt);
+        let start = Instant::now();
+        let mut request = conn.req_packed_command(&cmd);
+        let early_result = match read_hedging.delay() {
+            Some(delay) => tokio::time::timeout(delay, &mut request).await.ok(),
+            None => Some((&mut request).await),
+        };
+
+        let hedge = if early_result.is_none() {
+            let hedge_connection = core
+                .conn_lock
+                .read()
+                .expect(MUTEX_READ_ERR)
+                .hedge_connection_for_route(&route, &address);
+            // Nodes whose requests fail fast aren't used for hedging.
+            hedge_connection.and_then(|(hedge_address, hedge_conn)| {
+                let hedge_attempt = core.circuit_breaker_attempt(&hedge_address).ok()?;
+                let hedge_node_request = NodeRequest::new(&hedge_address, hedge_attempt);
+                Some((hedge_address, hedge_conn, hedge_node_request))
+            })
+        } else {
+            None
+        };
+        let (address, result) = match (early_result, hedge) {
+            (Some(result), _) => {
+                node_request.complete(&result);
+                (address, result)
+            }
+            (None, Some((hedge_address, hedge_conn, hedge_node_request))) => {
+                trace!("hedging the read to {address} with {hedge_address}");
+                let hedge_request = async {
+                    let mut hedge_conn = hedge_conn.await;
+                    hedge_conn.req_packed_command(&cmd).await
+                }
+                .boxed();
+                match future::select(request, hedge_request).await {
+                    future::Either::Left((result, hedge_request)) => {
+                        Self::settle_hedged_read(
+                            (address, node_request, result),
+                            (hedge_address, hedge_node_request, hedge_request),
+                        )
+                        .await
+                    }
+                    future::Either::Right((result, request)) => {
+                        Self::settle_hedged_read(
+                            (hedge_address, hedge_node_request, result),
+                            (address, node_request, request),
+                        )
+                        .await
+                    }
+                }
+            }
+            (None, None) => {
+                let result = request.await;
+                node_request.complete(&result);
+                (address, result)
+            }
+        };
+        if result.is_ok() {
+            read_hedging.record(start.elapsed());
+        }
+        result
             .map(Response::Single)
             .map_err(|err| (address.into(), err))
     }
 
+    /// Returns the result of the hedged request that finished first if it succeeded, and drops the other request.
+    /// If it failed, the other request is awaited, so a node that fails fast doesn't fail a read that the other node
+    /// can serve. The error of the first request is returned only if both failed.
+    async fn settle_hedged_read(
+        (address, node_request, result): (String, NodeRequest, RedisResult<Value>),
+        (other_address, other_node_request, other_request): (
+            String,
+            NodeRequest,
+            impl Future<Output = RedisResult<Value>>,
+        ),
+    ) -> (String, RedisResult<Value>) {
+        node_request.complete(&result);
+        if result.is_ok() {
+            // The request that was dropped isn't recorded, since it didn't complete.
+            other_node_request.abandon();
+            return (address, result);
+        }
+        let other_result = other_request.await;
+        other_node_request.complete(&other_result);
+        match other_result {
+            Ok(_) => (other_address, other_result),
+            Err(_) => (address, result),
+        }
+    }
+
     async fn try_pipeline_request(
         pipeline: Arc<crate::Pipeline>,
         offset: usize,
         count: usize,
         conn: impl Future<Output = RedisResult<(String, C)>>,
+        core: Core<C>,
     ) -> OperationResult {
         trace!("try_pipeline_request");
         let (address, mut conn) = conn.await.map_err(|err| (OperationTarget::NotFound, err))?;
-        conn.req_packed_commands(&pipeline, offset, count, None)
-            .await
+        let attempt = core.circuit_breaker_attempt(&address).map_err(|err| {
+            (
+                OperationTarget::Node {
+                    address: address.clone(),
+                },
+                err,
+            )
+        })?;
+        let node_request = NodeRequest::new(&address, attempt);
+        let result = conn
+            .req_packed_commands(&pipeline, offset, count, None)
+            .await;
+        node_request.complete(&result);
+        result
             .map(Response::Multiple)
             .map_err(|err| (OperationTarget::Node { address }, err))
     }
@@ -2555,9 +3246,10 @@ where
                         count,
                         Self::get_connection(
                             route.unwrap_or(InternalSingleNodeRouting::Random),
-                            core,
+                            core.clone(),
                             None,
                         ),
+                        core,
                     )
                     .await
                 } else {
@@ -2592,6 +3284,29 @@ where
                         .expect(MUTEX_WRITE_ERR);
                     Ok(Response::Single(Value::Okay))
                 }
+                Operation::UpdateTlsParams {
+                    tls_params,
+                    reconnect,
+                } => {
+                    if core
+                        .get_cluster_param(|params| params.tls.is_none())
+                        .expect(MUTEX_READ_ERR)
+                    {
+                        return Err((
+                            OperationTarget::FatalError,
+                            RedisError::from((
+                                ErrorKind::InvalidClientConfig,
+                                "Cannot update TLS parameters of a client that doesn't use TLS",
+                            )),
+                        ));
+                    }
+                    core.set_cluster_param(|params| params.tls_params = Some(tls_params))
+                        .expect(MUTEX_WRITE_ERR);
+                    if reconnect {
+                        tokio::spawn(Self::replace_all_connections(core));
+                    }
+                    Ok(Response::Single(Value::Okay))
+                }
                 Operation::GetUsername => {
                     let username = match core
                         .get_cluster_param(|params| params.username.clone())
@@ -2602,6 +3317,30 @@ where
                     };
                     Ok(Response::Single(username))
                 }
+                Operation::Subscribe {
+                    kind,
+                    channels_or_patterns,
+                } => {
+                    Self::check_resp3_for_subscriptions(&core)?;
+                    Self::add_subscriptions(core, kind, channels_or_patterns)
+                        .await
+                        .map_err(|err| (OperationTarget::FanOut, err))?;
+                    Ok(Response::Single(Value::Okay))
+                }
+                Operation::Unsubscribe {
+                    kind,
+                    channels_or_patterns,
+                } => {
+                    Self::check_resp3_for_subscriptions(&core)?;
+                    Self::remove_subscriptions(core, kind, channels_or_patterns)
+                        .await
+                        .map_err(|err| (OperationTarget::FanOut, err))?;
+                    Ok(Response::Single(Value::Okay))
+                }
+                Operation::GetSubscriptions => {
+                    let (desired, current) = Self::get_subscriptions(core).await;
+                    Ok(Response::Subscriptions { desired, current })
+                }
             },
         }
     }
@@ -2757,20 +3496,23 @@ where
             InternalSingleNodeRouting::Redirect {
                 redirect: Redirect::Moved(moved_addr),
                 ..
-            } => core
-                .conn_lock
-                .read()
-                .expect(MUTEX_READ_ERR)
-                .connection_for_address(moved_addr.as_str())
-                .map_or(
-                    ConnectionCheck::OnlyAddress(moved_addr),
-                    ConnectionCheck::Found,
-                ),
+            } => {
+                let moved_addr = core.reachable_address(moved_addr);
+                core.conn_lock
+                    .read()
+                    .expect(MUTEX_READ_ERR)
+                    .connection_for_address(moved_addr.as_str())
+                    .map_or(
+                        ConnectionCheck::OnlyAddress(moved_addr),
+                        ConnectionCheck::Found,
+                    )
+            }
             InternalSingleNodeRouting::Redirect {
                 redirect: Redirect::Ask(ask_addr, should_exec_asking),
                 ..
             } => {
                 asking = should_exec_asking;
+                let ask_addr = core.reachable_address(ask_addr);
                 core.conn_lock
                     .read()
                     .expect(MUTEX_READ_ERR)
@@ -2800,7 +3542,7 @@ where
                     //   (e.g., sending management command to a different node than the user asked for); instead, raise the error.
                     let mut conn_check = ConnectionCheck::RandomConnection;
 
-                    let routable_cmd = cmd.and_then(|cmd| Routable::command(&*cmd));
+                    let routable_cmd = cmd.as_ref().and_then(|cmd| Routable::command(&**cmd));
                     if routable_cmd.is_some()
                         && !RoutingInfo::is_key_routing_command(&routable_cmd.unwrap())
                     {
@@ -2951,12 +3693,108 @@ where
             }
         };
 
+        // Blocking commands are sent over a connection of their own, so they don't delay other requests.
+        if cmd.as_deref().is_some_and(cluster_routing::is_blocking) {
+            if let Some(blocking_conn) =
+                Self::get_or_create_blocking_connection(&core, &address).await
+            {
+                conn = blocking_conn;
+            }
+        }
+
         if asking {
             let _ = conn.req_packed_command(&crate::cmd::cmd("ASKING")).await;
         }
         Ok((address, conn))
     }
 
+    /// Returns the connection that blocking commands are sent over in the node at `address`.
+    /// A node with a connection pool reserves the last connection of the pool for blocking commands, and it's reconnected
+    /// along with the pool. Otherwise, a dedicated connection is created on first use, and recreated on the next use
+    /// after it was closed.
+    /// Returns `None` if the connection isn't available, in which case the command is sent over a user connection.
+    async fn get_or_create_blocking_connection(core: &Core<C>, address: &str) -> Option<C> {
+        let blocking_conn = core
+            .conn_lock
+            .read()
+            .expect(MUTEX_READ_ERR)
+            .blocking_connection_for_address(address);
+        if let Some(blocking_conn) = blocking_conn {
+            let blocking_conn = blocking_conn.await;
+            if !blocking_conn.is_closed() {
+                return Some(blocking_conn);
+            }
+        }
+        let node = core
+            .conn_lock
+            .read()
+            .expect(MUTEX_READ_ERR)
+            .node_for_address(address)?;
+        if !node.additional_user_connections.is_empty() {
+            return None;
+        }
+
+        // Only one request at a time creates the connection. The requests that arrive meanwhile don't wait for it.
+        let creation_lock = core
+            .blocking_connection_locks
+            .entry(address.to_string())
+            .or_default()
+            .clone();
+        let creation_guard = creation_lock.try_lock_owned().ok()?;
+        let cluster_params = core.cluster_params.read().expect(MUTEX_READ_ERR).clone();
+        match connections_logic::create_blocking_connection::<C>(
+            address,
+            &cluster_params,
+            core.glide_connection_options.clone(),
+            std::iter::empty(),
+        )
+        .await
+        {
+            Ok(connection) => {
+                let blocking_conn = connection.conn.clone();
+                core.conn_lock
+                    .read()
+                    .expect(MUTEX_READ_ERR)
+                    .set_blocking_connection_for_address(address, connection.into_future());
+                Some(blocking_conn)
+            }
+            Err(err) => {
+                warn!(
+                    "Failed to create the blocking connection for node {}, using a user connection instead. Error: {:?}",
+                    address, err
+                );
+                // The connection is retried in the background, so the failed attempt doesn't delay the request further.
+                let core = core.clone();
+                let address = address.to_string();
+                tokio::spawn(async move {
+                    let _creation_guard = creation_guard;
+                    let backoff_durations = core
+                        .glide_connection_options
+                        .connection_retry_strategy
+                        .unwrap_or_default()
+                        .get_bounded_backoff_dur_iterator();
+                    if let Ok(connection) = connections_logic::create_blocking_connection::<C>(
+                        &address,
+                        &cluster_params,
+                        core.glide_connection_options.clone(),
+                        backoff_durations,
+                    )
+                    .await
+                    {
+                        core.conn_lock
+                            .read()
+                            .expect(MUTEX_READ_ERR)
+                            .set_blocking_connection_for_address(
+                                &address,
+                                connection.into_future(),
+                            );
+                    }
+                });
+                None
+            }
+        }
+    }
+
     fn poll_recover(&mut self, cx: &mut task::Context<'_>) -> Poll<Result<(), RedisError>> {
         trace!("entered poll_recover");
 
@@ -3096,6 +3934,10 @@ where
             .inner
             .get_cluster_param(|params| params.retry_params.clone())
             .expect(MUTEX_READ_ERR);
+        let connection_error_retry = self
+            .inner
+            .get_cluster_param(|params| params.connection_error_retry.clone())
+            .expect(MUTEX_READ_ERR);
         let mut poll_flush_action = PollFlushAction::None;
         let mut pending_requests_guard = self.inner.pending_requests.lock().unwrap();
         if !pending_requests_guard.is_empty() {
@@ -3111,6 +3953,7 @@ where
                 let future = Self::try_request(request.info.clone(), self.inner.clone()).boxed();
                 self.in_flight_requests.push(Box::pin(Request {
                     retry_params: retry_params.clone(),
+                    connection_error_retry: connection_error_retry.clone(),
                     request: Some(request),
                     future: RequestState::Future { future },
                 }));
@@ -3131,6 +3974,7 @@ where
                     let future = Self::try_request(request.info.clone(), self.inner.clone());
                     self.in_flight_requests.push(Box::pin(Request {
                         retry_params: retry_params.clone(),
+                        connection_error_retry: connection_error_retry.clone(),
                         request: Some(request),
                         future: RequestState::Future {
                             future: Box::pin(future),
@@ -3148,6 +3992,7 @@ where
                     );
                     self.in_flight_requests.push(Box::pin(Request {
                         retry_params: retry_params.clone(),
+                        connection_error_retry: connection_error_retry.clone(),
                         request: Some(request),
                         future: RequestState::Future {
                             future: Box::pin(future),
@@ -3189,6 +4034,7 @@ where
                     if let Some(future) = future {
                         self.in_flight_requests.push(Box::pin(Request {
                             retry_params,
+                            connection_error_retry: connection_error_retry.clone(),
                             request,
                             future,
                         }));
@@ -3278,7 +4124,11 @@ where
     }
 
     fn start_send(self: Pin<&mut Self>, msg: Message<C>) -> Result<(), Self::Error> {
-        let Message { cmd, sender } = msg;
+        let Message {
+            cmd,
+            sender,
+            timed_out,
+        } = msg;
 
         let info = RequestInfo { cmd };
 
@@ -3288,7 +4138,9 @@ where
             .unwrap()
             .push(PendingRequest {
                 retry: 0,
+                connection_error_retries: 0,
                 sender,
+                timed_out,
                 info,
             });
         Ok(())
@@ -3402,7 +4254,24 @@ where
     let topology_join_results =
         futures::future::join_all(requested_nodes.into_iter().map(|(addr, conn)| async move {
             let mut conn: C = conn.await;
-            let res = conn.req_packed_command(&slot_cmd()).await;
+            if inner.cluster_shards_unsupported.load(Ordering::Relaxed) {
+                return (addr, conn.req_packed_command(&slot_cmd()).await);
+            }
+            // `CLUSTER SHARDS` also reports the health of the nodes, but servers older than Redis 7 don't support it.
+            let res = match conn.req_packed_command(&shards_cmd()).await {
+                Err(err)
+                    if matches!(
+                        err.kind(),
+                        ErrorKind::ResponseError | ErrorKind::ExtensionError
+                    ) =>
+                {
+                    inner
+                        .cluster_shards_unsupported
+                        .store(true, Ordering::Relaxed);
+                    conn.req_packed_command(&slot_cmd()).await
+                }
+                res => res,
+            };
             (addr, res)
         }))
         .await;
@@ -3425,6 +4294,9 @@ where
     let read_from_replicas = inner
         .get_cluster_param(|params| params.read_from_replicas.clone())
         .expect(MUTEX_READ_ERR);
+    let address_remap = inner
+        .get_cluster_param(|params| params.address_remap.clone())
+        .expect(MUTEX_READ_ERR);
     (
         calculate_topology(
             topology_values,
@@ -3432,6 +4304,7 @@ where
             tls_mode,
             num_of_nodes_to_query,
             read_from_replicas,
+            &address_remap,
         ),
         failed_addresses,
     )
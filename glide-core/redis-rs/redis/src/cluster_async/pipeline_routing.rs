@@ -1,4 +1,5 @@
 use crate::aio::ConnectionLike;
+use crate::circuit_breaker::TimedOutFlag;
 use crate::cluster_async::ClusterConnInner;
 use crate::cluster_async::Connect;
 use crate::cluster_async::MUTEX_READ_ERR;
@@ -180,10 +181,15 @@ where
             entry.add_command(cmd.clone(), index, None, false);
         }
     } else {
+        let command_routing_table = core
+            .get_cluster_param(|params| params.command_routing_table.clone())
+            .map_err(|err| (OperationTarget::NotFound, err))?;
         for (index, cmd) in pipeline.cmd_iter().enumerate() {
-            match RoutingInfo::for_routable(cmd.as_ref())
-                .unwrap_or(SingleNode(SingleNodeRoutingInfo::Random))
-            {
+            let routing = match &command_routing_table {
+                Some(table) => RoutingInfo::for_routable_with_table(cmd.as_ref(), table),
+                None => RoutingInfo::for_routable(cmd.as_ref()),
+            };
+            match routing.unwrap_or(SingleNode(SingleNodeRoutingInfo::Random)) {
                 SingleNode(route) => {
                     handle_pipeline_single_node_routing(
                         &mut pipelines_per_node,
@@ -320,7 +326,7 @@ async fn handle_pipeline_multi_slot_routing<C>(
     slots: Vec<(Route, Vec<usize>)>,
 ) -> Result<(), (OperationTarget, RedisError)>
 where
-    C: Clone,
+    C: Clone + ConnectionLike,
 {
     // inner_index is used to keep track of the index of the sub-commands in the multi slot routing info vector.
     for (inner_index, (route, indices)) in slots.iter().enumerate() {
@@ -441,7 +447,9 @@ where
         receivers.push(receiver);
         pending_requests.push(PendingRequest {
             retry,
+            connection_error_retries: 0,
             sender,
+            timed_out: TimedOutFlag::default(),
             info: RequestInfo {
                 cmd: CmdArg::Pipeline {
                     count: context.pipeline.len(),
@@ -637,6 +645,17 @@ fn process_pipeline_responses(
                 },
                 RetryMethod::NoRetry,
             ),
+            // If we received a subscriptions response for a pipeline, we will create a ServerError and append it to the relevant indices
+            // We are not supposed to get in here, but it's better than using unreachable!()
+            Ok(Ok(Response::Subscriptions { .. })) => (
+                ServerError::ExtensionError {
+                    code: ("SubscriptionsError".to_string()),
+                    detail: (Some(
+                        "Received a subscriptions result inside a pipeline.".to_string(),
+                    )),
+                },
+                RetryMethod::NoRetry,
+            ),
 
             // If we received a redis error, we will convert it to a ServerError and append it to the relevant indices
             Ok(Err(err)) => {
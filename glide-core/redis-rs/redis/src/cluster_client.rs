@@ -1,4 +1,6 @@
+use crate::cluster_routing::{CommandRoutingTable, ConnectionErrorRetryPolicy};
 use crate::cluster_slotmap::ReadFromReplicaStrategy;
+use crate::cluster_topology::AddressRemap;
 #[cfg(feature = "cluster-async")]
 use crate::cluster_topology::{
     DEFAULT_SLOTS_REFRESH_MAX_JITTER_MILLI, DEFAULT_SLOTS_REFRESH_WAIT_DURATION,
@@ -6,14 +8,19 @@ use crate::cluster_topology::{
 use crate::connection::{ConnectionAddr, ConnectionInfo, IntoConnectionInfo};
 use crate::types::{ErrorKind, ProtocolVersion, RedisError, RedisResult};
 use crate::{cluster, cluster::TlsMode};
-use crate::{PubSubSubscriptionInfo, PushInfo, RetryStrategy};
+use crate::{
+    ClientTrackingMode, HedgePolicy, PubSubSubscriptionInfo, PushInfo, ReadHedging, RetryStrategy,
+};
 use rand::Rng;
 #[cfg(feature = "cluster-async")]
 use std::ops::Add;
+use std::sync::Arc;
 use std::time::Duration;
 
 use crate::tls::TlsConnParams;
 
+#[cfg(feature = "cluster-async")]
+use crate::aio::ConnectionCredentialsProvider;
 #[cfg(feature = "cluster-async")]
 use crate::cluster_async;
 
@@ -44,6 +51,15 @@ struct BuilderParams {
     protocol: ProtocolVersion,
     pubsub_subscriptions: Option<PubSubSubscriptionInfo>,
     reconnect_retry_strategy: Option<RetryStrategy>,
+    client_tracking: Option<ClientTrackingMode>,
+    connections_per_node: Option<usize>,
+    address_remap: AddressRemap,
+    circuit_breaker_threshold: Option<u32>,
+    read_hedging: Option<HedgePolicy>,
+    connection_error_retry: Option<ConnectionErrorRetryPolicy>,
+    command_routing_table: Option<Arc<CommandRoutingTable>>,
+    #[cfg(feature = "cluster-async")]
+    credentials_provider: Option<Arc<dyn ConnectionCredentialsProvider>>,
 }
 
 #[derive(Clone)]
@@ -144,6 +160,24 @@ pub struct ClusterParams {
     pub(crate) protocol: ProtocolVersion,
     pub(crate) pubsub_subscriptions: Option<PubSubSubscriptionInfo>,
     pub(crate) reconnect_retry_strategy: Option<RetryStrategy>,
+    pub(crate) client_tracking: Option<ClientTrackingMode>,
+    /// The number of user connections to each node.
+    pub(crate) connections_per_node: usize,
+    /// Translates the node addresses announced by the cluster into reachable addresses.
+    pub(crate) address_remap: AddressRemap,
+    /// The number of consecutive timeouts or IO errors after which requests to a node fail fast.
+    /// If `None`, the circuit breakers are disabled.
+    pub(crate) circuit_breaker_threshold: Option<u32>,
+    /// Sends reads that weren't answered in time to another node as well. If `None`, reads aren't hedged.
+    pub(crate) read_hedging: Option<Arc<ReadHedging>>,
+    /// Retries idempotent commands whose connection dropped mid-request. If `None`, such commands fail.
+    pub(crate) connection_error_retry: Option<Arc<ConnectionErrorRetryPolicy>>,
+    /// Routes for the commands that the built-in routing table doesn't know. If `None`, such commands are routed by their first argument.
+    pub(crate) command_routing_table: Option<Arc<CommandRoutingTable>>,
+    /// Provides the credentials that each new connection authenticates with. If `None`, the connections
+    /// authenticate with `username` and `password`.
+    #[cfg(feature = "cluster-async")]
+    pub(crate) credentials_provider: Option<Arc<dyn ConnectionCredentialsProvider>>,
 }
 
 impl ClusterParams {
@@ -173,6 +207,18 @@ impl ClusterParams {
             protocol: value.protocol,
             pubsub_subscriptions: value.pubsub_subscriptions,
             reconnect_retry_strategy: value.reconnect_retry_strategy,
+            client_tracking: value.client_tracking,
+            connections_per_node: value.connections_per_node.unwrap_or(1),
+            address_remap: value.address_remap,
+            circuit_breaker_threshold: value.circuit_breaker_threshold,
+            read_hedging: value
+                .read_hedging
+                .map(|policy| ReadHedging::new(policy).map(Arc::new))
+                .transpose()?,
+            connection_error_retry: value.connection_error_retry.map(Arc::new),
+            command_routing_table: value.command_routing_table,
+            #[cfg(feature = "cluster-async")]
+            credentials_provider: value.credentials_provider,
         })
     }
 }
@@ -296,6 +342,90 @@ impl ClusterClientBuilder {
         })
     }
 
+    /// Sets the provider that every connection of the new ClusterClient fetches its credentials from before
+    /// connecting, instead of authenticating with the configured username and password.
+    #[cfg(feature = "cluster-async")]
+    pub fn credentials_provider(
+        mut self,
+        provider: Arc<dyn ConnectionCredentialsProvider>,
+    ) -> ClusterClientBuilder {
+        self.builder_params.credentials_provider = Some(provider);
+        self
+    }
+
+    /// Enables `CLIENT TRACKING` with the given mode on the user connections of the new ClusterClient.
+    /// Requires the RESP3 protocol.
+    pub fn client_tracking(mut self, tracking_mode: ClientTrackingMode) -> ClusterClientBuilder {
+        self.builder_params.client_tracking = Some(tracking_mode);
+        self
+    }
+
+    /// Sets the number of user connections that are opened to each node of the new ClusterClient. Defaults to 1.
+    ///
+    /// With more than one connection, requests are sent over the connection with the fewest pending requests,
+    /// and the last connection is reserved for blocking commands, so they don't delay other requests.
+    /// With a single connection, blocking commands are sent over a separate connection, which is opened on first use.
+    pub fn connections_per_node(mut self, connections_per_node: usize) -> ClusterClientBuilder {
+        self.builder_params.connections_per_node = Some(connections_per_node.max(1));
+        self
+    }
+
+    /// Sets the translation of the node addresses that the cluster announces into addresses that are reachable by the
+    /// new ClusterClient, e.g. when the nodes run behind NAT or in containers.
+    ///
+    /// The translation is applied to the topology and to the addresses in `MOVED` and `ASK` redirects.
+    pub fn address_remap(mut self, address_remap: AddressRemap) -> ClusterClientBuilder {
+        self.builder_params.address_remap = address_remap;
+        self
+    }
+
+    /// Enables a circuit breaker per node in the new ClusterClient, which opens after `failure_threshold` consecutive
+    /// timeouts or IO errors. While a node's breaker is open, requests to the node fail fast with
+    /// [`ErrorKind::CircuitBreakerOpen`] instead of waiting out the request timeout.
+    ///
+    /// Open breakers are moved to half-open by the periodic connections checks, so those should be enabled as well.
+    pub fn circuit_breaker(mut self, failure_threshold: u32) -> ClusterClientBuilder {
+        self.builder_params.circuit_breaker_threshold = Some(failure_threshold.max(1));
+        self
+    }
+
+    /// Enables hedged reads in the new ClusterClient. A read that is routed to a replica and isn't answered
+    /// by the time `policy` allows, is also sent to another replica of the slot, or to its primary.
+    /// The first response is returned, and the other request is dropped.
+    ///
+    /// Reads are only hedged when they're allowed to be served by replicas, see [`ClusterClientBuilder::read_from`].
+    pub fn read_hedging(mut self, policy: HedgePolicy) -> ClusterClientBuilder {
+        self.builder_params.read_hedging = Some(policy);
+        self
+    }
+
+    /// Enables retries of single commands that failed because their connection dropped mid-request, in the new
+    /// ClusterClient. The command is sent again once the connection is restored, as long as `policy` considers it
+    /// idempotent and its retries budget isn't exhausted.
+    ///
+    /// Without this, such commands fail, since the server might have executed them before the connection dropped.
+    pub fn connection_error_retry(
+        mut self,
+        policy: ConnectionErrorRetryPolicy,
+    ) -> ClusterClientBuilder {
+        self.builder_params.connection_error_retry = Some(policy);
+        self
+    }
+
+    /// Sets the routes of the commands that the built-in routing table doesn't know, such as module commands,
+    /// in the new ClusterClient. If the table discovers routes, the client fills it from the `COMMAND` reply of the cluster
+    /// whenever the topology changes.
+    ///
+    /// The table is shared, so callers that compute the routing of commands themselves can pass it to
+    /// [`crate::cluster_routing::RoutingInfo::for_routable_with_table`].
+    pub fn command_routing_table(
+        mut self,
+        table: Arc<CommandRoutingTable>,
+    ) -> ClusterClientBuilder {
+        self.builder_params.command_routing_table = Some(table);
+        self
+    }
+
     /// Sets client name for the new ClusterClient.
     pub fn client_name(mut self, client_name: String) -> ClusterClientBuilder {
         self.builder_params.client_name = Some(client_name);
@@ -386,6 +516,7 @@ impl ClusterClientBuilder {
     /// `ReadFromReplicaStrategy::AZAffinityReplicasAndPrimary(availability_zone)` - attempt to access nodes in the same availability zone.
     ///  prioritizing local replicas, then the local primary, and falling back to any replica or the primary if needed.
     /// `ReadFromReplicaStrategy::RoundRobin` - reads are distributed across replicas for load balancing using round-robin algorithm. Falling back to primary if needed.
+    /// `ReadFromReplicaStrategy::LowestLatency` - reads are sent to the replica with the lowest round-trip time, as measured by the periodic connections checks. Falling back to primary if needed.
     /// `ReadFromReplicaStrategy::AlwaysFromPrimary` ensures all read and write queries are directed to the primary node.
     ///
     /// # Parameters
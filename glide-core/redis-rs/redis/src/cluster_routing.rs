@@ -8,7 +8,7 @@ use crate::{ErrorKind, RedisError, RedisResult};
 use core::cmp::Ordering;
 use std::borrow::Cow;
 use std::cmp::min;
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet};
 use std::iter::Once;
 use std::sync::Arc;
 use std::sync::{RwLock, RwLockWriteGuard};
@@ -224,6 +224,20 @@ pub fn combine_map_results(values: Vec<Value>) -> RedisResult<Value> {
                     }
                 }
             }
+            // With the auto protocol, the nodes that negotiated RESP3 reply with a map.
+            Value::Map(pairs) => {
+                for (key, value) in pairs {
+                    match (key, value) {
+                        (Value::BulkString(key_bytes), Value::Int(value)) => {
+                            *map.entry(key_bytes).or_insert(0) += value;
+                        }
+                        (Value::BulkString(_), _) => {
+                            return Err((ErrorKind::TypeError, "expected integer value").into());
+                        }
+                        _ => return Err((ErrorKind::TypeError, "expected string key").into()),
+                    }
+                }
+            }
             _ => {
                 return Err((ErrorKind::TypeError, "expected array of values as response").into());
             }
@@ -451,7 +465,8 @@ pub enum MultiSlotArgPattern {
 ///
 /// # Arguments
 /// * `routable` - The command or structure containing key-related data that can be routed.
-/// * `cmd` - A byte slice representing the command name or opcode (e.g., `b"MGET"`).
+/// * `is_readonly` - Whether the command is read-only, and so may be served by replicas.
+/// * `response_policy` - How to combine the responses of the sub-commands.
 /// * `first_key_index` - The starting index in the command where the first key is located.
 /// * `args_pattern` - Specifies how keys and values are patterned in the command (e.g., `OnlyKeys`, `KeyValuePairs`).
 ///
@@ -460,14 +475,14 @@ pub enum MultiSlotArgPattern {
 /// `None` if no routing info could be derived.
 fn multi_shard<R>(
     routable: &R,
-    cmd: &[u8],
+    is_readonly: bool,
+    response_policy: Option<ResponsePolicy>,
     first_key_index: usize,
     args_pattern: MultiSlotArgPattern,
 ) -> Option<RoutingInfo>
 where
     R: Routable + ?Sized,
 {
-    let is_readonly = is_readonly_cmd(cmd);
     let mut routes = HashMap::new();
     let mut curr_arg_idx = 0;
     let incr_add_next_arg = |arg_indices: &mut Vec<usize>, mut curr_arg_idx: usize| {
@@ -523,7 +538,7 @@ where
     } else {
         RoutingInfo::MultiNode((
             MultipleNodeRoutingInfo::MultiSlot((routes, args_pattern)),
-            ResponsePolicy::for_command(cmd),
+            response_policy,
         ))
     })
 }
@@ -803,7 +818,13 @@ impl RoutingInfo {
                 ResponsePolicy::for_command(cmd),
             ))),
 
-            RouteBy::MultiShard(arg_pattern) => multi_shard(r, cmd, 1, arg_pattern),
+            RouteBy::MultiShard(arg_pattern) => multi_shard(
+                r,
+                is_readonly_cmd(cmd),
+                ResponsePolicy::for_command(cmd),
+                1,
+                arg_pattern,
+            ),
 
             RouteBy::Random => Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random)),
 
@@ -859,6 +880,22 @@ impl RoutingInfo {
         }
     }
 
+    /// Returns the routing info for `r`, using the routes in `table` for commands that the built-in routing table doesn't know.
+    pub fn for_routable_with_table<R>(r: &R, table: &CommandRoutingTable) -> Option<RoutingInfo>
+    where
+        R: Routable + ?Sized,
+    {
+        let cmd = r.command()?;
+        // The built-in table routes the commands that it doesn't list by their first key. The table is consulted only
+        // for these, and only holds discovered routes that differ from the first key routing.
+        if matches!(base_routing(&cmd), RouteBy::FirstKey) {
+            if let Some(route) = table.route(&cmd) {
+                return route.routing_info(r);
+            }
+        }
+        Self::for_routable(r)
+    }
+
     fn for_key(cmd: &[u8], key: &[u8]) -> RoutingInfo {
         RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(get_route(
             is_readonly_cmd(cmd),
@@ -875,6 +912,571 @@ pub fn is_readonly(routable: &impl Routable) -> bool {
     }
 }
 
+/// Returns true if the given `routable` represents a command that blocks its connection until the server replies,
+/// such as BLPOP, or XREAD with the BLOCK option.
+pub fn is_blocking(routable: &impl Routable) -> bool {
+    match routable.command() {
+        Some(cmd) => match cmd.as_slice() {
+            b"BLPOP" | b"BRPOP" | b"BLMOVE" | b"BLMPOP" | b"BRPOPLPUSH" | b"BZPOPMAX"
+            | b"BZPOPMIN" | b"BZMPOP" | b"WAIT" | b"WAITAOF" => true,
+            b"XREAD" | b"XREADGROUP" => routable.position(b"BLOCK").is_some(),
+            _ => false,
+        },
+        None => false,
+    }
+}
+
+/// Retries single commands that failed because their connection dropped mid-request.
+///
+/// Since it's unknown whether the server executed such a command before the connection dropped, only commands that are
+/// safe to execute more than once are retried: read-only commands, and the commands that were added to the policy.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct ConnectionErrorRetryPolicy {
+    max_retries: u32,
+    idempotent_commands: HashSet<Vec<u8>>,
+}
+
+impl ConnectionErrorRetryPolicy {
+    /// Creates a policy that retries each command up to `max_retries` times.
+    pub fn new(max_retries: u32) -> Self {
+        Self {
+            max_retries,
+            idempotent_commands: HashSet::new(),
+        }
+    }
+
+    /// Marks `command` as safe to retry, e.g. `SET`, or `CONFIG SET` for a command with a subcommand.
+    pub fn idempotent_command(mut self, command: &str) -> Self {
+        self.idempotent_commands
+            .insert(command.to_ascii_uppercase().into_bytes());
+        self
+    }
+
+    /// Returns the number of times that a command may be retried.
+    pub fn max_retries(&self) -> u32 {
+        self.max_retries
+    }
+
+    /// Returns true if `routable` is safe to execute more than once.
+    pub fn is_idempotent(&self, routable: &impl Routable) -> bool {
+        routable.command().is_some_and(|command| {
+            is_readonly_cmd(&command) || self.idempotent_commands.contains(&command)
+        })
+    }
+}
+
+/// The position of the keys of a command in a [`CommandRoute`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CommandKeys {
+    /// The command has no keys.
+    None,
+    /// The first key is the argument at the given index, where index 1 is the first argument after the command name.
+    Index(usize),
+    /// The argument at the given index is the number of keys, and the keys follow it.
+    AfterKeyCount(usize),
+    /// The keys follow the given keyword, e.g. `STREAMS` in `XREAD`.
+    AfterKeyword(Vec<u8>),
+}
+
+/// How to route a command that the built-in routing table doesn't know, e.g. a module command.
+#[derive(Debug, Clone, PartialEq)]
+pub enum CommandRoute {
+    /// Route to the node that serves the first key of the command, or to a random node if the command has no keys.
+    Keys {
+        /// The position of the keys.
+        keys: CommandKeys,
+        /// Whether the command is read-only, and so may be served by replicas.
+        readonly: bool,
+    },
+    /// Split the command by the slots of its keys, and combine the responses of the sub-commands.
+    MultiShard {
+        /// The index of the first key.
+        first_key: usize,
+        /// How the keys and the other arguments are ordered, starting at the first key.
+        pattern: MultiSlotArgPattern,
+        /// Whether the command is read-only, and so may be served by replicas.
+        readonly: bool,
+        /// How to combine the responses of the sub-commands.
+        response_policy: Option<ResponsePolicy>,
+    },
+    /// Route to all nodes, and combine their responses according to the response policy.
+    AllNodes(Option<ResponsePolicy>),
+    /// Route to all primaries, and combine their responses according to the response policy.
+    AllPrimaries(Option<ResponsePolicy>),
+}
+
+impl CommandRoute {
+    fn routing_info<R>(&self, r: &R) -> Option<RoutingInfo>
+    where
+        R: Routable + ?Sized,
+    {
+        match self {
+            CommandRoute::Keys { keys, readonly } => {
+                let key = match keys {
+                    CommandKeys::None => None,
+                    CommandKeys::Index(index) => r.arg_idx(*index),
+                    CommandKeys::AfterKeyCount(index) => {
+                        let key_count = r
+                            .arg_idx(*index)
+                            .and_then(|x| std::str::from_utf8(x).ok())
+                            .and_then(|x| x.parse::<u64>().ok())?;
+                        if key_count == 0 {
+                            None
+                        } else {
+                            r.arg_idx(index + 1)
+                        }
+                    }
+                    CommandKeys::AfterKeyword(keyword) => r
+                        .position(keyword)
+                        .and_then(|position| r.arg_idx(position + 1)),
+                };
+                Some(RoutingInfo::SingleNode(match key {
+                    Some(key) => SingleNodeRoutingInfo::SpecificNode(get_route(*readonly, key)),
+                    None => SingleNodeRoutingInfo::Random,
+                }))
+            }
+            CommandRoute::MultiShard {
+                first_key,
+                pattern,
+                readonly,
+                response_policy,
+            } => multi_shard(r, *readonly, *response_policy, *first_key, pattern.clone()),
+            CommandRoute::AllNodes(response_policy) => Some(RoutingInfo::MultiNode((
+                MultipleNodeRoutingInfo::AllNodes,
+                *response_policy,
+            ))),
+            CommandRoute::AllPrimaries(response_policy) => Some(RoutingInfo::MultiNode((
+                MultipleNodeRoutingInfo::AllMasters,
+                *response_policy,
+            ))),
+        }
+    }
+}
+
+/// Routes for the commands that the built-in routing table doesn't know, such as module commands.
+///
+/// Routes can be registered manually, and can also be discovered from the `COMMAND` reply of the cluster, which describes
+/// the keys and the request and response policies of each command. Registered routes take precedence over discovered ones.
+/// Commands without a route are routed by their first argument, like the built-in table does for commands it doesn't list.
+#[derive(Debug, Default)]
+pub struct CommandRoutingTable {
+    registered: HashMap<Vec<u8>, CommandRoute>,
+    discover: bool,
+    discovered: RwLock<DiscoveredRoutes>,
+}
+
+#[derive(Debug, Default)]
+struct DiscoveredRoutes {
+    routes: HashMap<Vec<u8>, CommandRoute>,
+    topology_hash: Option<crate::cluster_topology::TopologyHash>,
+}
+
+impl CommandRoutingTable {
+    /// Creates a table without routes. If `discover` is true, the cluster client fills it from the `COMMAND` reply
+    /// of the cluster, once for every topology.
+    pub fn new(discover: bool) -> Self {
+        Self {
+            discover,
+            ..Default::default()
+        }
+    }
+
+    /// Registers the route of `command`, e.g. `BF.ADD`, or `CONFIG SET` for a command with a subcommand.
+    pub fn register(mut self, command: &str, route: CommandRoute) -> Self {
+        self.registered
+            .insert(command.to_ascii_uppercase().into_bytes(), route);
+        self
+    }
+
+    /// Returns true if the routes are discovered from the cluster.
+    pub fn discovers_routes(&self) -> bool {
+        self.discover
+    }
+
+    /// Returns the route of `cmd`, if it was registered or discovered.
+    pub fn route(&self, cmd: &[u8]) -> Option<CommandRoute> {
+        self.registered
+            .get(cmd)
+            .cloned()
+            .or_else(|| self.discovered.read().unwrap().routes.get(cmd).cloned())
+    }
+
+    /// Returns true if the routes should be discovered for the topology with `topology_hash`.
+    pub(crate) fn should_discover(
+        &self,
+        topology_hash: crate::cluster_topology::TopologyHash,
+    ) -> bool {
+        self.discover && self.discovered.read().unwrap().topology_hash != Some(topology_hash)
+    }
+
+    /// Replaces the discovered routes with the routes described by `reply`, a reply to `COMMAND` or `COMMAND INFO`.
+    pub(crate) fn load_command_info(
+        &self,
+        reply: &Value,
+        topology_hash: crate::cluster_topology::TopologyHash,
+    ) -> RedisResult<()> {
+        let Value::Array(entries) = reply else {
+            return Err((
+                ErrorKind::TypeError,
+                "Unexpected COMMAND reply",
+                format!("{reply:?}"),
+            )
+                .into());
+        };
+        let mut routes = HashMap::new();
+        for entry in entries {
+            add_command_info_routes(entry, &mut routes);
+        }
+        *self.discovered.write().unwrap() = DiscoveredRoutes {
+            routes,
+            topology_hash: Some(topology_hash),
+        };
+        Ok(())
+    }
+}
+
+fn value_bytes(value: &Value) -> Option<&[u8]> {
+    match value {
+        Value::BulkString(bytes) => Some(bytes),
+        Value::SimpleString(string) => Some(string.as_bytes()),
+        _ => None,
+    }
+}
+
+fn value_int(value: &Value) -> Option<i64> {
+    match value {
+        Value::Int(int) => Some(*int),
+        _ => None,
+    }
+}
+
+fn value_items(value: &Value) -> &[Value] {
+    match value {
+        Value::Array(items) | Value::Set(items) => items,
+        _ => &[],
+    }
+}
+
+/// Returns the field `name` of a map, which RESP2 replies encode as an array of alternating names and values.
+fn map_field<'a>(value: &'a Value, name: &[u8]) -> Option<&'a Value> {
+    match value {
+        Value::Map(fields) => fields
+            .iter()
+            .find(|(key, _)| value_bytes(key) == Some(name))
+            .map(|(_, value)| value),
+        Value::Array(fields) => fields
+            .chunks_exact(2)
+            .find(|pair| value_bytes(&pair[0]) == Some(name))
+            .map(|pair| &pair[1]),
+        _ => None,
+    }
+}
+
+/// The keys of a command, as described by the first key specification in its `COMMAND` entry,
+/// or by its legacy first key, last key and step fields.
+struct KeySpec {
+    keys: CommandKeys,
+    /// The index of the last key relative to the end of the arguments, and the step between keys, if the keys are a range
+    /// that starts at an index.
+    range: Option<(i64, i64)>,
+}
+
+fn parse_key_spec(key_spec: &Value) -> Option<KeySpec> {
+    let begin_search = map_field(key_spec, b"begin_search")?;
+    let begin_spec = map_field(begin_search, b"spec")?;
+    let find_keys = map_field(key_spec, b"find_keys")?;
+    let find_spec = map_field(find_keys, b"spec")?;
+    let find_type = map_field(find_keys, b"type").and_then(value_bytes)?;
+    match map_field(begin_search, b"type").and_then(value_bytes)? {
+        b"index" => {
+            let index =
+                usize::try_from(map_field(begin_spec, b"index").and_then(value_int)?).ok()?;
+            match find_type {
+                b"range" => Some(KeySpec {
+                    keys: CommandKeys::Index(index),
+                    range: Some((
+                        map_field(find_spec, b"lastkey").and_then(value_int)?,
+                        map_field(find_spec, b"keystep").and_then(value_int)?,
+                    )),
+                }),
+                b"keynum" => {
+                    let key_num_index =
+                        usize::try_from(map_field(find_spec, b"keynumidx").and_then(value_int)?)
+                            .ok()?;
+                    let first_key = map_field(find_spec, b"firstkey").and_then(value_int)?;
+                    // Only keys that directly follow the key count are supported.
+                    (first_key == key_num_index as i64 + 1).then_some(KeySpec {
+                        keys: CommandKeys::AfterKeyCount(index + key_num_index),
+                        range: None,
+                    })
+                }
+                _ => None,
+            }
+        }
+        b"keyword" if find_type == b"range" => Some(KeySpec {
+            keys: CommandKeys::AfterKeyword(
+                map_field(begin_spec, b"keyword")
+                    .and_then(value_bytes)?
+                    .to_ascii_uppercase(),
+            ),
+            range: None,
+        }),
+        _ => None,
+    }
+}
+
+/// Adds the route of the command in a `COMMAND` reply `entry`, and the routes of its subcommands, to `routes`.
+/// Commands whose keys can't be found are left out, so they're routed by their first argument.
+fn add_command_info_routes(entry: &Value, routes: &mut HashMap<Vec<u8>, CommandRoute>) {
+    let fields = value_items(entry);
+    if let Some(subcommands) = fields.get(9) {
+        for subcommand in value_items(subcommands) {
+            add_command_info_routes(subcommand, routes);
+        }
+    }
+    let Some(name) = fields.first().and_then(value_bytes) else {
+        return;
+    };
+    // Subcommands are named with a pipe, e.g. `config|get`.
+    let name: Vec<u8> = name
+        .iter()
+        .map(|byte| match byte {
+            b'|' => b' ',
+            byte => byte.to_ascii_uppercase(),
+        })
+        .collect();
+
+    let flags: Vec<&[u8]> = fields
+        .get(2)
+        .map(value_items)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(value_bytes)
+        .collect();
+    let readonly = flags.contains(&&b"readonly"[..]);
+    let key_spec = match fields
+        .get(8)
+        .map(value_items)
+        .and_then(|specs| specs.first())
+    {
+        Some(key_spec) => parse_key_spec(key_spec),
+        None => {
+            let first_key = fields.get(3).and_then(value_int).unwrap_or(0);
+            if first_key > 0 {
+                Some(KeySpec {
+                    keys: CommandKeys::Index(first_key as usize),
+                    range: fields
+                        .get(4)
+                        .and_then(value_int)
+                        .zip(fields.get(5).and_then(value_int)),
+                })
+            } else if flags.contains(&&b"movablekeys"[..]) {
+                None
+            } else {
+                Some(KeySpec {
+                    keys: CommandKeys::None,
+                    range: None,
+                })
+            }
+        }
+    };
+    let Some(key_spec) = key_spec else {
+        return;
+    };
+
+    let mut request_policy = None;
+    let mut response_policy = None;
+    for tip in fields
+        .get(7)
+        .map(value_items)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(value_bytes)
+    {
+        if let Some(policy) = tip.strip_prefix(b"request_policy:") {
+            request_policy = Some(policy);
+        } else if let Some(policy) = tip.strip_prefix(b"response_policy:") {
+            response_policy = match policy {
+                b"one_succeeded" => Some(ResponsePolicy::OneSucceeded),
+                b"all_succeeded" => Some(ResponsePolicy::AllSucceeded),
+                b"agg_logical_and" => {
+                    Some(ResponsePolicy::AggregateLogical(LogicalAggregateOp::And))
+                }
+                b"agg_min" => Some(ResponsePolicy::Aggregate(AggregateOp::Min)),
+                b"agg_sum" => Some(ResponsePolicy::Aggregate(AggregateOp::Sum)),
+                b"special" => Some(ResponsePolicy::Special),
+                _ => None,
+            };
+        }
+    }
+
+    let route = match request_policy {
+        Some(b"all_nodes") => CommandRoute::AllNodes(response_policy),
+        Some(b"all_shards") => CommandRoute::AllPrimaries(response_policy),
+        Some(b"multi_shard") => match (&key_spec.keys, key_spec.range) {
+            (CommandKeys::Index(first_key), Some((last_key, step))) => {
+                let pattern = match (last_key, step) {
+                    (-1, 1) => Some(MultiSlotArgPattern::KeysOnly),
+                    (-1, 2) => Some(MultiSlotArgPattern::KeyValuePairs),
+                    (-1, 3) => Some(MultiSlotArgPattern::KeyWithTwoArgTriples),
+                    (-2, 1) => Some(MultiSlotArgPattern::KeysAndLastArg),
+                    _ => None,
+                };
+                match pattern {
+                    // Without a response policy, the responses are arrays that are combined in the order of the keys.
+                    Some(pattern) => CommandRoute::MultiShard {
+                        first_key: *first_key,
+                        pattern,
+                        readonly,
+                        response_policy: response_policy.or(Some(ResponsePolicy::CombineArrays)),
+                    },
+                    None => CommandRoute::Keys {
+                        keys: key_spec.keys,
+                        readonly,
+                    },
+                }
+            }
+            _ => CommandRoute::Keys {
+                keys: key_spec.keys,
+                readonly,
+            },
+        },
+        _ => CommandRoute::Keys {
+            keys: key_spec.keys,
+            readonly,
+        },
+    };
+    // The commands that the built-in table lists, and the ones that it already routes by their first key, aren't added,
+    // so the discovered routes only affect the commands that the built-in table doesn't know.
+    let routed_by_first_key = matches!(
+        &route,
+        CommandRoute::Keys { keys: CommandKeys::Index(1), readonly }
+            if *readonly == is_readonly_cmd(&name)
+    );
+    if routed_by_first_key || !matches!(base_routing(&name), RouteBy::FirstKey) {
+        return;
+    }
+    routes.insert(name, route);
+}
+
+/// Returns the indices of the arguments of `r` that are keys, or key patterns such as the `BY` and `GET` patterns of `SORT`.
+/// Index 0 is the command name. Pub/Sub channels aren't keys, and keyless commands have no key indices.
+pub fn key_indices<R>(r: &R) -> Vec<usize>
+where
+    R: Routable + ?Sized,
+{
+    let Some(cmd) = r.command() else {
+        return Vec::new();
+    };
+    let arg_count = (0..).take_while(|idx| r.arg_idx(*idx).is_some()).count();
+    let after_key_count = |count_idx: usize| -> Vec<usize> {
+        r.arg_idx(count_idx)
+            .and_then(|count| std::str::from_utf8(count).ok())
+            .and_then(|count| count.parse::<usize>().ok())
+            .map(|count| (count_idx + 1..arg_count.min(count_idx + 1 + count)).collect())
+            .unwrap_or_default()
+    };
+    let after_keyword = |keyword: &[u8], from: usize| -> Option<usize> {
+        (from..arg_count.saturating_sub(1))
+            .find(|idx| {
+                r.arg_idx(*idx)
+                    .is_some_and(|arg| arg.eq_ignore_ascii_case(keyword))
+            })
+            .map(|idx| idx + 1)
+    };
+
+    match cmd.as_slice() {
+        // Keyless commands that would otherwise be taken to be routed by their first key.
+        b"ASKING" | b"BGREWRITEAOF" | b"DISCARD" | b"EXEC" | b"FAILOVER" | b"HELLO"
+        | b"MONITOR" | b"MULTI" | b"PSUBSCRIBE" | b"PSYNC" | b"PUBLISH" | b"PUNSUBSCRIBE"
+        | b"QUIT" | b"REPLCONF" | b"RESET" | b"ROLE" | b"SELECT" | b"SPUBLISH" | b"SSUBSCRIBE"
+        | b"SUBSCRIBE" | b"SUNSUBSCRIBE" | b"SWAPDB" | b"SYNC" | b"UNSUBSCRIBE" => Vec::new(),
+
+        b"MSETNX" => (1..arg_count).step_by(2).collect(),
+
+        b"PFCOUNT" | b"PFMERGE" | b"RENAME" | b"RENAMENX" | b"SDIFF" | b"SDIFFSTORE"
+        | b"SINTER" | b"SINTERSTORE" | b"SUNION" | b"SUNIONSTORE" => (1..arg_count).collect(),
+
+        b"BLMOVE" | b"BRPOPLPUSH" | b"COPY" | b"GEOSEARCHSTORE" | b"LCS" | b"LMOVE"
+        | b"RPOPLPUSH" | b"SMOVE" | b"ZRANGESTORE" => (1..arg_count.min(3)).collect(),
+
+        // The keys are followed by a timeout.
+        b"BLPOP" | b"BRPOP" | b"BZPOPMAX" | b"BZPOPMIN" => {
+            (1..arg_count.saturating_sub(1)).collect()
+        }
+
+        b"BITOP" => (2..arg_count).collect(),
+
+        b"ZDIFFSTORE" | b"ZINTERSTORE" | b"ZUNIONSTORE" => std::iter::once(1)
+            .filter(|idx| *idx < arg_count)
+            .chain(after_key_count(2))
+            .collect(),
+
+        b"GEORADIUS" | b"GEORADIUSBYMEMBER" => std::iter::once(Some(1))
+            .chain([after_keyword(b"STORE", 2), after_keyword(b"STOREDIST", 2)])
+            .flatten()
+            .filter(|idx| *idx < arg_count)
+            .collect(),
+
+        b"SORT" | b"SORT_RO" => {
+            let mut indices = vec![1];
+            let mut idx = 2;
+            while let Some(arg) = r.arg_idx(idx) {
+                if arg.eq_ignore_ascii_case(b"BY") || arg.eq_ignore_ascii_case(b"STORE") {
+                    indices.push(idx + 1);
+                    idx += 2;
+                } else if arg.eq_ignore_ascii_case(b"GET") {
+                    // `GET #` returns the element itself.
+                    if r.arg_idx(idx + 1).is_some_and(|pattern| pattern != b"#") {
+                        indices.push(idx + 1);
+                    }
+                    idx += 2;
+                } else if arg.eq_ignore_ascii_case(b"LIMIT") {
+                    idx += 3;
+                } else {
+                    idx += 1;
+                }
+            }
+            indices.retain(|idx| *idx < arg_count);
+            indices
+        }
+
+        // MIGRATE host port key|"" destination-db timeout [COPY] [REPLACE] [AUTH ...] [KEYS key ...]
+        b"MIGRATE" => match r.arg_idx(3) {
+            Some(b"") => after_keyword(b"KEYS", 6)
+                .map(|first_key| (first_key..arg_count).collect())
+                .unwrap_or_default(),
+            Some(_) => vec![3],
+            None => Vec::new(),
+        },
+
+        _ => match base_routing(&cmd) {
+            // The first argument of a command with a subcommand, e.g. `FUNCTION DUMP`, is the subcommand.
+            RouteBy::FirstKey if cmd.contains(&b' ') => Vec::new(),
+            RouteBy::FirstKey => (1..arg_count.min(2)).collect(),
+            RouteBy::SecondArg => (2..arg_count.min(3)).collect(),
+            RouteBy::SecondArgAfterKeyCount => after_key_count(1),
+            RouteBy::ThirdArgAfterKeyCount => after_key_count(2),
+            RouteBy::StreamsIndex => match after_keyword(b"STREAMS", 1) {
+                // The keys are followed by an ID for each key.
+                Some(first_key) => (first_key..first_key + (arg_count - first_key) / 2).collect(),
+                None => Vec::new(),
+            },
+            RouteBy::MultiShard(pattern) => match pattern {
+                MultiSlotArgPattern::KeysOnly => (1..arg_count).collect(),
+                MultiSlotArgPattern::KeyValuePairs => (1..arg_count).step_by(2).collect(),
+                MultiSlotArgPattern::KeysAndLastArg => (1..arg_count.saturating_sub(1)).collect(),
+                MultiSlotArgPattern::KeyWithTwoArgTriples => (1..arg_count).step_by(3).collect(),
+            },
+            RouteBy::AllNodes
+            | RouteBy::AllPrimaries
+            | RouteBy::Random
+            | RouteBy::SecondArgSlot
+            | RouteBy::Undefined => Vec::new(),
+        },
+    }
+}
+
 /// Returns `true` if the given `cmd` is a readonly command.
 pub fn is_readonly_cmd(cmd: &[u8]) -> bool {
     matches!(
@@ -976,14 +1578,18 @@ pub fn is_readonly_cmd(cmd: &[u8]) -> bool {
             | b"GETRANGE"
             | b"HELLO"
             | b"HEXISTS"
+            | b"HEXPIRETIME"
             | b"HGET"
             | b"HGETALL"
             | b"HKEYS"
             | b"HLEN"
             | b"HMGET"
+            | b"HPEXPIRETIME"
+            | b"HPTTL"
             | b"HRANDFIELD"
             | b"HSCAN"
             | b"HSTRLEN"
+            | b"HTTL"
             | b"HVALS"
             | b"JSON.ARRINDEX"
             | b"JSON.ARRLEN"
@@ -1429,8 +2035,10 @@ fn random_slot() -> u16 {
 #[cfg(test)]
 mod tests_routing {
     use super::{
-        command_for_multi_slot_indices, AggregateOp, MultiSlotArgPattern, MultipleNodeRoutingInfo,
-        ResponsePolicy, Route, RoutingInfo, ShardAddrs, SingleNodeRoutingInfo, SlotAddr,
+        command_for_multi_slot_indices, is_blocking, key_indices, AggregateOp, CommandKeys,
+        CommandRoute, CommandRoutingTable, ConnectionErrorRetryPolicy, MultiSlotArgPattern,
+        MultipleNodeRoutingInfo, ResponsePolicy, Route, RoutingInfo, ShardAddrs,
+        SingleNodeRoutingInfo, SlotAddr,
     };
     use crate::cluster_routing::ShardUpdateResult;
     use crate::{cluster_topology::slot, cmd, parser::parse_redis_value, Value};
@@ -1660,6 +2268,328 @@ mod tests_routing {
         }
     }
 
+    #[test]
+    fn test_is_blocking() {
+        assert!(is_blocking(cmd("blpop").arg("foo").arg(0)));
+        assert!(is_blocking(cmd("WAIT").arg(1).arg(0)));
+        assert!(is_blocking(
+            cmd("XREAD")
+                .arg("block")
+                .arg(0)
+                .arg("STREAMS")
+                .arg("foo")
+                .arg(0)
+        ));
+        assert!(!is_blocking(cmd("XREAD").arg("STREAMS").arg("foo").arg(0)));
+        assert!(!is_blocking(cmd("GET").arg("foo")));
+    }
+
+    #[test]
+    fn test_key_indices() {
+        assert_eq!(key_indices(cmd("GET").arg("foo")), [1]);
+        assert_eq!(key_indices(&cmd("PING")), [] as [usize; 0]);
+        assert_eq!(key_indices(cmd("SELECT").arg(1)), [] as [usize; 0]);
+        assert_eq!(
+            key_indices(cmd("CONFIG").arg("GET").arg("foo")),
+            [] as [usize; 0]
+        );
+        assert_eq!(key_indices(cmd("MGET").arg("a").arg("b")), [1, 2]);
+        assert_eq!(
+            key_indices(cmd("MSET").arg("a").arg(1).arg("b").arg(2)),
+            [1, 3]
+        );
+        assert_eq!(
+            key_indices(
+                cmd("EVALSHA")
+                    .arg("sha")
+                    .arg(2)
+                    .arg("a")
+                    .arg("b")
+                    .arg("arg")
+            ),
+            [3, 4]
+        );
+        assert_eq!(key_indices(cmd("BLPOP").arg("a").arg("b").arg(0)), [1, 2]);
+        assert_eq!(
+            key_indices(cmd("BLMPOP").arg(0).arg(2).arg("a").arg("b").arg("LEFT")),
+            [3, 4]
+        );
+        assert_eq!(
+            key_indices(
+                cmd("XREAD")
+                    .arg("COUNT")
+                    .arg(1)
+                    .arg("STREAMS")
+                    .arg("a")
+                    .arg("b")
+                    .arg(0)
+                    .arg(0)
+            ),
+            [4, 5]
+        );
+        assert_eq!(
+            key_indices(
+                cmd("ZUNIONSTORE")
+                    .arg("dest")
+                    .arg(2)
+                    .arg("a")
+                    .arg("b")
+                    .arg("WEIGHTS")
+                    .arg(1)
+                    .arg(2)
+            ),
+            [1, 3, 4]
+        );
+        assert_eq!(
+            key_indices(
+                cmd("SORT")
+                    .arg("list")
+                    .arg("BY")
+                    .arg("weight_*")
+                    .arg("LIMIT")
+                    .arg(0)
+                    .arg(10)
+                    .arg("GET")
+                    .arg("#")
+                    .arg("GET")
+                    .arg("data_*")
+                    .arg("STORE")
+                    .arg("dest")
+            ),
+            [1, 3, 10, 12]
+        );
+    }
+
+    #[test]
+    fn test_connection_error_retry_policy_classifies_idempotent_commands() {
+        let policy = ConnectionErrorRetryPolicy::new(2)
+            .idempotent_command("set")
+            .idempotent_command("config set");
+        assert!(policy.is_idempotent(cmd("GET").arg("foo")));
+        assert!(policy.is_idempotent(cmd("SET").arg("foo").arg("bar")));
+        assert!(policy.is_idempotent(cmd("CONFIG").arg("set").arg("maxmemory").arg(0)));
+        assert!(!policy.is_idempotent(cmd("INCR").arg("foo")));
+        assert!(!policy.is_idempotent(cmd("FUNCTION").arg("FLUSH")));
+        assert_eq!(policy.max_retries(), 2);
+    }
+
+    #[test]
+    fn test_command_routing_table_routes_unknown_commands() {
+        let table = CommandRoutingTable::new(false)
+            .register(
+                "my.scan",
+                CommandRoute::AllPrimaries(Some(ResponsePolicy::CombineArrays)),
+            )
+            .register(
+                "MY.READ",
+                CommandRoute::Keys {
+                    keys: CommandKeys::AfterKeyword(b"KEYS".to_vec()),
+                    readonly: true,
+                },
+            )
+            .register("DBSIZE", CommandRoute::AllNodes(None));
+
+        assert_eq!(
+            RoutingInfo::for_routable_with_table(&cmd("MY.SCAN"), &table),
+            Some(RoutingInfo::MultiNode((
+                MultipleNodeRoutingInfo::AllMasters,
+                Some(ResponsePolicy::CombineArrays)
+            )))
+        );
+        assert_eq!(
+            RoutingInfo::for_routable_with_table(
+                cmd("my.read").arg("LIMIT").arg(10).arg("keys").arg("foo"),
+                &table
+            ),
+            Some(RoutingInfo::SingleNode(
+                SingleNodeRoutingInfo::SpecificNode(Route::new(
+                    slot(b"foo"),
+                    SlotAddr::ReplicaOptional
+                ))
+            ))
+        );
+        // Commands that the built-in table knows aren't affected.
+        assert_eq!(
+            RoutingInfo::for_routable_with_table(&cmd("DBSIZE"), &table),
+            RoutingInfo::for_routable(&cmd("DBSIZE"))
+        );
+        // Commands without a route are routed by their first argument.
+        assert_eq!(
+            RoutingInfo::for_routable_with_table(cmd("MY.WRITE").arg("foo"), &table),
+            RoutingInfo::for_routable(cmd("MY.WRITE").arg("foo"))
+        );
+    }
+
+    fn command_info(
+        name: &str,
+        flags: &[&str],
+        first_key: i64,
+        tips: &[&str],
+        key_specs: Vec<Value>,
+    ) -> Value {
+        let strings = |strings: &[&str]| {
+            Value::Array(
+                strings
+                    .iter()
+                    .map(|string| Value::SimpleString(string.to_string()))
+                    .collect(),
+            )
+        };
+        Value::Array(vec![
+            Value::BulkString(name.as_bytes().to_vec()),
+            Value::Int(-2),
+            strings(flags),
+            Value::Int(first_key),
+            Value::Int(-1),
+            Value::Int(1),
+            Value::Array(vec![]),
+            strings(tips),
+            Value::Array(key_specs),
+            Value::Array(vec![]),
+        ])
+    }
+
+    fn map(fields: Vec<(&str, Value)>) -> Value {
+        Value::Map(
+            fields
+                .into_iter()
+                .map(|(name, value)| (Value::SimpleString(name.to_string()), value))
+                .collect(),
+        )
+    }
+
+    fn range_key_spec(index: i64, last_key: i64, step: i64) -> Value {
+        map(vec![
+            (
+                "begin_search",
+                map(vec![
+                    ("type", Value::SimpleString("index".to_string())),
+                    ("spec", map(vec![("index", Value::Int(index))])),
+                ]),
+            ),
+            (
+                "find_keys",
+                map(vec![
+                    ("type", Value::SimpleString("range".to_string())),
+                    (
+                        "spec",
+                        map(vec![
+                            ("lastkey", Value::Int(last_key)),
+                            ("keystep", Value::Int(step)),
+                            ("limit", Value::Int(0)),
+                        ]),
+                    ),
+                ]),
+            ),
+        ])
+    }
+
+    #[test]
+    fn test_command_routing_table_discovers_routes_from_command_info() {
+        let table = CommandRoutingTable::new(true);
+        assert!(table.should_discover(1));
+        let reply = Value::Array(vec![
+            command_info(
+                "my.mget",
+                &["readonly"],
+                1,
+                &["request_policy:multi_shard"],
+                vec![range_key_spec(1, -1, 1)],
+            ),
+            command_info(
+                "my.count",
+                &["readonly"],
+                0,
+                &["request_policy:all_shards", "response_policy:agg_sum"],
+                vec![],
+            ),
+            command_info("my.search", &["readonly"], 0, &[], vec![]),
+            command_info("my.put", &["write"], 2, &[], vec![range_key_spec(2, 0, 1)]),
+            command_info("get", &["readonly"], 1, &[], vec![range_key_spec(1, 0, 1)]),
+            command_info(
+                "dbsize",
+                &["readonly"],
+                0,
+                &["request_policy:all_shards", "response_policy:agg_sum"],
+                vec![],
+            ),
+        ]);
+        table.load_command_info(&reply, 1).unwrap();
+        assert!(!table.should_discover(1));
+        assert!(table.should_discover(2));
+        // The commands that the built-in table lists or routes by their first key aren't added.
+        assert_eq!(table.route(b"GET"), None);
+        assert_eq!(table.route(b"DBSIZE"), None);
+
+        let routing =
+            RoutingInfo::for_routable_with_table(cmd("MY.MGET").arg("foo").arg("bar"), &table);
+        let Some(RoutingInfo::MultiNode((
+            MultipleNodeRoutingInfo::MultiSlot((routes, MultiSlotArgPattern::KeysOnly)),
+            Some(ResponsePolicy::CombineArrays),
+        ))) = routing
+        else {
+            panic!("unexpected routing: {routing:?}");
+        };
+        assert!(routes
+            .iter()
+            .all(|(route, _)| route.slot_addr() == SlotAddr::ReplicaOptional));
+        assert_eq!(
+            RoutingInfo::for_routable_with_table(&cmd("MY.COUNT"), &table),
+            Some(RoutingInfo::MultiNode((
+                MultipleNodeRoutingInfo::AllMasters,
+                Some(ResponsePolicy::Aggregate(AggregateOp::Sum))
+            )))
+        );
+        // Keyless commands aren't routed by their first argument.
+        assert_eq!(
+            RoutingInfo::for_routable_with_table(cmd("MY.SEARCH").arg("index"), &table),
+            Some(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random))
+        );
+        assert_eq!(
+            RoutingInfo::for_routable_with_table(cmd("MY.PUT").arg("field").arg("foo"), &table),
+            Some(RoutingInfo::SingleNode(
+                SingleNodeRoutingInfo::SpecificNode(Route::new(slot(b"foo"), SlotAddr::Master))
+            ))
+        );
+
+        // A table that doesn't discover routes is never filled from the cluster.
+        assert!(!CommandRoutingTable::new(false).should_discover(1));
+    }
+
+    #[test]
+    fn test_hash_field_expiration_commands_route_by_key() {
+        let key = b"session";
+        let key_slot = slot(key);
+        for (name, readonly) in [
+            ("HEXPIRE", false),
+            ("HPEXPIRE", false),
+            ("HEXPIREAT", false),
+            ("HPEXPIREAT", false),
+            ("HPERSIST", false),
+            ("HGETEX", false),
+            ("HSETEX", false),
+            ("HTTL", true),
+            ("HPTTL", true),
+            ("HEXPIRETIME", true),
+            ("HPEXPIRETIME", true),
+        ] {
+            let mut command = cmd(name);
+            command.arg(key).arg("FIELDS").arg(1).arg("field");
+            let slot_addr = if readonly {
+                SlotAddr::ReplicaOptional
+            } else {
+                SlotAddr::Master
+            };
+            assert_eq!(
+                RoutingInfo::for_routable(&command),
+                Some(RoutingInfo::SingleNode(
+                    SingleNodeRoutingInfo::SpecificNode(Route::new(key_slot, slot_addr))
+                )),
+                "{name}"
+            );
+        }
+    }
+
     #[test]
     fn test_slot_for_packed_cmd() {
         assert!(matches!(RoutingInfo::for_routable(&parse_redis_value(&[
@@ -1955,11 +2885,9 @@ mod tests_routing {
                 Value::BulkString(b"key2".to_vec()),
                 Value::Int(10),
             ]),
-            Value::Array(vec![
-                Value::BulkString(b"key1".to_vec()),
-                Value::Int(3),
-                Value::BulkString(b"key3".to_vec()),
-                Value::Int(15),
+            Value::Map(vec![
+                (Value::BulkString(b"key1".to_vec()), Value::Int(3)),
+                (Value::BulkString(b"key3".to_vec()), Value::Int(15)),
             ]),
         ];
         let result = super::combine_map_results(input).unwrap();
@@ -1,6 +1,6 @@
 use std::sync::Arc;
 use std::{
-    collections::{BTreeMap, HashSet},
+    collections::{BTreeMap, HashMap, HashSet},
     fmt::Display,
     sync::atomic::AtomicUsize,
 };
@@ -12,6 +12,41 @@ use crate::ErrorKind;
 use crate::RedisError;
 use crate::RedisResult;
 pub(crate) type NodesMap = DashMap<Arc<String>, Arc<ShardAddrs>>;
+/// The metadata of the cluster's nodes, by their addresses. Ordered, so that equal topology views hash equally.
+pub(crate) type NodesMetadata = BTreeMap<String, NodeMetadata>;
+
+/// The health of a node, as reported by `CLUSTER SHARDS`.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub(crate) enum NodeHealth {
+    /// The node is ready to serve requests.
+    #[default]
+    Online,
+    /// The node is loading its dataset, e.g. a replica that is syncing with its primary.
+    Loading,
+    /// The node is considered failed by the cluster.
+    Failed,
+}
+
+impl NodeHealth {
+    pub(crate) fn from_reported(health: &str) -> Self {
+        match health {
+            "loading" => NodeHealth::Loading,
+            "fail" => NodeHealth::Failed,
+            // Unknown states don't exclude the node, so that newer servers don't make the client skip healthy nodes.
+            _ => NodeHealth::Online,
+        }
+    }
+}
+
+/// Information about a node that isn't required to route requests to it, as reported by `CLUSTER SHARDS`.
+/// `CLUSTER SLOTS` doesn't report it, so nodes discovered through it don't have metadata, and are considered healthy.
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+pub(crate) struct NodeMetadata {
+    pub(crate) health: NodeHealth,
+    pub(crate) hostname: Option<String>,
+    pub(crate) endpoint: Option<String>,
+    pub(crate) availability_zone: Option<String>,
+}
 
 #[derive(Debug)]
 pub(crate) struct SlotMapValue {
@@ -35,12 +70,17 @@ pub enum ReadFromReplicaStrategy {
     /// Spread the read requests among nodes within the client's Availability Zone (AZ) in a round robin manner,
     /// prioritizing local replicas, then the local primary, and falling back to any replica or the primary if needed.
     AZAffinityReplicasAndPrimary(String),
+    /// Send the read requests to the replica with the lowest measured round-trip time.
+    /// Until latencies were measured, the read requests are spread between all replicas in a round robin manner.
+    /// If no replica is available, route the requests to the primary.
+    LowestLatency,
 }
 
 #[derive(Debug, Default)]
 pub(crate) struct SlotMap {
     pub(crate) slots: BTreeMap<u16, SlotMapValue>,
     nodes_map: NodesMap,
+    nodes_metadata: HashMap<String, NodeMetadata>,
     read_from_replica: ReadFromReplicaStrategy,
 }
 
@@ -55,7 +95,8 @@ fn get_address_from_slot(
     }
     match read_from_replica {
         ReadFromReplicaStrategy::AlwaysFromPrimary => addrs.primary(),
-        ReadFromReplicaStrategy::RoundRobin => {
+        // The sync client doesn't measure node latencies, so it falls back to round robin.
+        ReadFromReplicaStrategy::RoundRobin | ReadFromReplicaStrategy::LowestLatency => {
             let index = slot
                 .last_used_replica
                 .fetch_add(1, std::sync::atomic::Ordering::Relaxed)
@@ -72,6 +113,7 @@ impl SlotMap {
         SlotMap {
             slots: BTreeMap::new(),
             nodes_map: DashMap::new(),
+            nodes_metadata: HashMap::new(),
             read_from_replica,
         }
     }
@@ -115,10 +157,25 @@ impl SlotMap {
         slot_map
     }
 
+    /// Attaches the metadata of the nodes, as reported by the server, to the slot map.
+    pub(crate) fn with_nodes_metadata(mut self, nodes_metadata: NodesMetadata) -> Self {
+        self.nodes_metadata = nodes_metadata.into_iter().collect();
+        self
+    }
+
     pub(crate) fn nodes_map(&self) -> &NodesMap {
         &self.nodes_map
     }
 
+    pub(crate) fn node_metadata(&self, address: &str) -> Option<&NodeMetadata> {
+        self.nodes_metadata.get(address)
+    }
+
+    /// Returns false if the node in `address` was reported as loading or failed.
+    pub(crate) fn is_node_healthy(&self, address: &str) -> bool {
+        !matches!(self.node_metadata(address), Some(metadata) if metadata.health != NodeHealth::Online)
+    }
+
     pub fn is_primary(&self, address: &String) -> bool {
         self.nodes_map
             .get(address)
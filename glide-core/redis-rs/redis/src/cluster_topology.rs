@@ -4,7 +4,9 @@ use crate::cluster::get_connection_addr;
 #[cfg(feature = "cluster-async")]
 use crate::cluster_client::SlotsRefreshRateLimit;
 use crate::cluster_routing::Slot;
-use crate::cluster_slotmap::{ReadFromReplicaStrategy, SlotMap};
+use crate::cluster_slotmap::{
+    NodeHealth, NodeMetadata, NodesMetadata, ReadFromReplicaStrategy, SlotMap,
+};
 use crate::{cluster::TlsMode, ErrorKind, RedisError, RedisResult, Value};
 #[cfg(all(feature = "cluster-async", not(feature = "tokio-comp")))]
 use async_std::sync::RwLock;
@@ -56,11 +58,106 @@ impl SlotRefreshState {
     }
 }
 
+/// Translates the node addresses that a cluster announces into addresses that the client can reach.
+///
+/// Cluster nodes running behind NAT or in containers announce internal addresses, which are unreachable from outside
+/// their network. The translation is applied to the addresses in the topology responses and in `MOVED` and `ASK` redirects.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AddressRemap {
+    addresses: HashMap<String, String>,
+    host_rules: Vec<HostRemapRule>,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+struct HostRemapRule {
+    pattern: String,
+    host: String,
+}
+
+impl AddressRemap {
+    /// Creates an empty remap, which keeps all addresses as announced.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Translates the announced `host:port` address into the reachable `host:port` address.
+    /// Takes precedence over the host rules.
+    pub fn address(mut self, announced: impl Into<String>, reachable: impl Into<String>) -> Self {
+        self.addresses.insert(announced.into(), reachable.into());
+        self
+    }
+
+    /// Replaces the host of the announced addresses whose host matches `pattern` with `host`, and keeps their port.
+    /// `*` in the pattern matches any sequence of characters, e.g. `10.0.*` or `*.svc.cluster.local`.
+    /// The rules are tried in the order in which they were added, and the first matching rule is applied.
+    pub fn host_rule(mut self, pattern: impl Into<String>, host: impl Into<String>) -> Self {
+        self.host_rules.push(HostRemapRule {
+            pattern: pattern.into(),
+            host: host.into(),
+        });
+        self
+    }
+
+    /// Returns true if the remap doesn't translate any address.
+    pub fn is_empty(&self) -> bool {
+        self.addresses.is_empty() && self.host_rules.is_empty()
+    }
+
+    /// Returns the reachable address of the announced `host:port` address, or `None` if no translation applies to it.
+    pub fn translate(&self, address: &str) -> Option<String> {
+        if let Some(reachable) = self.addresses.get(address) {
+            return Some(reachable.clone());
+        }
+        let (host, port) = address.rsplit_once(':')?;
+        self.host_rules
+            .iter()
+            .find(|rule| wildcard_match(&rule.pattern, host))
+            .map(|rule| format!("{}:{port}", rule.host))
+    }
+
+    pub(crate) fn apply(&self, address: String) -> String {
+        self.translate(&address).unwrap_or(address)
+    }
+
+    pub(crate) fn apply_to_slots(&self, slots: &mut [Slot]) {
+        for slot in slots.iter_mut() {
+            slot.master = self.apply(std::mem::take(&mut slot.master));
+            for replica in slot.replicas.iter_mut() {
+                *replica = self.apply(std::mem::take(replica));
+            }
+            slot.replicas.sort_unstable();
+        }
+    }
+}
+
+// Returns true if `text` matches `pattern`, in which `*` matches any sequence of characters.
+fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let mut parts = pattern.split('*');
+    let first = parts.next().unwrap_or_default();
+    let Some(mut rest) = text.strip_prefix(first) else {
+        return false;
+    };
+    let mut parts = parts.peekable();
+    while let Some(part) = parts.next() {
+        if parts.peek().is_none() {
+            // The last part must match the end of the text, after a wildcard.
+            return rest.ends_with(part);
+        }
+        match rest.find(part) {
+            Some(index) => rest = &rest[index + part.len()..],
+            None => return false,
+        }
+    }
+    // The pattern has no wildcards.
+    rest.is_empty()
+}
+
 #[derive(Debug)]
 pub(crate) struct TopologyView {
     pub(crate) hash_value: TopologyHash,
     pub(crate) nodes_count: u16,
     slots_and_count: (u16, Vec<Slot>),
+    nodes_metadata: NodesMetadata,
 }
 
 impl PartialEq for TopologyView {
@@ -190,6 +287,182 @@ pub(crate) fn parse_and_count_slots(
     Ok((count, slots))
 }
 
+// Returns true if the response is a `CLUSTER SHARDS` response. Each shard is described by a map (an array of alternating keys
+// and values in RESP2), while the slot ranges of `CLUSTER SLOTS` are arrays that start with the range's boundaries.
+fn is_cluster_shards_response(raw_topology_resp: &Value) -> bool {
+    let Value::Array(items) = raw_topology_resp else {
+        return false;
+    };
+    match items.first() {
+        Some(Value::Map(_)) => true,
+        Some(Value::Array(fields)) => {
+            matches!(
+                fields.first(),
+                Some(Value::BulkString(_) | Value::SimpleString(_))
+            )
+        }
+        _ => false,
+    }
+}
+
+// Returns the fields of a map in the `CLUSTER SHARDS` response, in both RESP2 and RESP3.
+fn shards_response_fields(value: &Value) -> Option<HashMap<String, &Value>> {
+    let pairs: Vec<(&Value, &Value)> = match value {
+        Value::Map(pairs) => pairs.iter().map(|(key, value)| (key, value)).collect(),
+        Value::Array(items) if items.len() % 2 == 0 => items
+            .chunks_exact(2)
+            .map(|pair| (&pair[0], &pair[1]))
+            .collect(),
+        _ => return None,
+    };
+    pairs
+        .into_iter()
+        .map(|(key, value)| Some((shards_response_string(key)?, value)))
+        .collect()
+}
+
+fn shards_response_string(value: &Value) -> Option<String> {
+    match value {
+        Value::BulkString(bytes) => Some(String::from_utf8_lossy(bytes).into_owned()),
+        Value::SimpleString(string) => Some(string.clone()),
+        Value::VerbatimString { text, .. } => Some(text.clone()),
+        _ => None,
+    }
+}
+
+fn shards_response_int(value: &Value) -> Option<i64> {
+    match value {
+        Value::Int(int) => Some(*int),
+        _ => shards_response_string(value)?.parse().ok(),
+    }
+}
+
+// Parse slot data and nodes' metadata from a raw `CLUSTER SHARDS` response.
+pub(crate) fn parse_and_count_shards(
+    raw_shards_resp: &Value,
+    tls: Option<TlsMode>,
+    // The DNS address of the node from which `raw_shards_resp` was received.
+    addr_of_answering_node: &str,
+) -> RedisResult<((u16, Vec<Slot>), NodesMetadata)> {
+    let mut slots = Vec::with_capacity(2);
+    let mut nodes_metadata = NodesMetadata::new();
+    let mut count = 0;
+
+    if let Value::Array(shards) = raw_shards_resp {
+        for shard in shards.iter().filter_map(shards_response_fields) {
+            let ranges: Vec<(u16, u16)> = match shard.get("slots") {
+                Some(Value::Array(bounds)) => bounds
+                    .chunks_exact(2)
+                    .filter_map(|range| {
+                        Some((
+                            shards_response_int(&range[0])? as u16,
+                            shards_response_int(&range[1])? as u16,
+                        ))
+                    })
+                    .filter(|(start, end)| start <= end)
+                    .collect(),
+                _ => continue,
+            };
+            let Some(Value::Array(nodes)) = shard.get("nodes") else {
+                continue;
+            };
+
+            let mut primary = None;
+            let mut replicas = Vec::new();
+            for node in nodes.iter().filter_map(shards_response_fields) {
+                let endpoint = node
+                    .get("endpoint")
+                    .and_then(|value| shards_response_string(value));
+                // As in `CLUSTER SLOTS`, an empty or missing endpoint means the endpoint of the responding node,
+                // and "?" marks an unknown endpoint.
+                let host = match endpoint.as_deref() {
+                    None | Some("") => addr_of_answering_node.to_string(),
+                    Some("?") => continue,
+                    Some(endpoint) => endpoint.to_string(),
+                };
+                // A node reports "tls-port" only when TLS is enabled, and then "port" is its plaintext port, if any.
+                let port_field = if tls.is_some() { "tls-port" } else { "port" };
+                let Some(port) = node
+                    .get(port_field)
+                    .and_then(|value| shards_response_int(value))
+                else {
+                    continue;
+                };
+                let address = get_connection_addr(host, port as u16, tls, None).to_string();
+
+                let field_string = |field: &str| {
+                    node.get(field)
+                        .and_then(|value| shards_response_string(value))
+                        .filter(|value| !value.is_empty())
+                };
+                nodes_metadata.insert(
+                    address.clone(),
+                    NodeMetadata {
+                        health: field_string("health")
+                            .map(|health| NodeHealth::from_reported(&health))
+                            .unwrap_or_default(),
+                        hostname: field_string("hostname"),
+                        endpoint,
+                        availability_zone: field_string("availability-zone"),
+                    },
+                );
+                match field_string("role").as_deref() {
+                    Some("master") | Some("primary") => primary = Some(address),
+                    _ => replicas.push(address),
+                }
+            }
+
+            let Some(primary) = primary else {
+                continue;
+            };
+            // we sort the replicas, because different nodes in a cluster might return the same shard
+            // with different order of the replicas, which might cause the views to be considered evaluated as not equal.
+            replicas.sort_unstable();
+            for (start, end) in ranges {
+                count += end - start;
+                slots.push(Slot::new(start, end, primary.clone(), replicas.clone()));
+            }
+        }
+    }
+    if slots.is_empty() {
+        return Err(RedisError::from((
+            ErrorKind::ResponseError,
+            "Error parsing shards: No healthy node found",
+            format!("Raw shards response: {raw_shards_resp:?}"),
+        )));
+    }
+    // The order of the shards isn't defined, so the slots are sorted for equal views to hash equally.
+    slots.sort_unstable_by_key(|slot| slot.start);
+
+    Ok(((count, slots), nodes_metadata))
+}
+
+// Parse a topology view, which was received either from `CLUSTER SHARDS` or from `CLUSTER SLOTS`.
+fn parse_topology_view(
+    raw_topology_resp: &Value,
+    tls: Option<TlsMode>,
+    addr_of_answering_node: &str,
+    address_remap: &AddressRemap,
+) -> RedisResult<((u16, Vec<Slot>), NodesMetadata)> {
+    let ((count, mut slots), nodes_metadata) = if is_cluster_shards_response(raw_topology_resp) {
+        parse_and_count_shards(raw_topology_resp, tls, addr_of_answering_node)?
+    } else {
+        (
+            parse_and_count_slots(raw_topology_resp, tls, addr_of_answering_node)?,
+            NodesMetadata::new(),
+        )
+    };
+    if address_remap.is_empty() {
+        return Ok(((count, slots), nodes_metadata));
+    }
+    address_remap.apply_to_slots(&mut slots);
+    let nodes_metadata = nodes_metadata
+        .into_iter()
+        .map(|(address, metadata)| (address_remap.apply(address), metadata))
+        .collect();
+    Ok(((count, slots), nodes_metadata))
+}
+
 fn calculate_hash<T: Hash>(t: &T) -> u64 {
     let mut s = DefaultHasher::new();
     t.hash(&mut s);
@@ -202,15 +475,21 @@ pub(crate) fn calculate_topology<'a>(
     tls_mode: Option<TlsMode>,
     num_of_queried_nodes: usize,
     read_from_replica: ReadFromReplicaStrategy,
+    address_remap: &AddressRemap,
 ) -> RedisResult<(SlotMap, TopologyHash)> {
     let mut hash_view_map = HashMap::new();
     for (host, view) in topology_views {
-        if let Ok(slots_and_count) = parse_and_count_slots(view, tls_mode, host) {
+        if let Ok((slots_and_count, nodes_metadata)) =
+            parse_topology_view(view, tls_mode, host, address_remap)
+        {
+            // Only the slots and the addresses of their nodes are hashed, so views that differ only in the nodes'
+            // metadata, e.g. a node whose health was reported differently by different nodes, reach a consensus.
             let hash_value = calculate_hash(&slots_and_count);
             let topology_entry = hash_view_map.entry(hash_value).or_insert(TopologyView {
                 hash_value,
                 nodes_count: 0,
                 slots_and_count,
+                nodes_metadata,
             });
             topology_entry.nodes_count += 1;
         }
@@ -257,7 +536,8 @@ pub(crate) fn calculate_topology<'a>(
         );
         let slots_data = most_frequent_topology.slots_and_count.1;
         Ok((
-            SlotMap::new(slots_data, read_from_replica),
+            SlotMap::new(slots_data, read_from_replica)
+                .with_nodes_metadata(most_frequent_topology.nodes_metadata),
             most_frequent_topology.hash_value,
         ))
     };
@@ -526,6 +806,7 @@ mod tests {
             None,
             queried_nodes,
             ReadFromReplicaStrategy::AlwaysFromPrimary,
+            &AddressRemap::default(),
         )
         .unwrap();
         let res = collect_shard_addrs(&topology_view);
@@ -549,6 +830,7 @@ mod tests {
             None,
             queried_nodes,
             ReadFromReplicaStrategy::AlwaysFromPrimary,
+            &AddressRemap::default(),
         );
         assert!(topology_view.is_err());
     }
@@ -568,6 +850,7 @@ mod tests {
             None,
             queried_nodes,
             ReadFromReplicaStrategy::AlwaysFromPrimary,
+            &AddressRemap::default(),
         )
         .unwrap();
         let res = collect_shard_addrs(&topology_view);
@@ -591,6 +874,7 @@ mod tests {
             None,
             queried_nodes,
             ReadFromReplicaStrategy::AlwaysFromPrimary,
+            &AddressRemap::default(),
         )
         .unwrap();
         let res = collect_shard_addrs(&topology_view);
@@ -615,6 +899,7 @@ mod tests {
             None,
             queried_nodes,
             ReadFromReplicaStrategy::AlwaysFromPrimary,
+            &AddressRemap::default(),
         )
         .unwrap();
         let res = collect_shard_addrs(&topology_view);
@@ -639,6 +924,7 @@ mod tests {
             None,
             queried_nodes,
             ReadFromReplicaStrategy::AlwaysFromPrimary,
+            &AddressRemap::default(),
         )
         .unwrap();
         let res = collect_shard_addrs(&topology_view);
@@ -646,4 +932,298 @@ mod tests {
         let expected = vec![node_1];
         assert_eq!(res, expected);
     }
+
+    fn shards_map_value(fields: Vec<(&str, Value)>, resp3: bool) -> Value {
+        let fields = fields
+            .into_iter()
+            .map(|(key, value)| (Value::BulkString(key.as_bytes().to_vec()), value));
+        if resp3 {
+            Value::Map(fields.collect())
+        } else {
+            Value::Array(fields.flat_map(|(key, value)| [key, value]).collect())
+        }
+    }
+
+    fn shard_node_value(endpoint: &str, port: u16, role: &str, health: &str, resp3: bool) -> Value {
+        let string = |value: &str| Value::BulkString(value.as_bytes().to_vec());
+        shards_map_value(
+            vec![
+                ("id", string(&format!("{endpoint}-{port}-id"))),
+                ("port", Value::Int(port as i64)),
+                ("endpoint", string(endpoint)),
+                ("hostname", string(&format!("{endpoint}.example.com"))),
+                ("role", string(role)),
+                ("replication-offset", Value::Int(72156)),
+                ("health", string(health)),
+                ("availability-zone", string("use-1a")),
+            ],
+            resp3,
+        )
+    }
+
+    fn shard_value(ranges: &[(u16, u16)], nodes: Vec<Value>, resp3: bool) -> Value {
+        shards_map_value(
+            vec![
+                (
+                    "slots",
+                    Value::Array(
+                        ranges
+                            .iter()
+                            .flat_map(|(start, end)| {
+                                [Value::Int(*start as i64), Value::Int(*end as i64)]
+                            })
+                            .collect(),
+                    ),
+                ),
+                ("nodes", Value::Array(nodes)),
+            ],
+            resp3,
+        )
+    }
+
+    fn shards_view(resp3: bool) -> Vec<Value> {
+        vec![
+            shard_value(
+                &[(0, 4000), (8001, 9000)],
+                vec![
+                    shard_node_value("primary1", 6379, "master", "online", resp3),
+                    shard_node_value("replica1_1", 6379, "replica", "loading", resp3),
+                ],
+                resp3,
+            ),
+            shard_value(
+                &[(4001, 8000), (9001, 16383)],
+                vec![
+                    shard_node_value("replica2_2", 6379, "replica", "online", resp3),
+                    shard_node_value("", 6380, "master", "online", resp3),
+                    shard_node_value("replica2_1", 6379, "replica", "fail", resp3),
+                ],
+                resp3,
+            ),
+        ]
+    }
+
+    #[test]
+    fn parse_shards_returns_slots_and_nodes_metadata() {
+        let view = Value::Array(shards_view(true));
+
+        let ((slot_count, slots), nodes_metadata) =
+            parse_and_count_shards(&view, None, "node").unwrap();
+        assert_eq!(slot_count, 4000 + 999 + 3999 + 7382);
+        assert_eq!(
+            slots
+                .iter()
+                .map(|slot| (slot.start, slot.end, slot.master()))
+                .collect::<Vec<_>>(),
+            vec![
+                (0, 4000, "primary1:6379"),
+                (4001, 8000, "node:6380"),
+                (8001, 9000, "primary1:6379"),
+                (9001, 16383, "node:6380"),
+            ]
+        );
+        assert_eq!(
+            slots[1].replicas(),
+            vec!["replica2_1:6379", "replica2_2:6379"]
+        );
+
+        assert_eq!(
+            nodes_metadata.get("replica1_1:6379"),
+            Some(&NodeMetadata {
+                health: NodeHealth::Loading,
+                hostname: Some("replica1_1.example.com".to_string()),
+                endpoint: Some("replica1_1".to_string()),
+                availability_zone: Some("use-1a".to_string()),
+            })
+        );
+        assert_eq!(
+            nodes_metadata.get("replica2_1:6379").unwrap().health,
+            NodeHealth::Failed
+        );
+        assert_eq!(
+            nodes_metadata.get("node:6380").unwrap().health,
+            NodeHealth::Online
+        );
+    }
+
+    #[test]
+    fn parse_shards_in_resp2_and_resp3_returns_the_same_view() {
+        let resp2_view = Value::Array(shards_view(false));
+        let resp3_view = Value::Array(shards_view(true));
+
+        let res1 = parse_and_count_shards(&resp2_view, None, "node").unwrap();
+        let res2 = parse_and_count_shards(&resp3_view, None, "node").unwrap();
+        assert_eq!(calculate_hash(&res1), calculate_hash(&res2));
+    }
+
+    #[test]
+    fn parse_shards_with_different_shards_order_returns_the_same_view() {
+        let mut shards = shards_view(true);
+        let view1 = Value::Array(shards.clone());
+        shards.reverse();
+        let view2 = Value::Array(shards);
+
+        let res1 = parse_and_count_shards(&view1, None, "node").unwrap();
+        let res2 = parse_and_count_shards(&view2, None, "node").unwrap();
+        assert_eq!(calculate_hash(&res1), calculate_hash(&res2));
+    }
+
+    #[test]
+    fn test_topology_calculator_tracks_node_health_from_shards_response() {
+        let view = Value::Array(shards_view(true));
+        let (slot_map, _) = calculate_topology(
+            [("node", &view)].into_iter(),
+            1,
+            None,
+            1,
+            ReadFromReplicaStrategy::RoundRobin,
+            &AddressRemap::default(),
+        )
+        .unwrap();
+
+        assert!(slot_map.is_node_healthy("primary1:6379"));
+        assert!(slot_map.is_node_healthy("replica2_2:6379"));
+        assert!(!slot_map.is_node_healthy("replica1_1:6379"));
+        assert!(!slot_map.is_node_healthy("replica2_1:6379"));
+        assert_eq!(
+            slot_map
+                .node_metadata("primary1:6379")
+                .and_then(|metadata| metadata.availability_zone.as_deref()),
+            Some("use-1a")
+        );
+    }
+
+    #[test]
+    fn test_topology_calculator_ignores_node_health_in_the_topology_hash() {
+        let calculate_hash_of = |view: &Value| {
+            calculate_topology(
+                [("node", view)].into_iter(),
+                1,
+                None,
+                1,
+                ReadFromReplicaStrategy::RoundRobin,
+                &AddressRemap::default(),
+            )
+            .unwrap()
+            .1
+        };
+        let healthy_view = Value::Array(shards_view(true));
+        let mut shards = shards_view(true);
+        shards[0] = shard_value(
+            &[(0, 4000), (8001, 9000)],
+            vec![
+                shard_node_value("primary1", 6379, "master", "online", true),
+                shard_node_value("replica1_1", 6379, "replica", "fail", true),
+            ],
+            true,
+        );
+        let unhealthy_view = Value::Array(shards);
+
+        assert_eq!(
+            calculate_hash_of(&healthy_view),
+            calculate_hash_of(&unhealthy_view)
+        );
+    }
+
+    #[test]
+    fn parse_shards_uses_the_port_that_matches_the_tls_mode() {
+        let string = |value: &str| Value::BulkString(value.as_bytes().to_vec());
+        let node = |fields: Vec<(&str, Value)>| {
+            shards_map_value(
+                [
+                    ("endpoint", string("primary")),
+                    ("role", string("master")),
+                    ("health", string("online")),
+                ]
+                .into_iter()
+                .chain(fields)
+                .collect(),
+                true,
+            )
+        };
+        let view = |node| Value::Array(vec![shard_value(&[(0, 16383)], vec![node], true)]);
+        let primary = |view: &Value, tls| {
+            parse_and_count_shards(view, tls, "node")
+                .map(|((_, slots), _)| slots[0].master().to_string())
+        };
+
+        let both_ports = view(node(vec![
+            ("port", Value::Int(6379)),
+            ("tls-port", Value::Int(6380)),
+        ]));
+        assert_eq!(primary(&both_ports, None).unwrap(), "primary:6379");
+        assert_eq!(
+            primary(&both_ports, Some(TlsMode::Secure)).unwrap(),
+            "primary:6380"
+        );
+
+        let plaintext_port = view(node(vec![("port", Value::Int(6379))]));
+        assert!(primary(&plaintext_port, Some(TlsMode::Secure)).is_err());
+        let tls_port = view(node(vec![("tls-port", Value::Int(6380))]));
+        assert!(primary(&tls_port, None).is_err());
+    }
+
+    #[test]
+    fn address_remap_translates_addresses_and_hosts() {
+        let address_remap = AddressRemap::new()
+            .address("172.17.0.2:6379", "localhost:7000")
+            .host_rule("172.17.*", "docker-host")
+            .host_rule("*.svc.cluster.local", "proxy")
+            .host_rule("*", "fallback");
+
+        assert_eq!(
+            address_remap.translate("172.17.0.2:6379"),
+            Some("localhost:7000".to_string())
+        );
+        assert_eq!(
+            address_remap.translate("172.17.0.3:6380"),
+            Some("docker-host:6380".to_string())
+        );
+        assert_eq!(
+            address_remap.translate("node-1.redis.svc.cluster.local:6379"),
+            Some("proxy:6379".to_string())
+        );
+        assert_eq!(
+            address_remap.translate("10.0.0.1:6379"),
+            Some("fallback:6379".to_string())
+        );
+        assert_eq!(AddressRemap::new().translate("10.0.0.1:6379"), None);
+        assert_eq!(
+            AddressRemap::new()
+                .host_rule("10.0.0.1", "node")
+                .translate("10.0.0.12:6379"),
+            None
+        );
+    }
+
+    #[test]
+    fn test_topology_calculator_applies_address_remap() {
+        let view = Value::Array(shards_view(true));
+        let address_remap = AddressRemap::new()
+            .address("primary1:6379", "localhost:7000")
+            .host_rule("replica1_*", "replica1");
+        let (slot_map, _) = calculate_topology(
+            [("node", &view)].into_iter(),
+            1,
+            None,
+            1,
+            ReadFromReplicaStrategy::AlwaysFromPrimary,
+            &address_remap,
+        )
+        .unwrap();
+
+        let shard_addrs = slot_map.shard_addrs_for_slot(0).unwrap();
+        assert_eq!(shard_addrs.primary().as_str(), "localhost:7000");
+        assert_eq!(
+            shard_addrs
+                .replicas()
+                .iter()
+                .map(|replica| replica.as_str())
+                .collect::<Vec<_>>(),
+            vec!["replica1:6379"]
+        );
+        assert!(!slot_map.is_node_healthy("replica1:6379"));
+        assert!(slot_map.node_metadata("localhost:7000").is_some());
+        assert!(slot_map.node_metadata("primary1:6379").is_none());
+    }
 }
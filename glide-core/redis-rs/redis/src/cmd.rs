@@ -586,6 +586,27 @@ impl Cmd {
         Some(&self.data[start..end])
     }
 
+    /// Prepends `prefix` to the arguments at `indices`, where index 0 is the command name.
+    pub fn prefix_args(&mut self, indices: &[usize], prefix: &[u8]) -> &mut Cmd {
+        if indices.is_empty() || prefix.is_empty() {
+            return self;
+        }
+        let mut data = Vec::with_capacity(self.data.len() + indices.len() * prefix.len());
+        let mut prev = 0;
+        for (idx, arg) in self.args.iter_mut().enumerate() {
+            if let Arg::Simple(end) = arg {
+                if indices.contains(&idx) {
+                    data.extend_from_slice(prefix);
+                }
+                data.extend_from_slice(&self.data[prev..*end]);
+                prev = *end;
+                *end = data.len();
+            }
+        }
+        self.data = data;
+        self
+    }
+
     /// Client won't read and wait for results. Currently only used for Pub/Sub commands in RESP3.
     #[inline]
     pub fn set_no_response(&mut self, nr: bool) -> &mut Cmd {
@@ -682,4 +703,16 @@ mod tests {
         assert_eq!(c.arg_idx(3), None);
         assert_eq!(c.arg_idx(4), None);
     }
+
+    #[test]
+    fn test_cmd_prefix_args() {
+        let mut c = Cmd::new();
+        c.arg("MSET").arg("foo").arg("1").arg("bar").arg("2");
+        c.prefix_args(&[1, 3], b"app:");
+        assert_eq!(c.arg_idx(0), Some(&b"MSET"[..]));
+        assert_eq!(c.arg_idx(1), Some(&b"app:foo"[..]));
+        assert_eq!(c.arg_idx(2), Some(&b"1"[..]));
+        assert_eq!(c.arg_idx(3), Some(&b"app:bar"[..]));
+        assert_eq!(c.arg_idx(4), Some(&b"2"[..]));
+    }
 }
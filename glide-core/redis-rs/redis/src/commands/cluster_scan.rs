@@ -90,7 +90,7 @@ type SlotsBitsArray = [u64; BITS_ARRAY_SIZE as usize];
 ///     .build();
 /// ```
 
-#[derive(Clone, Default)]
+#[derive(Debug, Clone, Default)]
 pub struct ClusterScanArgs {
     /// Reference-counted scan state cursor, managed internally.
     pub scan_state_cursor: ScanStateRC,
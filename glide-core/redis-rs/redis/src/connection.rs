@@ -207,6 +207,26 @@ pub enum PubSubSubscriptionKind {
     Sharded = 2,
 }
 
+impl PubSubSubscriptionKind {
+    /// Returns the name of the command that subscribes to channels or patterns of this kind.
+    pub fn subscribe_command(&self) -> &'static str {
+        match self {
+            PubSubSubscriptionKind::Exact => "SUBSCRIBE",
+            PubSubSubscriptionKind::Pattern => "PSUBSCRIBE",
+            PubSubSubscriptionKind::Sharded => "SSUBSCRIBE",
+        }
+    }
+
+    /// Returns the name of the command that unsubscribes from channels or patterns of this kind.
+    pub fn unsubscribe_command(&self) -> &'static str {
+        match self {
+            PubSubSubscriptionKind::Exact => "UNSUBSCRIBE",
+            PubSubSubscriptionKind::Pattern => "PUNSUBSCRIBE",
+            PubSubSubscriptionKind::Sharded => "SUNSUBSCRIBE",
+        }
+    }
+}
+
 impl From<PubSubSubscriptionKind> for usize {
     fn from(val: PubSubSubscriptionKind) -> Self {
         val as usize
@@ -603,15 +623,17 @@ impl ActualConnection {
             } => {
                 let host: &str = host;
                 let config = create_rustls_config(insecure, tls_params.as_ref().cloned())?;
-                let server_name = rustls_pki_types::ServerName::try_from(host)
-                    .map_err(|e| {
-                        RedisError::from((
-                            ErrorKind::InvalidClientConfig,
-                            "Invalid hostname for TLS",
-                            format!("{e}"),
-                        ))
-                    })?
-                    .to_owned();
+                let server_name = rustls_pki_types::ServerName::try_from(
+                    TlsConnParams::server_name(tls_params, host),
+                )
+                .map_err(|e| {
+                    RedisError::from((
+                        ErrorKind::InvalidClientConfig,
+                        "Invalid hostname for TLS",
+                        format!("{e}"),
+                    ))
+                })?
+                .to_owned();
                 let conn =
                     rustls::ClientConnection::new(Arc::new(config), server_name).map_err(|e| {
                         RedisError::from((
@@ -958,8 +980,18 @@ fn setup_connection(
     if connection_info.protocol != ProtocolVersion::RESP2 {
         let hello_cmd = resp3_hello(connection_info);
         let val: RedisResult<Value> = hello_cmd.query(&mut rv);
-        if let Err(err) = val {
-            return Err(get_resp3_hello_command_error(err));
+        match val {
+            Ok(_) => rv.protocol = ProtocolVersion::RESP3,
+            Err(err)
+                if connection_info.protocol == ProtocolVersion::Auto
+                    && is_resp3_unsupported(err.code(), err.detail()) =>
+            {
+                rv.protocol = ProtocolVersion::RESP2;
+                if connection_info.password.is_some() {
+                    connect_auth(&mut rv, connection_info)?;
+                }
+            }
+            Err(err) => return Err(get_resp3_hello_command_error(err)),
         }
     } else if connection_info.password.is_some() {
         connect_auth(&mut rv, connection_info)?;
@@ -1708,6 +1740,17 @@ pub fn resp3_is_pub_sub_state_cleared(
     *received_unsub && *received_punsub && num == 0
 }
 
+/// Returns true if the error of a failed HELLO command means that the server doesn't support RESP3,
+/// i.e. HELLO is an unknown command, as opposed to the command being rejected because of its arguments,
+/// e.g. wrong credentials.
+pub(crate) fn is_resp3_unsupported(error_code: Option<&str>, detail: Option<&str>) -> bool {
+    match error_code {
+        Some("NOPROTO") => true,
+        Some("ERR") => detail.is_some_and(|detail| detail.starts_with("unknown command")),
+        _ => false,
+    }
+}
+
 /// Common logic for checking real cause of hello3 command error
 pub fn get_resp3_hello_command_error(err: RedisError) -> RedisError {
     if let Some(detail) = err.detail() {
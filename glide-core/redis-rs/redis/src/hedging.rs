@@ -0,0 +1,151 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use crate::types::{ErrorKind, RedisResult};
+use std::sync::Mutex;
+use std::time::Duration;
+
+/// The number of recent response times that percentiles are calculated over.
+const RESPONSE_TIMES_WINDOW: usize = 512;
+/// The percentile is recalculated after this number of new response times.
+/// Reads aren't hedged by a percentile until this number of response times was recorded.
+const RECALCULATION_INTERVAL: usize = 32;
+
+/// Decides when a read that wasn't answered yet is also sent to another node that serves it.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum HedgePolicy {
+    /// Hedge reads that weren't answered within a fixed delay.
+    Delay(Duration),
+    /// Hedge reads that weren't answered within the given percentile, between 0 and 100, of the recent read response times.
+    Percentile(f64),
+}
+
+/// Hedges reads according to a [`HedgePolicy`], and tracks the read response times that a percentile policy relies on.
+///
+/// A hedged read is sent to a second node once the hedging delay passes. The first response is used,
+/// and the other request is dropped, so a single slow node, e.g. one that pauses for garbage collection,
+/// doesn't delay the reads that it serves.
+#[derive(Debug)]
+pub struct ReadHedging {
+    policy: HedgePolicy,
+    response_times: Mutex<ResponseTimes>,
+}
+
+#[derive(Debug, Default)]
+struct ResponseTimes {
+    samples: Vec<Duration>,
+    next_index: usize,
+    new_samples: usize,
+    percentile: Option<Duration>,
+}
+
+impl ResponseTimes {
+    fn record(&mut self, response_time: Duration, percentile: f64) {
+        if self.samples.len() < RESPONSE_TIMES_WINDOW {
+            self.samples.push(response_time);
+        } else {
+            self.samples[self.next_index] = response_time;
+        }
+        self.next_index = (self.next_index + 1) % RESPONSE_TIMES_WINDOW;
+        self.new_samples += 1;
+        if self.new_samples < RECALCULATION_INTERVAL {
+            return;
+        }
+        self.new_samples = 0;
+        let mut sorted = self.samples.clone();
+        sorted.sort_unstable();
+        let rank = (percentile.clamp(0.0, 100.0) / 100.0 * (sorted.len() - 1) as f64).round();
+        self.percentile = Some(sorted[rank as usize]);
+    }
+}
+
+impl ReadHedging {
+    /// Creates a hedging policy. A percentile policy doesn't hedge reads until enough response times were recorded.
+    ///
+    /// A zero delay is rejected, since it would send every read to two nodes.
+    pub fn new(policy: HedgePolicy) -> RedisResult<Self> {
+        if policy == HedgePolicy::Delay(Duration::ZERO) {
+            return Err((
+                ErrorKind::InvalidClientConfig,
+                "The read hedging delay must be positive",
+            )
+                .into());
+        }
+        Ok(Self {
+            policy,
+            response_times: Mutex::new(ResponseTimes::default()),
+        })
+    }
+
+    /// Returns the policy that decides when reads are hedged.
+    pub fn policy(&self) -> HedgePolicy {
+        self.policy
+    }
+
+    /// Returns how long to wait for a response before hedging a read, or `None` if reads shouldn't be hedged yet.
+    pub fn delay(&self) -> Option<Duration> {
+        match self.policy {
+            HedgePolicy::Delay(delay) => Some(delay),
+            HedgePolicy::Percentile(_) => self.response_times.lock().unwrap().percentile,
+        }
+    }
+
+    /// Records the response time of a read.
+    ///
+    /// The response time of a hedged read is the time until its first response. Such a response time is never shorter than the
+    /// hedging delay, so the percentiles up to the delay are unaffected by hedging, and the hedging delay stays stable.
+    pub fn record(&self, response_time: Duration) {
+        if let HedgePolicy::Percentile(percentile) = self.policy {
+            self.response_times
+                .lock()
+                .unwrap()
+                .record(response_time, percentile);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_read_hedging_with_fixed_delay() {
+        let hedging = ReadHedging::new(HedgePolicy::Delay(Duration::from_millis(5))).unwrap();
+        assert_eq!(hedging.delay(), Some(Duration::from_millis(5)));
+        hedging.record(Duration::from_millis(100));
+        assert_eq!(hedging.delay(), Some(Duration::from_millis(5)));
+    }
+
+    #[test]
+    fn test_read_hedging_rejects_zero_delay() {
+        let err = ReadHedging::new(HedgePolicy::Delay(Duration::ZERO)).unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::InvalidClientConfig);
+    }
+
+    #[test]
+    fn test_read_hedging_by_percentile_waits_for_enough_response_times() {
+        let hedging = ReadHedging::new(HedgePolicy::Percentile(90.0)).unwrap();
+        for _ in 1..RECALCULATION_INTERVAL {
+            hedging.record(Duration::from_millis(1));
+        }
+        assert_eq!(hedging.delay(), None);
+
+        hedging.record(Duration::from_millis(1));
+        assert_eq!(hedging.delay(), Some(Duration::from_millis(1)));
+    }
+
+    #[test]
+    fn test_read_hedging_by_percentile_follows_the_recent_response_times() {
+        let hedging = ReadHedging::new(HedgePolicy::Percentile(80.0)).unwrap();
+        for millis in 1..=100 {
+            hedging.record(Duration::from_millis(millis));
+        }
+        // The percentile was last recalculated over the first 96 response times.
+        assert_eq!(hedging.delay(), Some(Duration::from_millis(77)));
+
+        // Older response times are replaced by newer ones.
+        for _ in 0..RESPONSE_TIMES_WINDOW {
+            hedging.record(Duration::from_millis(3));
+        }
+        assert_eq!(hedging.delay(), Some(Duration::from_millis(3)));
+    }
+}
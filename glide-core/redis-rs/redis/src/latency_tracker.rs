@@ -0,0 +1,89 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Duration;
+
+/// A new round-trip sample contributes 1/`SMOOTHING_DIVISOR` of the updated moving average.
+/// A lower value reacts faster to latency changes, a higher value smooths out short spikes.
+const SMOOTHING_DIVISOR: u128 = 5;
+
+/// Marks a tracker that hasn't received any samples yet.
+const NO_SAMPLES: u64 = u64::MAX;
+
+/// Tracks an exponentially weighted moving average of a node's round-trip time.
+///
+/// The tracker is lock-free, so it can be updated by the periodic connection checks
+/// while being concurrently read by the request routing logic.
+#[derive(Debug)]
+pub struct LatencyTracker {
+    average_micros: AtomicU64,
+}
+
+impl Default for LatencyTracker {
+    fn default() -> Self {
+        Self {
+            average_micros: AtomicU64::new(NO_SAMPLES),
+        }
+    }
+}
+
+impl LatencyTracker {
+    /// Adds a round-trip time sample to the moving average.
+    pub fn record(&self, round_trip_time: Duration) {
+        let sample = round_trip_time.as_micros().min((NO_SAMPLES - 1) as u128) as u64;
+        let _ = self
+            .average_micros
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |current| {
+                if current == NO_SAMPLES {
+                    return Some(sample);
+                }
+                let updated = (sample as u128 + (SMOOTHING_DIVISOR - 1) * current as u128)
+                    / SMOOTHING_DIVISOR;
+                Some(updated as u64)
+            });
+    }
+
+    /// Returns the current moving average, or `None` if no samples were recorded.
+    pub fn average(&self) -> Option<Duration> {
+        match self.average_micros.load(Ordering::Relaxed) {
+            NO_SAMPLES => None,
+            micros => Some(Duration::from_micros(micros)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_tracker_has_no_average_without_samples() {
+        let tracker = LatencyTracker::default();
+        assert_eq!(tracker.average(), None);
+    }
+
+    #[test]
+    fn test_latency_tracker_first_sample_is_the_average() {
+        let tracker = LatencyTracker::default();
+        tracker.record(Duration::from_micros(500));
+        assert_eq!(tracker.average(), Some(Duration::from_micros(500)));
+    }
+
+    #[test]
+    fn test_latency_tracker_moves_towards_new_samples() {
+        let tracker = LatencyTracker::default();
+        tracker.record(Duration::from_micros(1000));
+        tracker.record(Duration::from_micros(2000));
+        // (2000 + 4 * 1000) / 5
+        assert_eq!(tracker.average(), Some(Duration::from_micros(1200)));
+
+        for _ in 0..100 {
+            tracker.record(Duration::from_micros(100));
+        }
+        let average = tracker.average().unwrap();
+        assert!(
+            average >= Duration::from_micros(100) && average < Duration::from_micros(110),
+            "Average {average:?} didn't converge to the latest samples"
+        );
+    }
+}
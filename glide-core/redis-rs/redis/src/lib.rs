@@ -337,7 +337,7 @@ assert_eq!(result, Ok(("foo".to_string(), b"bar".to_vec())));
 
 // public api
 pub use crate::client::Client;
-pub use crate::client::GlideConnectionOptions;
+pub use crate::client::{ClientTrackingMode, GlideConnectionOptions};
 pub use crate::cmd::{cmd, pack_command, pipe, Arg, Cmd, Iter};
 pub use crate::commands::{
     Commands, ControlFlow, Direction, LposOptions, PubSubCommands, SetOptions,
@@ -349,6 +349,11 @@ pub use crate::connection::{
 };
 pub use crate::parser::{parse_redis_value, Parser};
 pub use crate::pipeline::{Pipeline, PipelineRetryStrategy};
+pub use circuit_breaker::{
+    with_request_timeout, CircuitBreaker, CircuitBreakerAttempt, CircuitState, NodeRequest,
+};
+pub use hedging::{HedgePolicy, ReadHedging};
+pub use latency_tracker::LatencyTracker;
 pub use push_manager::{PushInfo, PushManager};
 pub use retry_strategies::RetryStrategy;
 
@@ -423,6 +428,23 @@ mod cluster_client;
 pub mod testing {
     #[cfg(feature = "cluster")]
     pub use crate::cluster_client::ClusterParams;
+
+    /// Runs the connection handshake over `con`, sending each of its commands in its own round trip if `sequential`
+    /// is set, or all of them in a single pipeline otherwise. Used to benchmark the pipelined handshake.
+    #[cfg(all(feature = "aio", feature = "bench"))]
+    pub async fn setup_connection<C: crate::aio::ConnectionLike>(
+        connection_info: &crate::RedisConnectionInfo,
+        con: &mut C,
+        discover_az: bool,
+        sequential: bool,
+    ) -> crate::RedisResult<()> {
+        let mode = if sequential {
+            crate::aio::HandshakeMode::Sequential
+        } else {
+            crate::aio::HandshakeMode::Pipelined
+        };
+        crate::aio::setup_connection_with_mode(connection_info, con, discover_az, None, mode).await
+    }
 }
 
 #[cfg(feature = "cluster")]
@@ -444,12 +466,15 @@ pub mod sentinel;
 
 mod tls;
 
-pub use crate::tls::{ClientTlsConfig, TlsCertificates};
+pub use crate::tls::{retrieve_tls_certificates, ClientTlsConfig, TlsCertificates, TlsConnParams};
 
+mod circuit_breaker;
 mod client;
 mod cmd;
 mod commands;
 mod connection;
+mod hedging;
+mod latency_tracker;
 mod parser;
 mod push_manager;
 mod retry_strategies;
@@ -305,6 +305,12 @@ macro_rules! implement_pipeline_commands {
                 self.commands.iter()
             }
 
+            /// Returns a mutable iterator over all the commands currently in the pipeline.
+            /// Commands that are shared with clones of the pipeline are copied before they're returned.
+            pub fn cmd_iter_mut(&mut self) -> impl Iterator<Item = &mut Cmd> {
+                self.commands.iter_mut().map(Arc::make_mut)
+            }
+
             /// Instructs the pipeline to ignore the return value of this command.
             /// It will still be ensured that it is not an error, but any successful
             /// result is just thrown away.  This makes result processing through
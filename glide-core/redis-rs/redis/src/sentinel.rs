@@ -54,6 +54,7 @@
 //!         "master_name",
 //!         Some(&SentinelNodeConnectionInfo {
 //!             tls_mode: None,
+//!             tls_params: None,
 //!             redis_connection_info: Some(RedisConnectionInfo {
 //!                 db: 1,
 //!                 username: Some(String::from("foo")),
@@ -71,6 +72,7 @@
 //!         "master_name",
 //!         Some(&SentinelNodeConnectionInfo {
 //!             tls_mode: Some(redis::TlsMode::Secure),
+//!             tls_params: None,
 //!             redis_connection_info: None,
 //!         }),
 //!     )
@@ -90,6 +92,7 @@
 //!     String::from("master1"),
 //!     Some(SentinelNodeConnectionInfo {
 //!         tls_mode: Some(redis::TlsMode::Insecure),
+//!         tls_params: None,
 //!         redis_connection_info: Some(RedisConnectionInfo {
 //!             username: Some(String::from("user")),
 //!             password: Some(String::from("pass")),
@@ -113,7 +116,8 @@ use crate::aio::MultiplexedConnection as AsyncConnection;
 
 use crate::{
     client::GlideConnectionOptions, connection::ConnectionInfo, types::RedisResult, Client, Cmd,
-    Connection, ErrorKind, FromRedisValue, IntoConnectionInfo, RedisConnectionInfo, TlsMode, Value,
+    Connection, ErrorKind, FromRedisValue, IntoConnectionInfo, RedisConnectionInfo, TlsConnParams,
+    TlsMode, Value,
 };
 
 /// The Sentinel type, serves as a special purpose client which builds other clients on
@@ -134,6 +138,9 @@ pub struct SentinelNodeConnectionInfo {
     /// (just a plain TCP connection).
     pub tls_mode: Option<TlsMode>,
 
+    /// Custom TLS certificates to use when `tls_mode` is set, see [`crate::retrieve_tls_certificates`].
+    pub tls_params: Option<TlsConnParams>,
+
     /// The Redis specific/connection independent information to be used.
     pub redis_connection_info: Option<RedisConnectionInfo>,
 }
@@ -146,13 +153,13 @@ impl SentinelNodeConnectionInfo {
                 host: ip,
                 port,
                 insecure: false,
-                tls_params: None,
+                tls_params: self.tls_params.clone(),
             },
             Some(TlsMode::Insecure) => crate::ConnectionAddr::TcpTls {
                 host: ip,
                 port,
                 insecure: true,
-                tls_params: None,
+                tls_params: self.tls_params.clone(),
             },
         };
 
@@ -167,6 +174,7 @@ impl Default for &SentinelNodeConnectionInfo {
     fn default() -> Self {
         static DEFAULT_VALUE: SentinelNodeConnectionInfo = SentinelNodeConnectionInfo {
             tls_mode: None,
+            tls_params: None,
             redis_connection_info: None,
         };
         &DEFAULT_VALUE
@@ -663,6 +671,19 @@ impl Sentinel {
         try_connect_to_first_replica(&addresses, start_index)
     }
 
+    /// Determines the addresses of all the valid replicas of the given master name.
+    pub async fn async_replica_addresses_for(
+        &mut self,
+        service_name: &str,
+        node_connection_info: Option<&SentinelNodeConnectionInfo>,
+    ) -> RedisResult<Vec<ConnectionInfo>> {
+        self.async_find_valid_replica_addresses(
+            service_name,
+            node_connection_info.unwrap_or_default(),
+        )
+        .await
+    }
+
     /// Attempts to connect to a different replica of the given master name each time.
     /// There is no guarantee that we'll actually be connecting to a different replica
     /// in the next call, but in a static set of replicas (no replicas added or
@@ -18,6 +18,7 @@ pub struct ClientTlsConfig {
 /// Structure to hold TLS certificates
 /// - `client_tls`: binaries of clientkey and certificate within a `ClientTlsConfig` structure if mTLS is used
 /// - `root_cert`: binary CA certificate in PEM format if CA is not in local truststore
+/// - `server_name`: name used for SNI and certificate verification instead of the connected host
 ///
 #[derive(Clone)]
 pub struct TlsCertificates {
@@ -25,6 +26,8 @@ pub struct TlsCertificates {
     pub client_tls: Option<ClientTlsConfig>,
     /// root certificate byte stream in PEM format if the local truststore is *not* to be used
     pub root_cert: Option<Vec<u8>>,
+    /// server name to expect in the server's certificate, if it differs from the host we connect to
+    pub server_name: Option<String>,
 }
 
 pub(crate) fn inner_build_with_tls(
@@ -56,12 +59,12 @@ pub(crate) fn inner_build_with_tls(
     Ok(Client { connection_info })
 }
 
-pub(crate) fn retrieve_tls_certificates(
-    certificates: TlsCertificates,
-) -> RedisResult<TlsConnParams> {
+/// Parses the PEM encoded certificates into the parameters used by TLS connections.
+pub fn retrieve_tls_certificates(certificates: TlsCertificates) -> RedisResult<TlsConnParams> {
     let TlsCertificates {
         client_tls,
         root_cert,
+        server_name,
     } = certificates;
 
     let client_tls_params = if let Some(ClientTlsConfig {
@@ -110,6 +113,7 @@ pub(crate) fn retrieve_tls_certificates(
     Ok(TlsConnParams {
         client_tls_params,
         root_cert_store,
+        server_name,
     })
 }
 
@@ -135,8 +139,20 @@ impl Clone for ClientTlsParams {
     }
 }
 
+/// Parsed TLS certificates, created by [`retrieve_tls_certificates`].
 #[derive(Debug, Clone)]
 pub struct TlsConnParams {
     pub(crate) client_tls_params: Option<ClientTlsParams>,
     pub(crate) root_cert_store: Option<RootCertStore>,
+    pub(crate) server_name: Option<String>,
+}
+
+impl TlsConnParams {
+    /// Returns the name to verify the server's certificate against when connecting to `host`.
+    pub(crate) fn server_name<'a>(tls_params: &'a Option<TlsConnParams>, host: &'a str) -> &'a str {
+        tls_params
+            .as_ref()
+            .and_then(|params| params.server_name.as_deref())
+            .unwrap_or(host)
+    }
 }
@@ -156,6 +156,12 @@ pub enum ErrorKind {
     /// Used when an error occurs on when user perform wrong usage of management operation.
     /// E.g. not allowed configuration change.
     UserOperationError,
+
+    /// The node didn't respond to recent requests, so requests to it fail fast until it recovers.
+    CircuitBreakerOpen,
+
+    /// The command was rejected by the client, e.g. by its command policy, and wasn't sent.
+    CommandNotAllowed,
 }
 
 #[derive(PartialEq, Debug, Clone, Display, Copy)]
@@ -947,6 +953,8 @@ impl RedisError {
             ErrorKind::ParseError => "parse error",
             ErrorKind::NotAllSlotsCovered => "not all slots are covered",
             ErrorKind::UserOperationError => "Wrong usage of management operation",
+            ErrorKind::CircuitBreakerOpen => "circuit breaker open",
+            ErrorKind::CommandNotAllowed => "command not allowed",
         }
     }
 
@@ -1151,6 +1159,8 @@ impl RedisError {
             ErrorKind::FatalReceiveError => RetryMethod::Reconnect,
             ErrorKind::FatalSendError => RetryMethod::ReconnectAndRetry,
             ErrorKind::UserOperationError => RetryMethod::NoRetry,
+            ErrorKind::CircuitBreakerOpen => RetryMethod::NoRetry,
+            ErrorKind::CommandNotAllowed => RetryMethod::NoRetry,
         }
     }
 }
@@ -2377,4 +2387,7 @@ pub enum ProtocolVersion {
     /// <https://github.com/redis/redis-specifications/blob/master/protocol/RESP3.md>
     #[default]
     RESP3,
+    /// Uses RESP3 if the server supports it, and falls back to RESP2 otherwise.
+    /// The protocol is negotiated separately for each connection.
+    Auto,
 }
@@ -35,6 +35,12 @@ pub struct MockConnectionBehavior {
     pub connection_id_provider: AtomicUsize,
     pub returned_ip_type: ConnectionIPReturnType,
     pub return_connection_err: ShouldReturnConnectionError,
+    /// Whether `CLUSTER SHARDS` is passed to the handler. Otherwise the node rejects it, like servers that don't support it,
+    /// and describes its topology through `CLUSTER SLOTS`.
+    pub supports_cluster_shards: bool,
+    /// Delays the responses of the nodes on the given ports, e.g. to simulate slow nodes. A request that is dropped
+    /// before its delay passes never reaches the handler.
+    pub response_delays: HashMap<u16, Duration>,
 }
 
 impl MockConnectionBehavior {
@@ -45,6 +51,8 @@ impl MockConnectionBehavior {
             connection_id_provider: AtomicUsize::new(0),
             returned_ip_type: ConnectionIPReturnType::default(),
             return_connection_err: ShouldReturnConnectionError::default(),
+            supports_cluster_shards: false,
+            response_delays: HashMap::new(),
         }
     }
 
@@ -55,10 +63,43 @@ impl MockConnectionBehavior {
     }
 
     fn get_handler(&self) -> Handler {
-        self.handler.clone()
+        let id = self.id.clone();
+        let handler = self.handler.clone();
+        Arc::new(move |cmd, port| {
+            if contains_slice(cmd, b"CLUSTER")
+                && contains_slice(cmd, b"SHARDS")
+                && !supports_cluster_shards(&id)
+            {
+                return Err(Err(RedisError::from((
+                    ErrorKind::ResponseError,
+                    "An error was signalled by the server",
+                    "unknown subcommand 'SHARDS'".to_string(),
+                ))));
+            }
+            handler(cmd, port)
+        })
     }
 }
 
+// Read on every command, so the behavior can be changed after the connections were created.
+fn supports_cluster_shards(id: &str) -> bool {
+    MOCK_CONN_BEHAVIORS
+        .read()
+        .unwrap()
+        .get(id)
+        .is_some_and(|behavior| behavior.supports_cluster_shards)
+}
+
+// Read on every command, so the behavior can be changed after the connections were created.
+#[cfg(feature = "cluster-async")]
+fn response_delay(id: &str, port: u16) -> Option<Duration> {
+    MOCK_CONN_BEHAVIORS
+        .read()
+        .unwrap()
+        .get(id)
+        .and_then(|behavior| behavior.response_delays.get(&port).copied())
+}
+
 pub fn modify_mock_connection_behavior(name: &str, func: impl FnOnce(&mut MockConnectionBehavior)) {
     func(
         get_behaviors()
@@ -83,6 +124,7 @@ pub fn get_mock_connection(name: &str, id: usize) -> MockConnection {
 pub fn get_mock_connection_with_port(name: &str, id: usize, port: u16) -> MockConnection {
     MockConnection {
         id,
+        name: name.to_string(),
         handler: get_mock_connection_handler(name),
         port,
     }
@@ -121,6 +163,8 @@ pub enum ShouldReturnConnectionError {
 #[derive(Clone)]
 pub struct MockConnection {
     pub id: usize,
+    /// The name that the behavior of the connection is registered under.
+    pub name: String,
     pub handler: Handler,
     pub port: u16,
 }
@@ -176,6 +220,7 @@ impl cluster_async::Connect for MockConnection {
                 id: conn_utils
                     .connection_id_provider
                     .fetch_add(1, Ordering::SeqCst),
+                name: name.to_string(),
                 handler: conn_utils.get_handler(),
                 port,
             },
@@ -203,6 +248,7 @@ impl cluster::Connect for MockConnection {
             id: conn_utils
                 .connection_id_provider
                 .fetch_add(1, Ordering::SeqCst),
+            name: name.to_string(),
             handler: conn_utils.get_handler(),
             port,
         })
@@ -349,11 +395,19 @@ pub fn respond_startup_with_config(
 #[cfg(feature = "cluster-async")]
 impl aio::ConnectionLike for MockConnection {
     fn req_packed_command<'a>(&'a mut self, cmd: &'a redis::Cmd) -> RedisFuture<'a, Value> {
-        Box::pin(future::ready(
+        let delay = response_delay(&self.name, self.port);
+        let respond = move || {
             (self.handler)(&cmd.get_packed_command(), self.port)
                 .map_err(|err| err.and_then(|v| v.extract_error()))
-                .expect_err("Handler did not specify a response"),
-        ))
+                .expect_err("Handler did not specify a response")
+        };
+        match delay {
+            Some(delay) => Box::pin(async move {
+                tokio::time::sleep(delay).await;
+                respond()
+            }),
+            None => Box::pin(future::ready(respond())),
+        }
     }
 
     fn req_packed_commands<'a>(
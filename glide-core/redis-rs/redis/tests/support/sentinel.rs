@@ -383,6 +383,7 @@ impl TestSentinelContext {
             } else {
                 None
             },
+            tls_params: None,
             redis_connection_info: None,
         }
     }
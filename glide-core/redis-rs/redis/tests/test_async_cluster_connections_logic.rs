@@ -229,6 +229,7 @@ mod test_connect_and_check {
         let user_conn_id: usize = 1000;
         let user_conn = MockConnection {
             id: user_conn_id,
+            name: name.to_string(),
             handler: get_mock_connection_handler(name),
             port: 6379,
         };
@@ -276,6 +277,7 @@ mod test_connect_and_check {
         let user_conn_id: usize = 1000;
         let user_conn = MockConnection {
             id: user_conn_id,
+            name: name.to_string(),
             handler: get_mock_connection_handler(name),
             port: 6379,
         };
@@ -328,11 +330,13 @@ mod test_connect_and_check {
         let management_conn_id: usize = 2000;
         let old_user_conn = MockConnection {
             id: old_user_conn_id,
+            name: name.to_string(),
             handler: get_mock_connection_handler(name),
             port: 6379,
         };
         let management_conn = MockConnection {
             id: management_conn_id,
+            name: name.to_string(),
             handler: get_mock_connection_handler(name),
             port: 6379,
         };
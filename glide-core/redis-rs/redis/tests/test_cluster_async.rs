@@ -26,14 +26,15 @@ mod cluster_async {
 
     use redis::{
         aio::{ConnectionLike, MultiplexedConnection},
-        cluster::ClusterClient,
+        cluster::{AddressRemap, ClusterClient},
         cluster_async::{testing::MANAGEMENT_CONN_NAME, ClusterConnection, Connect},
         cluster_routing::{
-            MultipleNodeRoutingInfo, Route, RoutingInfo, SingleNodeRoutingInfo, SlotAddr,
+            CommandRoutingTable, ConnectionErrorRetryPolicy, MultipleNodeRoutingInfo, Route,
+            RoutingInfo, SingleNodeRoutingInfo, SlotAddr,
         },
         cluster_topology::{get_slot, DEFAULT_NUMBER_OF_REFRESH_SLOTS_RETRIES},
         cmd, from_owned_redis_value, parse_redis_value, AsyncCommands, Cmd, ErrorKind,
-        FromRedisValue, GlideConnectionOptions, InfoDict, IntoConnectionInfo,
+        FromRedisValue, GlideConnectionOptions, HedgePolicy, InfoDict, IntoConnectionInfo,
         PipelineRetryStrategy, ProtocolVersion, PubSubChannelOrPattern, PubSubSubscriptionInfo,
         PubSubSubscriptionKind, PushInfo, PushKind, RedisError, RedisFuture, RedisResult, Value,
     };
@@ -3108,6 +3109,111 @@ mod cluster_async {
         );
     }
 
+    #[test]
+    #[serial_test::serial]
+    fn test_async_cluster_circuit_breaker_fails_fast_after_node_failures() {
+        let name = "test_async_cluster_circuit_breaker_fails_fast_after_node_failures";
+        let echo_count = Arc::new(atomic::AtomicU16::new(0));
+        let echo_count_clone = echo_count.clone();
+
+        let MockEnv {
+            runtime,
+            async_connection: mut connection,
+            handler: _handler,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")])
+                .retries(0)
+                .circuit_breaker(1),
+            name,
+            move |cmd: &[u8], port| {
+                respond_startup(name, cmd)?;
+
+                if contains_slice(cmd, b"ECHO") && port == 6379 {
+                    echo_count.fetch_add(1, Ordering::Relaxed);
+                    Err(Err(broken_pipe_error()))
+                } else {
+                    panic!("unexpected command {cmd:?}")
+                }
+            },
+        );
+        let routing = RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress {
+            host: name.to_string(),
+            port: 6379,
+        });
+
+        let value = runtime.block_on(connection.route_command(&cmd("ECHO"), routing.clone()));
+        assert_eq!(
+            value.unwrap_err().to_string(),
+            broken_pipe_error().to_string()
+        );
+
+        // The breaker opened after the failure, so the next request fails without reaching the node.
+        let err = runtime
+            .block_on(connection.route_command(&cmd("ECHO"), routing))
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::CircuitBreakerOpen);
+        assert_eq!(err.detail(), Some(format!("{name}:6379").as_str()));
+        assert_eq!(echo_count_clone.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_async_cluster_circuit_breaker_opens_when_a_node_does_not_respond() {
+        let name = "test_async_cluster_circuit_breaker_opens_when_a_node_does_not_respond";
+
+        let MockEnv {
+            runtime,
+            async_connection: mut connection,
+            handler: _handler,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")])
+                .retries(0)
+                .circuit_breaker(2),
+            name,
+            move |cmd: &[u8], port| {
+                respond_startup(name, cmd)?;
+
+                if contains_slice(cmd, b"ECHO") && port == 6379 {
+                    Err(Ok(Value::BulkString(b"hello".to_vec())))
+                } else {
+                    panic!("unexpected command {cmd:?}")
+                }
+            },
+        );
+        modify_mock_connection_behavior(name, |behavior| {
+            behavior
+                .response_delays
+                .insert(6379, Duration::from_secs(60));
+        });
+        let routing = RoutingInfo::SingleNode(SingleNodeRoutingInfo::ByAddress {
+            host: name.to_string(),
+            port: 6379,
+        });
+        let request_timeout = Duration::from_millis(50);
+
+        for _ in 0..2 {
+            let value = runtime.block_on(redis::with_request_timeout(
+                request_timeout,
+                connection.route_command(&cmd("ECHO"), routing.clone()),
+            ));
+            assert!(value.is_none());
+        }
+        // Let the connection's task drop the requests that timed out.
+        runtime.block_on(async { tokio::time::sleep(Duration::from_millis(50)).await });
+
+        // Both requests timed out, so the breaker opened, and the next request fails fast.
+        let err = runtime
+            .block_on(redis::with_request_timeout(
+                request_timeout,
+                connection.route_command(&cmd("ECHO"), routing),
+            ))
+            .expect("The request should fail fast")
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::CircuitBreakerOpen);
+    }
+
     #[test]
     #[serial_test::serial]
     fn test_async_cluster_refresh_slots_rate_limiter_skips_refresh() {
@@ -3180,6 +3286,233 @@ mod cluster_async {
         assert_eq!(value, Ok(Some(123)));
     }
 
+    fn cluster_shards_response(name: &str, shards: &[(u16, u16, u16)]) -> Value {
+        let string = |value: &str| Value::BulkString(value.as_bytes().to_vec());
+        Value::Array(
+            shards
+                .iter()
+                .map(|(start, end, port)| {
+                    Value::Map(vec![
+                        (
+                            string("slots"),
+                            Value::Array(vec![Value::Int(*start as i64), Value::Int(*end as i64)]),
+                        ),
+                        (
+                            string("nodes"),
+                            Value::Array(vec![Value::Map(vec![
+                                (string("port"), Value::Int(*port as i64)),
+                                (string("endpoint"), string(name)),
+                                (string("role"), string("master")),
+                                (string("health"), string("online")),
+                            ])]),
+                        ),
+                    ])
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_async_cluster_discovers_topology_through_cluster_shards() {
+        let name = "discover_through_cluster_shards";
+        let MockEnv {
+            runtime,
+            client,
+            handler: _handler,
+            ..
+        } = MockEnv::new(name, move |cmd: &[u8], port| {
+            // `CLUSTER SLOTS` reports a single node, which serves all the slots.
+            respond_startup(name, cmd)?;
+            if contains_slice(cmd, b"CLUSTER") && contains_slice(cmd, b"SHARDS") {
+                return Err(Ok(cluster_shards_response(
+                    name,
+                    &[(0, 8000, 6379), (8001, 16383, 6380)],
+                )));
+            }
+            match port {
+                6380 => Err(Ok(Value::BulkString(b"123".to_vec()))),
+                _ => panic!("The slot of the key is served by {name}:6380"),
+            }
+        });
+        modify_mock_connection_behavior(name, |behavior| {
+            behavior.supports_cluster_shards = true;
+        });
+        let mut connection = runtime
+            .block_on(client.get_async_generic_connection::<MockConnection>())
+            .unwrap();
+
+        let value = runtime.block_on(
+            cmd("GET")
+                .arg("foo")
+                .query_async::<_, Option<i32>>(&mut connection),
+        );
+
+        assert_eq!(value, Ok(Some(123)));
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_async_cluster_stops_sending_cluster_shards_once_rejected() {
+        let name = "stop_sending_cluster_shards";
+        let shards_calls = Arc::new(AtomicU32::new(0));
+        let slots_calls = Arc::new(AtomicU32::new(0));
+        let MockEnv {
+            runtime,
+            client,
+            handler: _handler,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")])
+                .periodic_topology_checks(Duration::from_millis(10)),
+            name,
+            {
+                let shards_calls = shards_calls.clone();
+                let slots_calls = slots_calls.clone();
+                move |cmd: &[u8], _port| {
+                    if contains_slice(cmd, b"CLUSTER") && contains_slice(cmd, b"SHARDS") {
+                        shards_calls.fetch_add(1, Ordering::SeqCst);
+                        return Err(Err(RedisError::from((
+                            ErrorKind::ResponseError,
+                            "An error was signalled by the server",
+                            "unknown subcommand 'SHARDS'".to_string(),
+                        ))));
+                    }
+                    if contains_slice(cmd, b"CLUSTER") && contains_slice(cmd, b"SLOTS") {
+                        slots_calls.fetch_add(1, Ordering::SeqCst);
+                    }
+                    respond_startup(name, cmd)?;
+                    Err(Ok(Value::Okay))
+                }
+            },
+        );
+        // The handler sees `CLUSTER SHARDS` only from the connections that are created from now on.
+        modify_mock_connection_behavior(name, |behavior| {
+            behavior.supports_cluster_shards = true;
+        });
+        let slots_calls_before_connecting = slots_calls.load(Ordering::SeqCst);
+        let _connection = runtime
+            .block_on(client.get_async_generic_connection::<MockConnection>())
+            .unwrap();
+        runtime.block_on(async { tokio::time::sleep(Duration::from_millis(200)).await });
+
+        assert_eq!(shards_calls.load(Ordering::SeqCst), 1);
+        assert!(slots_calls.load(Ordering::SeqCst) > slots_calls_before_connecting + 1);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_async_cluster_routes_commands_by_the_discovered_command_routes() {
+        let name = "discovered_command_routes";
+        let queried_ports = Arc::new(std::sync::Mutex::new(HashSet::new()));
+        let table = Arc::new(CommandRoutingTable::new(true));
+        let MockEnv {
+            runtime,
+            async_connection: mut connection,
+            handler: _handler,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")])
+                .command_routing_table(table.clone()),
+            name,
+            {
+                let queried_ports = queried_ports.clone();
+                move |cmd: &[u8], port| {
+                    respond_startup_two_nodes(name, cmd)?;
+                    if contains_slice(cmd, b"MY.SCAN") {
+                        queried_ports.lock().unwrap().insert(port);
+                        return Err(Ok(Value::Array(vec![])));
+                    }
+                    if contains_slice(cmd, b"COMMAND") {
+                        let string = |value: &str| Value::BulkString(value.as_bytes().to_vec());
+                        return Err(Ok(Value::Array(vec![Value::Array(vec![
+                            string("my.scan"),
+                            Value::Int(-1),
+                            Value::Array(vec![string("readonly")]),
+                            Value::Int(0),
+                            Value::Int(0),
+                            Value::Int(0),
+                            Value::Array(vec![]),
+                            Value::Array(vec![string("request_policy:all_shards")]),
+                            Value::Array(vec![]),
+                            Value::Array(vec![]),
+                        ])])));
+                    }
+                    Err(Ok(Value::Okay))
+                }
+            },
+        );
+        // The routes are discovered in the background, after the slots were refreshed.
+        runtime.block_on(async {
+            tokio::time::timeout(Duration::from_secs(1), async {
+                while table.route(b"MY.SCAN").is_none() {
+                    tokio::time::sleep(Duration::from_millis(10)).await;
+                }
+            })
+            .await
+            .expect("the command routes weren't discovered")
+        });
+
+        // Single commands are routed by the caller, as the glide client does.
+        let scan = cmd("MY.SCAN");
+        let routing = RoutingInfo::for_routable_with_table(&scan, &table).unwrap();
+        runtime
+            .block_on(connection.route_command(&scan, routing))
+            .unwrap();
+
+        assert_eq!(*queried_ports.lock().unwrap(), HashSet::from([6379, 6380]));
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_async_cluster_ask_redirect_to_remapped_address() {
+        let name = "node";
+        let completed = Arc::new(AtomicI32::new(0));
+        let MockEnv {
+            async_connection: mut connection,
+            handler: _handler,
+            runtime,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")])
+                .address_remap(AddressRemap::new().host_rule("10.0.*", name)),
+            name,
+            {
+                move |cmd: &[u8], port| {
+                    respond_startup_two_nodes(name, cmd)?;
+                    let count = completed.fetch_add(1, Ordering::SeqCst);
+                    match port {
+                        // The node announces the internal address of the other node.
+                        6379 => match count {
+                            0 => Err(parse_redis_value(b"-ASK 14000 10.0.0.2:6380\r\n")),
+                            _ => panic!("Node should not be called now"),
+                        },
+                        6380 => match count {
+                            1 => {
+                                assert!(contains_slice(cmd, b"ASKING"));
+                                Err(Ok(Value::Okay))
+                            }
+                            2 => {
+                                assert!(contains_slice(cmd, b"GET"));
+                                Err(Ok(Value::BulkString(b"123".to_vec())))
+                            }
+                            _ => panic!("Node should not be called now"),
+                        },
+                        _ => panic!("Wrong node"),
+                    }
+                }
+            },
+        );
+
+        let value = runtime.block_on(
+            cmd("GET")
+                .arg("test")
+                .query_async::<_, Option<i32>>(&mut connection),
+        );
+
+        assert_eq!(value, Ok(Some(123)));
+    }
+
     #[test]
     #[serial_test::serial]
     fn test_async_cluster_ask_save_new_connection() {
@@ -3430,6 +3763,113 @@ mod cluster_async {
         assert_eq!(value, Ok(Some(Value::SimpleString("OK".to_owned()))));
     }
 
+    #[test]
+    #[serial_test::serial]
+    fn test_async_cluster_hedges_slow_replica_read() {
+        let name = "test_async_cluster_hedges_slow_replica_read";
+        let replica_reads = Arc::new(atomic::AtomicU16::new(0));
+        let replica_reads_clone = replica_reads.clone();
+        let response_delay = Duration::from_millis(500);
+
+        let MockEnv {
+            runtime,
+            async_connection: mut connection,
+            handler: _handler,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")])
+                .retries(0)
+                .read_from_replicas()
+                .circuit_breaker(1)
+                .read_hedging(HedgePolicy::Delay(Duration::from_millis(10))),
+            name,
+            move |cmd: &[u8], port| {
+                respond_startup_with_replica(name, cmd)?;
+                match port {
+                    6379 => Err(Ok(Value::BulkString(b"primary".to_vec()))),
+                    6380 => {
+                        replica_reads.fetch_add(1, Ordering::Relaxed);
+                        Err(Ok(Value::BulkString(b"replica".to_vec())))
+                    }
+                    _ => panic!("Wrong node"),
+                }
+            },
+        );
+        modify_mock_connection_behavior(name, |behavior| {
+            behavior.response_delays.insert(6380, response_delay);
+        });
+
+        // The replica doesn't answer within the hedging delay, so the read is also sent to the primary of the slot.
+        let value = runtime.block_on(
+            cmd("GET")
+                .arg("test")
+                .query_async::<_, Option<String>>(&mut connection),
+        );
+        assert_eq!(value, Ok(Some("primary".to_string())));
+
+        // The request to the replica was dropped once the primary answered, so it never reached the replica.
+        runtime.block_on(async { tokio::time::sleep(response_delay).await });
+        assert_eq!(replica_reads_clone.load(Ordering::Relaxed), 0);
+
+        // The abandoned request isn't counted as a failure of the replica, so its circuit breaker stays closed.
+        modify_mock_connection_behavior(name, |behavior| behavior.response_delays.clear());
+        let value = runtime.block_on(
+            cmd("GET")
+                .arg("test")
+                .query_async::<_, Option<String>>(&mut connection),
+        );
+        assert_eq!(value, Ok(Some("replica".to_string())));
+        assert_eq!(replica_reads_clone.load(Ordering::Relaxed), 1);
+    }
+
+    #[test]
+    #[serial_test::serial]
+    fn test_async_cluster_hedged_read_waits_for_the_hedge_when_the_first_response_fails() {
+        let name =
+            "test_async_cluster_hedged_read_waits_for_the_hedge_when_the_first_response_fails";
+
+        let MockEnv {
+            runtime,
+            async_connection: mut connection,
+            handler: _handler,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")])
+                .retries(0)
+                .read_from_replicas()
+                .read_hedging(HedgePolicy::Delay(Duration::from_millis(10))),
+            name,
+            move |cmd: &[u8], port| {
+                respond_startup_with_replica(name, cmd)?;
+                match port {
+                    6379 => Err(Ok(Value::BulkString(b"primary".to_vec()))),
+                    6380 => Err(Err(RedisError::from((
+                        ErrorKind::ResponseError,
+                        "An error was signalled by the server",
+                        "replica failure".to_string(),
+                    )))),
+                    _ => panic!("Wrong node"),
+                }
+            },
+        );
+        // The replica fails after the read was hedged to the primary, but before the primary answers.
+        modify_mock_connection_behavior(name, |behavior| {
+            behavior
+                .response_delays
+                .insert(6380, Duration::from_millis(50));
+            behavior
+                .response_delays
+                .insert(6379, Duration::from_millis(200));
+        });
+
+        let value = runtime.block_on(
+            cmd("GET")
+                .arg("test")
+                .query_async::<_, Option<String>>(&mut connection),
+        );
+        assert_eq!(value, Ok(Some("primary".to_string())));
+    }
+
     fn test_async_cluster_fan_out(
         command: &'static str,
         expected_ports: Vec<u16>,
@@ -4327,6 +4767,59 @@ mod cluster_async {
             .unwrap();
     }
 
+    #[test]
+    #[serial_test::serial]
+    fn test_async_cluster_retry_idempotent_commands_on_connection_errors() {
+        let name = "test_async_cluster_retry_idempotent_commands_on_connection_errors";
+        let get_requests = atomic::AtomicUsize::new(0);
+        let incr_requests = Arc::new(atomic::AtomicUsize::new(0));
+        let incr_requests_clone = incr_requests.clone();
+        let MockEnv {
+            runtime,
+            async_connection: mut connection,
+            ..
+        } = MockEnv::with_client_builder(
+            ClusterClient::builder(vec![&*format!("redis://{name}")])
+                .retries(3)
+                .connection_error_retry(ConnectionErrorRetryPolicy::new(1)),
+            name,
+            move |cmd: &[u8], _port| {
+                respond_startup_two_nodes(name, cmd)?;
+                if contains_slice(cmd, b"GET") {
+                    match get_requests.fetch_add(1, atomic::Ordering::SeqCst) {
+                        0 => Err(Err(broken_pipe_error())),
+                        _ => Err(Ok(Value::BulkString(b"bar".to_vec()))),
+                    }
+                } else if contains_slice(cmd, b"INCR") {
+                    incr_requests.fetch_add(1, atomic::Ordering::SeqCst);
+                    Err(Err(broken_pipe_error()))
+                } else {
+                    panic!("unexpected command {cmd:?}")
+                }
+            },
+        );
+        runtime
+            .block_on(async move {
+                // A read is retried once the connection is restored.
+                let value = cmd("GET")
+                    .arg("foo")
+                    .query_async::<_, String>(&mut connection)
+                    .await?;
+                assert_eq!(value, "bar");
+
+                // INCR might have been executed before the connection dropped, so it isn't retried.
+                let err = cmd("INCR")
+                    .arg("foo")
+                    .query_async::<_, i32>(&mut connection)
+                    .await
+                    .unwrap_err();
+                assert!(err.is_connection_dropped());
+                Ok::<_, RedisError>(())
+            })
+            .unwrap();
+        assert_eq!(incr_requests_clone.load(atomic::Ordering::SeqCst), 1);
+    }
+
     #[test]
     #[serial_test::serial]
     fn test_async_cluster_read_from_primary() {
@@ -0,0 +1,609 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use super::ClientSideCacheConfig;
+use logger_core::log_debug;
+use redis::cluster_routing::{Routable, is_readonly_cmd, key_indices};
+use redis::{Cmd, PushInfo, PushKind, Value};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicI64, Ordering};
+use std::time::Instant;
+
+/// The fixed memory overhead accounted for each cached value, in addition to its content.
+const VALUE_OVERHEAD: usize = std::mem::size_of::<Value>();
+
+/// Hit and miss counters of the client-side cache.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ClientSideCacheStatistics {
+    /// Number of read commands served from the cache.
+    pub hits: u64,
+    /// Number of cacheable read commands that were sent to the server.
+    pub misses: u64,
+    /// Number of entries removed because of the entries or memory bounds, or because their TTL passed.
+    pub evictions: u64,
+    /// Number of entries removed because the server invalidated one of their keys, or the client wrote to it.
+    pub invalidations: u64,
+    /// Number of entries currently in the cache.
+    pub entries: u64,
+    /// Estimated memory used by the entries currently in the cache, in bytes.
+    pub memory: u64,
+}
+
+/// The result of looking up a command in the cache.
+pub(super) enum CacheLookup {
+    Hit(Value),
+    /// The command isn't in the cache. Its response can be inserted with the given ticket.
+    Miss(InsertTicket),
+    /// The command can't be cached.
+    NotCacheable,
+}
+
+/// Allows inserting a response into the cache, as long as none of the command's keys was invalidated since the
+/// command was looked up. Otherwise, the response might have been read before a modification that the invalidation
+/// reported.
+pub(super) struct InsertTicket {
+    epoch: u64,
+    database: i64,
+    cache_key: Vec<u8>,
+    keys: Vec<Vec<u8>>,
+}
+
+struct CacheEntry {
+    value: Value,
+    keys: Vec<Vec<u8>>,
+    inserted_at: Instant,
+    last_access: u64,
+    size: usize,
+}
+
+#[derive(Default)]
+struct CacheState {
+    /// The cached responses, by the database and the packed command that produced them.
+    entries: HashMap<Vec<u8>, CacheEntry>,
+    /// The cache keys of the commands that read each key, used to find the entries to remove on invalidation.
+    commands_by_key: HashMap<Vec<u8>, HashSet<Vec<u8>>>,
+    /// The cache keys by their last access, least recently used first.
+    access_order: BTreeMap<u64, Vec<u8>>,
+    access_counter: u64,
+    /// Incremented on every invalidation.
+    epoch: u64,
+    /// The epoch at which each key was last invalidated, so responses that raced an invalidation of one of their
+    /// keys are not inserted.
+    invalidated_at: HashMap<Vec<u8>, u64>,
+    /// Tickets issued before this epoch are rejected, since the invalidations they might have raced were forgotten.
+    oldest_valid_epoch: u64,
+    memory: usize,
+    stats: ClientSideCacheStatistics,
+}
+
+/// An in-process cache of read command responses, kept consistent using the server's `CLIENT TRACKING` invalidations.
+pub(super) struct ClientSideCache {
+    config: ClientSideCacheConfig,
+    /// The database selected on the client's connections. Responses are only served to commands on the same database.
+    database: AtomicI64,
+    state: Mutex<CacheState>,
+}
+
+impl ClientSideCache {
+    pub(super) fn new(config: ClientSideCacheConfig, database: i64) -> Self {
+        Self {
+            config,
+            database: AtomicI64::new(database),
+            state: Mutex::new(CacheState::default()),
+        }
+    }
+
+    /// Records the database selected by `cmd`, if it's a `SELECT` command that succeeded.
+    pub(super) fn handle_select(&self, cmd: &Cmd) {
+        if cmd.command().as_deref() != Some(b"SELECT") {
+            return;
+        }
+        let database = match cmd.args_iter().nth(1) {
+            Some(redis::Arg::Simple(database)) => std::str::from_utf8(database)
+                .ok()
+                .and_then(|database| database.parse().ok()),
+            _ => None,
+        };
+        if let Some(database) = database {
+            self.database.store(database, Ordering::Relaxed);
+        }
+    }
+
+    pub(super) fn get(&self, cmd: &Cmd) -> CacheLookup {
+        let Some(keys) = cacheable_keys(cmd) else {
+            return CacheLookup::NotCacheable;
+        };
+        let database = self.database.load(Ordering::Relaxed);
+        let packed_command = cache_key(database, cmd);
+        let mut state = self.state.lock().unwrap();
+
+        let expired = match state.entries.get(&packed_command) {
+            Some(entry) => self
+                .config
+                .ttl
+                .is_some_and(|ttl| entry.inserted_at.elapsed() >= ttl),
+            None => {
+                state.stats.misses += 1;
+                return CacheLookup::Miss(InsertTicket {
+                    epoch: state.epoch,
+                    database,
+                    cache_key: packed_command,
+                    keys,
+                });
+            }
+        };
+        if expired {
+            state.remove(&packed_command);
+            state.stats.evictions += 1;
+            state.stats.misses += 1;
+            return CacheLookup::Miss(InsertTicket {
+                epoch: state.epoch,
+                database,
+                cache_key: packed_command,
+                keys,
+            });
+        }
+
+        state.stats.hits += 1;
+        state.access_counter += 1;
+        let access = state.access_counter;
+        let entry = state.entries.get_mut(&packed_command).unwrap();
+        let previous_access = std::mem::replace(&mut entry.last_access, access);
+        let value = entry.value.clone();
+        state.access_order.remove(&previous_access);
+        state.access_order.insert(access, packed_command);
+        CacheLookup::Hit(value)
+    }
+
+    pub(super) fn insert(&self, ticket: InsertTicket, value: &Value) {
+        // The response might have been read from another database, if one was selected while the command was sent.
+        if self.database.load(Ordering::Relaxed) != ticket.database {
+            return;
+        }
+        if self.config.max_entries == 0 {
+            return;
+        }
+        let packed_command = ticket.cache_key;
+        let size = packed_command.len() + value_size(value);
+        if self
+            .config
+            .max_memory
+            .is_some_and(|max_memory| size > max_memory)
+        {
+            return;
+        }
+
+        let mut state = self.state.lock().unwrap();
+        if ticket.epoch < state.oldest_valid_epoch
+            || ticket.keys.iter().any(|key| {
+                state
+                    .invalidated_at
+                    .get(key)
+                    .is_some_and(|invalidated_at| *invalidated_at > ticket.epoch)
+            })
+        {
+            return;
+        }
+        state.remove(&packed_command);
+        while state.entries.len() >= self.config.max_entries
+            || self
+                .config
+                .max_memory
+                .is_some_and(|max_memory| state.memory + size > max_memory)
+        {
+            if !state.evict_least_recently_used() {
+                break;
+            }
+        }
+
+        state.access_counter += 1;
+        let access = state.access_counter;
+        for key in ticket.keys.iter() {
+            state
+                .commands_by_key
+                .entry(key.clone())
+                .or_default()
+                .insert(packed_command.clone());
+        }
+        state.access_order.insert(access, packed_command.clone());
+        state.memory += size;
+        state.entries.insert(
+            packed_command,
+            CacheEntry {
+                value: value.clone(),
+                keys: ticket.keys,
+                inserted_at: Instant::now(),
+                last_access: access,
+                size,
+            },
+        );
+    }
+
+    /// Removes the entries that read the keys written by `cmd`, so the client reads its own writes without waiting for
+    /// the server's invalidation. `FLUSHDB` and `FLUSHALL` flush the whole cache.
+    pub(super) fn handle_write(&self, cmd: &Cmd) {
+        let Some(command) = cmd.command() else {
+            return;
+        };
+        if is_readonly_cmd(&command) {
+            return;
+        }
+        if matches!(command.as_slice(), b"FLUSHDB" | b"FLUSHALL") {
+            self.flush();
+            return;
+        }
+        let keys: Vec<_> = key_indices(cmd)
+            .into_iter()
+            .filter_map(|index| cmd.arg_idx(index))
+            .collect();
+        if !keys.is_empty() {
+            self.invalidate(&keys);
+        }
+    }
+
+    /// Removes the entries invalidated by a push notification.
+    /// Invalidation pushes remove the entries that read the invalidated keys, and disconnections flush the whole cache,
+    /// since invalidations might have been missed while the connection was down.
+    /// Returns whether the push was consumed by the cache.
+    pub(super) fn handle_push(&self, push: &PushInfo) -> bool {
+        match push.kind {
+            PushKind::Invalidate => {
+                match push.data.first() {
+                    Some(Value::Array(keys)) => {
+                        let keys: Vec<_> = keys
+                            .iter()
+                            .filter_map(|key| match key {
+                                Value::BulkString(key) => Some(key.as_slice()),
+                                _ => None,
+                            })
+                            .collect();
+                        self.invalidate(&keys);
+                    }
+                    // A nil payload is sent when the server flushes all of its keys.
+                    _ => self.flush(),
+                }
+                true
+            }
+            PushKind::Disconnection => {
+                self.flush();
+                false
+            }
+            _ => false,
+        }
+    }
+
+    fn invalidate(&self, keys: &[&[u8]]) {
+        let mut state = self.state.lock().unwrap();
+        state.epoch += 1;
+        // The invalidation epochs are forgotten once they outnumber the cache entries, rejecting all the tickets
+        // issued until now instead.
+        if state.invalidated_at.len() + keys.len() > self.config.max_entries {
+            state.invalidated_at.clear();
+            state.oldest_valid_epoch = state.epoch;
+        }
+        let epoch = state.epoch;
+        for key in keys {
+            state.invalidated_at.insert(key.to_vec(), epoch);
+            let Some(commands) = state.commands_by_key.remove(*key) else {
+                continue;
+            };
+            for command in commands {
+                if state.remove(&command) {
+                    state.stats.invalidations += 1;
+                }
+            }
+        }
+    }
+
+    fn flush(&self) {
+        let mut state = self.state.lock().unwrap();
+        let invalidated = state.entries.len() as u64;
+        *state = CacheState {
+            epoch: state.epoch + 1,
+            oldest_valid_epoch: state.epoch + 1,
+            access_counter: state.access_counter,
+            stats: ClientSideCacheStatistics {
+                invalidations: state.stats.invalidations + invalidated,
+                ..state.stats
+            },
+            ..Default::default()
+        };
+        log_debug(
+            "client side cache",
+            format!("flushed {invalidated} entries"),
+        );
+    }
+
+    pub(super) fn statistics(&self) -> ClientSideCacheStatistics {
+        let state = self.state.lock().unwrap();
+        ClientSideCacheStatistics {
+            entries: state.entries.len() as u64,
+            memory: state.memory as u64,
+            ..state.stats
+        }
+    }
+}
+
+impl CacheState {
+    /// Removes an entry, and returns whether it existed.
+    fn remove(&mut self, packed_command: &[u8]) -> bool {
+        let Some(entry) = self.entries.remove(packed_command) else {
+            return false;
+        };
+        self.memory -= entry.size;
+        self.access_order.remove(&entry.last_access);
+        for key in entry.keys.iter() {
+            if let Some(commands) = self.commands_by_key.get_mut(key) {
+                commands.remove(packed_command);
+                if commands.is_empty() {
+                    self.commands_by_key.remove(key);
+                }
+            }
+        }
+        true
+    }
+
+    /// Removes the least recently used entry, and returns whether there was one.
+    fn evict_least_recently_used(&mut self) -> bool {
+        let Some((_, packed_command)) = self.access_order.pop_first() else {
+            return false;
+        };
+        self.remove(&packed_command);
+        self.stats.evictions += 1;
+        true
+    }
+}
+
+/// Returns the key that the response to `cmd` is cached under, when `database` is selected.
+fn cache_key(database: i64, cmd: &Cmd) -> Vec<u8> {
+    let mut cache_key = database.to_be_bytes().to_vec();
+    cache_key.extend(cmd.get_packed_command());
+    cache_key
+}
+
+/// Returns the keys read by `cmd`, if its response can be cached.
+fn cacheable_keys(cmd: &Cmd) -> Option<Vec<Vec<u8>>> {
+    let command = cmd.command()?;
+    let args = cmd.args_iter().skip(1).map(|arg| match arg {
+        redis::Arg::Simple(arg) => Some(arg.to_vec()),
+        redis::Arg::Cursor => None,
+    });
+    match command.as_slice() {
+        b"GET" | b"STRLEN" | b"HGET" | b"HMGET" | b"HGETALL" | b"HLEN" | b"SMEMBERS" | b"SCARD" => {
+            args.take(1)
+                .collect::<Option<Vec<_>>>()
+                .filter(|keys| !keys.is_empty())
+        }
+        b"MGET" => args
+            .collect::<Option<Vec<_>>>()
+            .filter(|keys| !keys.is_empty()),
+        _ => None,
+    }
+}
+
+/// Estimates the memory used by a value.
+fn value_size(value: &Value) -> usize {
+    VALUE_OVERHEAD
+        + match value {
+            Value::BulkString(bytes) => bytes.len(),
+            Value::SimpleString(string) | Value::VerbatimString { text: string, .. } => {
+                string.len()
+            }
+            Value::Array(values) | Value::Set(values) => values.iter().map(value_size).sum(),
+            Value::Map(pairs) => pairs
+                .iter()
+                .map(|(key, value)| value_size(key) + value_size(value))
+                .sum(),
+            _ => 0,
+        }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    fn config(max_entries: usize) -> ClientSideCacheConfig {
+        ClientSideCacheConfig {
+            max_entries,
+            max_memory: None,
+            ttl: None,
+            tracking_mode: redis::ClientTrackingMode::Default,
+        }
+    }
+
+    fn get(key: &str) -> Cmd {
+        let mut cmd = redis::cmd("GET");
+        cmd.arg(key);
+        cmd
+    }
+
+    fn insert(cache: &ClientSideCache, cmd: &Cmd, value: &str) {
+        let CacheLookup::Miss(ticket) = cache.get(cmd) else {
+            panic!("expected a miss");
+        };
+        cache.insert(ticket, &Value::BulkString(value.as_bytes().to_vec()));
+    }
+
+    fn invalidate_push(keys: &[&str]) -> PushInfo {
+        PushInfo {
+            kind: PushKind::Invalidate,
+            data: vec![Value::Array(
+                keys.iter()
+                    .map(|key| Value::BulkString(key.as_bytes().to_vec()))
+                    .collect(),
+            )],
+        }
+    }
+
+    #[test]
+    fn test_cache_hit_after_insert_and_miss_after_invalidation() {
+        let cache = ClientSideCache::new(config(10), 0);
+        insert(&cache, &get("foo"), "bar");
+        assert!(matches!(
+            cache.get(&get("foo")),
+            CacheLookup::Hit(Value::BulkString(value)) if value == b"bar"
+        ));
+
+        assert!(cache.handle_push(&invalidate_push(&["foo"])));
+        assert!(matches!(cache.get(&get("foo")), CacheLookup::Miss(_)));
+
+        let statistics = cache.statistics();
+        assert_eq!(statistics.hits, 1);
+        assert_eq!(statistics.misses, 2);
+        assert_eq!(statistics.invalidations, 1);
+        assert_eq!(statistics.entries, 0);
+        assert_eq!(statistics.memory, 0);
+    }
+
+    #[test]
+    fn test_cache_invalidates_multi_key_entries_and_ignores_racing_responses() {
+        let cache = ClientSideCache::new(config(10), 0);
+        let mut mget = redis::cmd("MGET");
+        mget.arg("foo").arg("baz");
+        insert(&cache, &mget, "values");
+
+        let CacheLookup::Miss(ticket) = cache.get(&get("baz")) else {
+            panic!("expected a miss");
+        };
+        cache.handle_push(&invalidate_push(&["baz"]));
+        cache.insert(ticket, &Value::Nil);
+
+        assert!(matches!(cache.get(&mget), CacheLookup::Miss(_)));
+        assert!(matches!(cache.get(&get("baz")), CacheLookup::Miss(_)));
+    }
+
+    #[test]
+    fn test_cache_inserts_responses_that_raced_invalidations_of_other_keys() {
+        let cache = ClientSideCache::new(config(10), 0);
+        let CacheLookup::Miss(ticket) = cache.get(&get("foo")) else {
+            panic!("expected a miss");
+        };
+        cache.handle_push(&invalidate_push(&["baz"]));
+        cache.insert(ticket, &Value::Nil);
+        assert!(matches!(
+            cache.get(&get("foo")),
+            CacheLookup::Hit(Value::Nil)
+        ));
+
+        // Once the invalidations outnumber the entries, they are forgotten and the older tickets are rejected.
+        let CacheLookup::Miss(ticket) = cache.get(&get("bar")) else {
+            panic!("expected a miss");
+        };
+        let keys: Vec<_> = (0..10).map(|index| format!("key-{index}")).collect();
+        cache.handle_push(&invalidate_push(
+            &keys.iter().map(String::as_str).collect::<Vec<_>>(),
+        ));
+        cache.insert(ticket, &Value::Nil);
+        assert!(matches!(cache.get(&get("bar")), CacheLookup::Miss(_)));
+    }
+
+    #[test]
+    fn test_cache_keeps_the_responses_of_each_database_apart() {
+        let cache = ClientSideCache::new(config(10), 0);
+        insert(&cache, &get("foo"), "db-0");
+
+        let mut select = redis::cmd("SELECT");
+        select.arg(1);
+        cache.handle_select(&select);
+        assert!(matches!(cache.get(&get("foo")), CacheLookup::Miss(_)));
+        insert(&cache, &get("foo"), "db-1");
+
+        // A response to a command sent before another database was selected isn't inserted.
+        let CacheLookup::Miss(ticket) = cache.get(&get("bar")) else {
+            panic!("expected a miss");
+        };
+        let mut select = redis::cmd("SELECT");
+        select.arg(0);
+        cache.handle_select(&select);
+        cache.insert(ticket, &Value::Nil);
+        assert!(matches!(
+            cache.get(&get("foo")),
+            CacheLookup::Hit(Value::BulkString(value)) if value == b"db-0"
+        ));
+        assert!(matches!(cache.get(&get("bar")), CacheLookup::Miss(_)));
+
+        // The server's invalidations don't name the database, so they remove the key's entries in all of them.
+        cache.handle_push(&invalidate_push(&["foo"]));
+        assert_eq!(cache.statistics().invalidations, 2);
+    }
+
+    #[test]
+    fn test_cache_evicts_least_recently_used_entry() {
+        let cache = ClientSideCache::new(config(2), 0);
+        insert(&cache, &get("a"), "1");
+        insert(&cache, &get("b"), "2");
+        assert!(matches!(cache.get(&get("a")), CacheLookup::Hit(_)));
+        insert(&cache, &get("c"), "3");
+
+        assert!(matches!(cache.get(&get("a")), CacheLookup::Hit(_)));
+        assert!(matches!(cache.get(&get("b")), CacheLookup::Miss(_)));
+        assert_eq!(cache.statistics().evictions, 1);
+    }
+
+    #[test]
+    fn test_cache_expires_entries_and_flushes_on_disconnection() {
+        let cache = ClientSideCache::new(
+            ClientSideCacheConfig {
+                ttl: Some(Duration::ZERO),
+                ..config(10)
+            },
+            0,
+        );
+        insert(&cache, &get("foo"), "bar");
+        assert!(matches!(cache.get(&get("foo")), CacheLookup::Miss(_)));
+
+        let cache = ClientSideCache::new(config(10), 0);
+        insert(&cache, &get("foo"), "bar");
+        assert!(!cache.handle_push(&PushInfo {
+            kind: PushKind::Disconnection,
+            data: vec![],
+        }));
+        assert!(matches!(cache.get(&get("foo")), CacheLookup::Miss(_)));
+    }
+
+    #[test]
+    fn test_write_commands_are_not_cacheable() {
+        let cache = ClientSideCache::new(config(10), 0);
+        let mut set = redis::cmd("SET");
+        set.arg("foo").arg("bar");
+        assert!(matches!(cache.get(&set), CacheLookup::NotCacheable));
+    }
+
+    #[test]
+    fn test_cache_removes_the_entries_of_written_keys() {
+        let cache = ClientSideCache::new(config(10), 0);
+        insert(&cache, &get("foo"), "bar");
+        insert(&cache, &get("baz"), "qux");
+
+        // A read doesn't invalidate its keys.
+        cache.handle_write(&get("foo"));
+        assert!(matches!(cache.get(&get("foo")), CacheLookup::Hit(_)));
+
+        // A response to a read that raced the write isn't inserted.
+        let CacheLookup::Miss(ticket) = cache.get(&get("new")) else {
+            panic!("expected a miss");
+        };
+        let mut set = redis::cmd("SET");
+        set.arg("foo").arg("new-value");
+        cache.handle_write(&set);
+        let mut del = redis::cmd("DEL");
+        del.arg("new");
+        cache.handle_write(&del);
+        cache.insert(ticket, &Value::Nil);
+        assert!(matches!(cache.get(&get("foo")), CacheLookup::Miss(_)));
+        assert!(matches!(cache.get(&get("new")), CacheLookup::Miss(_)));
+        assert!(matches!(cache.get(&get("baz")), CacheLookup::Hit(_)));
+
+        cache.handle_write(&redis::cmd("FLUSHALL"));
+        assert!(matches!(cache.get(&get("baz")), CacheLookup::Miss(_)));
+    }
+
+    #[test]
+    fn test_cache_without_entries_does_not_insert() {
+        let cache = ClientSideCache::new(config(0), 0);
+        insert(&cache, &get("foo"), "bar");
+        assert!(matches!(cache.get(&get("foo")), CacheLookup::Miss(_)));
+        assert_eq!(cache.statistics().entries, 0);
+    }
+}
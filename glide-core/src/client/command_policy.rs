@@ -0,0 +1,314 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use super::{Middleware, MiddlewareRequest};
+use crate::request_type::RequestType;
+use redis::cluster_routing::{Routable, is_readonly_cmd};
+use redis::{Cmd, ErrorKind, RedisError, RedisResult, Value};
+use std::collections::HashSet;
+
+/// Restricts the commands that a client may send. Commands that the policy rejects fail with
+/// [`ErrorKind::CommandNotAllowed`], and aren't sent.
+///
+/// A policy either allows only the commands that were added to it, or all the commands but those.
+/// A command with a subcommand, e.g. `CONFIG SET`, can be added by its full name, or by its first word to cover all of its subcommands.
+/// A read-only policy also rejects the commands that may write keys, and the administrative commands that change the
+/// server's configuration or state, such as `CONFIG SET`, `SHUTDOWN` or `MODULE LOAD`. It sends scripts and functions
+/// through their read-only variants, e.g. `EVALSHA_RO`, so the server rejects the writes that they attempt.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct CommandPolicy {
+    allowlist: bool,
+    commands: HashSet<Vec<u8>>,
+    read_only: bool,
+}
+
+impl CommandPolicy {
+    /// Creates a policy that allows only the commands that are added to it.
+    pub fn allowlist() -> Self {
+        Self {
+            allowlist: true,
+            ..Default::default()
+        }
+    }
+
+    /// Creates a policy that allows all the commands but those that are added to it.
+    pub fn denylist() -> Self {
+        Self::default()
+    }
+
+    /// Adds `command` to the policy, e.g. `FLUSHALL`, or `CONFIG SET` for a command with a subcommand.
+    pub fn command(mut self, command: &str) -> Self {
+        self.commands
+            .insert(command.trim().to_ascii_uppercase().into_bytes());
+        self
+    }
+
+    /// Adds the command of `request_type` to the policy. Request types without a command, such as custom commands, are ignored.
+    pub fn request_type(mut self, request_type: RequestType) -> Self {
+        if let Some(command) = request_type.get_command().and_then(|cmd| cmd.command()) {
+            self.commands.insert(command);
+        }
+        self
+    }
+
+    /// Sets whether the commands that may write keys, and the administrative commands that change the server, are rejected.
+    pub fn read_only(mut self, read_only: bool) -> Self {
+        self.read_only = read_only;
+        self
+    }
+
+    /// Returns true if the commands that may write keys, and the administrative commands that change the server, are rejected.
+    pub fn is_read_only(&self) -> bool {
+        self.read_only
+    }
+
+    fn is_listed(&self, command: &[u8]) -> bool {
+        self.commands.contains(command)
+            || command
+                .split(|byte| *byte == b' ')
+                .next()
+                .is_some_and(|first_word| self.commands.contains(first_word))
+    }
+
+    /// Fails with [`ErrorKind::CommandNotAllowed`] if the policy rejects `cmd`.
+    pub fn check(&self, cmd: &Cmd) -> RedisResult<()> {
+        let Some(command) = cmd.command() else {
+            return Ok(());
+        };
+        let name = String::from_utf8_lossy(&command).into_owned();
+        if self.is_listed(&command) != self.allowlist {
+            return Err(RedisError::from((
+                ErrorKind::CommandNotAllowed,
+                "The command isn't allowed by the command policy of the client",
+                name,
+            )));
+        }
+        if !self.read_only {
+            return Ok(());
+        }
+        if read_only_variant(&command).is_none() && !is_readonly_cmd(&command) {
+            return Err(RedisError::from((
+                ErrorKind::CommandNotAllowed,
+                "The command may write, and the client is read-only",
+                name,
+            )));
+        }
+        if is_administrative(&command) {
+            return Err(RedisError::from((
+                ErrorKind::CommandNotAllowed,
+                "The command changes the server, and the client is read-only",
+                name,
+            )));
+        }
+        Ok(())
+    }
+
+    // Replaces the scripts and functions of a read-only client with their read-only variants.
+    fn rewrite(&self, cmd: &mut Cmd) {
+        if !self.read_only {
+            return;
+        }
+        let Some(read_only_name) = cmd
+            .command()
+            .and_then(|command| read_only_variant(&command))
+        else {
+            return;
+        };
+        let mut read_only_cmd = redis::cmd(read_only_name);
+        for arg in (1..).map_while(|idx| Routable::arg_idx(&*cmd, idx)) {
+            read_only_cmd.arg(arg);
+        }
+        read_only_cmd.set_span(cmd.span());
+        read_only_cmd.set_no_response(cmd.is_no_response());
+        *cmd = read_only_cmd;
+    }
+}
+
+fn read_only_variant(command: &[u8]) -> Option<&'static str> {
+    match command {
+        b"EVAL" => Some("EVAL_RO"),
+        b"EVALSHA" => Some("EVALSHA_RO"),
+        b"FCALL" => Some("FCALL_RO"),
+        _ => None,
+    }
+}
+
+/// Returns true for the commands that don't write keys, but change the server's configuration or state.
+fn is_administrative(command: &[u8]) -> bool {
+    matches!(
+        command,
+        b"ACL DELUSER"
+            | b"ACL LOAD"
+            | b"ACL SAVE"
+            | b"ACL SETUSER"
+            | b"BGREWRITEAOF"
+            | b"BGSAVE"
+            | b"CLIENT KILL"
+            | b"CLIENT NO-EVICT"
+            | b"CLIENT PAUSE"
+            | b"CLIENT UNBLOCK"
+            | b"CLIENT UNPAUSE"
+            | b"CLUSTER ADDSLOTS"
+            | b"CLUSTER ADDSLOTSRANGE"
+            | b"CLUSTER BUMPEPOCH"
+            | b"CLUSTER DELSLOTS"
+            | b"CLUSTER DELSLOTSRANGE"
+            | b"CLUSTER FAILOVER"
+            | b"CLUSTER FLUSHSLOTS"
+            | b"CLUSTER FORGET"
+            | b"CLUSTER MEET"
+            | b"CLUSTER REPLICATE"
+            | b"CLUSTER RESET"
+            | b"CLUSTER SAVECONFIG"
+            | b"CLUSTER SET-CONFIG-EPOCH"
+            | b"CLUSTER SETSLOT"
+            | b"CONFIG RESETSTAT"
+            | b"CONFIG REWRITE"
+            | b"CONFIG SET"
+            | b"DEBUG"
+            | b"FAILOVER"
+            | b"FUNCTION KILL"
+            | b"LATENCY RESET"
+            | b"MEMORY PURGE"
+            | b"MODULE LOAD"
+            | b"MODULE LOADEX"
+            | b"MODULE UNLOAD"
+            | b"REPLICAOF"
+            | b"SAVE"
+            | b"SCRIPT FLUSH"
+            | b"SCRIPT KILL"
+            | b"SCRIPT LOAD"
+            | b"SHUTDOWN"
+            | b"SLAVEOF"
+            | b"SLOWLOG RESET"
+    ) || command.starts_with(b"DEBUG ")
+}
+
+impl Middleware for CommandPolicy {
+    fn before(&self, request: &mut MiddlewareRequest) -> Option<RedisResult<Value>> {
+        let checked = match request {
+            MiddlewareRequest::Command { cmd, .. } => {
+                let checked = self.check(cmd);
+                if checked.is_ok() {
+                    self.rewrite(cmd);
+                }
+                checked
+            }
+            // A batch is rejected as a whole if any of its commands is rejected.
+            MiddlewareRequest::Batch { pipeline, .. } => {
+                let checked = pipeline.cmd_iter().try_for_each(|cmd| self.check(cmd));
+                if checked.is_ok() {
+                    pipeline.cmd_iter_mut().for_each(|cmd| self.rewrite(cmd));
+                }
+                checked
+            }
+            MiddlewareRequest::ClusterScan { .. } => self.check(&redis::cmd("SCAN")),
+        };
+        checked.err().map(Err)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use redis::cmd;
+
+    fn error_kind(policy: &CommandPolicy, cmd: &Cmd) -> Option<ErrorKind> {
+        policy.check(cmd).err().map(|err| err.kind())
+    }
+
+    #[test]
+    fn test_command_policy_denylist() {
+        let policy = CommandPolicy::denylist()
+            .request_type(RequestType::FlushAll)
+            .command("config set")
+            .command("DEBUG");
+        assert_eq!(
+            error_kind(&policy, &cmd("flushall")),
+            Some(ErrorKind::CommandNotAllowed)
+        );
+        assert_eq!(
+            error_kind(&policy, cmd("CONFIG").arg("SET").arg("maxmemory").arg(0)),
+            Some(ErrorKind::CommandNotAllowed)
+        );
+        assert_eq!(
+            error_kind(&policy, cmd("DEBUG").arg("SLEEP").arg(0)),
+            Some(ErrorKind::CommandNotAllowed)
+        );
+        assert_eq!(error_kind(&policy, cmd("CONFIG").arg("GET").arg("*")), None);
+        assert_eq!(error_kind(&policy, cmd("SET").arg("foo").arg("bar")), None);
+    }
+
+    #[test]
+    fn test_command_policy_allowlist() {
+        let policy = CommandPolicy::allowlist()
+            .request_type(RequestType::Get)
+            .command("CLIENT");
+        assert_eq!(error_kind(&policy, cmd("GET").arg("foo")), None);
+        assert_eq!(error_kind(&policy, cmd("CLIENT").arg("INFO")), None);
+        assert_eq!(
+            error_kind(&policy, cmd("SET").arg("foo").arg("bar")),
+            Some(ErrorKind::CommandNotAllowed)
+        );
+    }
+
+    #[test]
+    fn test_read_only_command_policy() {
+        let policy = CommandPolicy::denylist().read_only(true);
+        assert_eq!(error_kind(&policy, cmd("GET").arg("foo")), None);
+        assert_eq!(
+            error_kind(&policy, cmd("SET").arg("foo").arg("bar")),
+            Some(ErrorKind::CommandNotAllowed)
+        );
+        assert_eq!(error_kind(&policy, cmd("CONFIG").arg("GET").arg("*")), None);
+        for admin_cmd in [
+            "CONFIG SET maxmemory 0",
+            "SHUTDOWN",
+            "SCRIPT FLUSH",
+            "MODULE LOAD module.so",
+            "ACL SETUSER user on",
+            "CLUSTER FAILOVER",
+        ] {
+            let mut args = admin_cmd.split(' ');
+            let mut admin_cmd = cmd(args.next().unwrap());
+            for arg in args {
+                admin_cmd.arg(arg);
+            }
+            assert_eq!(
+                error_kind(&policy, &admin_cmd),
+                Some(ErrorKind::CommandNotAllowed)
+            );
+        }
+
+        let mut pipeline = redis::pipe();
+        pipeline
+            .get("foo")
+            .cmd("EVALSHA")
+            .arg("sha")
+            .arg(1)
+            .arg("foo");
+        let mut request = MiddlewareRequest::Batch {
+            pipeline,
+            routing: None,
+            is_atomic: true,
+        };
+        assert!(policy.before(&mut request).is_none());
+        let MiddlewareRequest::Batch { pipeline, .. } = &request else {
+            unreachable!()
+        };
+        let commands: Vec<_> = pipeline.cmd_iter().map(|cmd| cmd.command()).collect();
+        assert_eq!(
+            commands,
+            [Some(b"GET".to_vec()), Some(b"EVALSHA_RO".to_vec())]
+        );
+
+        let mut pipeline = redis::pipe();
+        pipeline.get("foo").del("foo");
+        let mut request = MiddlewareRequest::Batch {
+            pipeline,
+            routing: None,
+            is_atomic: false,
+        };
+        let response = policy.before(&mut request).unwrap();
+        assert_eq!(response.unwrap_err().kind(), ErrorKind::CommandNotAllowed);
+    }
+}
@@ -0,0 +1,170 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use async_trait::async_trait;
+use redis::aio::ConnectionCredentialsProvider;
+use redis::{ErrorKind, RedisError, RedisResult};
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// The interval in which the credentials file is re-read, when no interval is configured.
+pub const DEFAULT_CREDENTIALS_FILE_REFRESH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Credentials fetched from a [`CredentialProvider`].
+#[derive(Clone, PartialEq, Eq)]
+pub struct Credentials {
+    /// The username to authenticate with. If `None`, the username from the connection request is used.
+    pub username: Option<String>,
+    /// The password, or the auth token, to authenticate with.
+    pub password: String,
+    /// How long the credentials stay valid. The client fetches new credentials and re-authenticates
+    /// all connections before this duration passes. If `None`, the credentials are never refreshed.
+    pub valid_for: Option<Duration>,
+}
+
+impl fmt::Debug for Credentials {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Credentials")
+            .field("username", &self.username)
+            .field("valid_for", &self.valid_for)
+            .finish_non_exhaustive()
+    }
+}
+
+/// A source of short-lived credentials, such as the auth tokens issued by managed services.
+///
+/// The client fetches credentials from the provider before every connection attempt, including reconnects, and
+/// again before the previous credentials expire. On each refresh, all live connections are re-authenticated
+/// with the new credentials.
+#[async_trait]
+pub trait CredentialProvider: fmt::Debug + Send + Sync {
+    async fn get_credentials(&self) -> RedisResult<Credentials>;
+}
+
+/// A [`CredentialProvider`] that reads the auth token from a file.
+///
+/// The file is re-read every `refresh_interval`, and the connections are re-authenticated whenever its content changes,
+/// so the token can be rotated by rewriting the file.
+#[derive(Debug, Clone)]
+pub struct FileCredentialProvider {
+    path: PathBuf,
+    refresh_interval: Duration,
+}
+
+impl FileCredentialProvider {
+    pub fn new(path: impl Into<PathBuf>, refresh_interval: Option<Duration>) -> Self {
+        Self {
+            path: path.into(),
+            refresh_interval: refresh_interval.unwrap_or(DEFAULT_CREDENTIALS_FILE_REFRESH_INTERVAL),
+        }
+    }
+}
+
+#[async_trait]
+impl CredentialProvider for FileCredentialProvider {
+    async fn get_credentials(&self) -> RedisResult<Credentials> {
+        let content = tokio::fs::read_to_string(&self.path).await.map_err(|err| {
+            RedisError::from((
+                ErrorKind::InvalidClientConfig,
+                "Failed to read the credentials file",
+                format!("{}: {err}", self.path.display()),
+            ))
+        })?;
+        let token = content.trim();
+        if token.is_empty() {
+            return Err(RedisError::from((
+                ErrorKind::InvalidClientConfig,
+                "The credentials file is empty",
+                self.path.display().to_string(),
+            )));
+        }
+        Ok(Credentials {
+            username: None,
+            password: token.to_string(),
+            valid_for: Some(self.refresh_interval),
+        })
+    }
+}
+
+/// Lets the connections fetch their credentials from a [`CredentialProvider`] before connecting.
+#[derive(Debug)]
+struct ProviderConnectionCredentials(Arc<dyn CredentialProvider>);
+
+#[async_trait]
+impl ConnectionCredentialsProvider for ProviderConnectionCredentials {
+    async fn connection_credentials(&self) -> RedisResult<(Option<String>, String)> {
+        let credentials = self.0.get_credentials().await?;
+        Ok((credentials.username, credentials.password))
+    }
+}
+
+/// Wraps `provider` to be queried by each connection before it connects or reconnects.
+pub(super) fn for_connections(
+    provider: Arc<dyn CredentialProvider>,
+) -> Arc<dyn ConnectionCredentialsProvider> {
+    Arc::new(ProviderConnectionCredentials(provider))
+}
+
+/// Returns how long to wait before refreshing credentials that are valid for `valid_for`,
+/// leaving a fifth of their lifetime to fetch new credentials and re-authenticate.
+pub(super) fn refresh_delay(valid_for: Duration) -> Duration {
+    valid_for.mul_f64(0.8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+
+    #[tokio::test]
+    async fn test_file_credential_provider_reads_trimmed_token() {
+        let mut file = tempfile::NamedTempFile::new().unwrap();
+        writeln!(file, "  token-1 ").unwrap();
+        let provider = FileCredentialProvider::new(file.path(), Some(Duration::from_secs(5)));
+
+        let credentials = provider.get_credentials().await.unwrap();
+        assert_eq!(credentials.password, "token-1");
+        assert_eq!(credentials.username, None);
+        assert_eq!(credentials.valid_for, Some(Duration::from_secs(5)));
+
+        std::fs::write(file.path(), "token-2").unwrap();
+        let credentials = provider.get_credentials().await.unwrap();
+        assert_eq!(credentials.password, "token-2");
+    }
+
+    #[tokio::test]
+    async fn test_connections_fetch_the_credentials_from_the_provider() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        std::fs::write(file.path(), "token-1").unwrap();
+        let provider = for_connections(Arc::new(FileCredentialProvider::new(file.path(), None)));
+        assert_eq!(
+            provider.connection_credentials().await.unwrap(),
+            (None, "token-1".to_string())
+        );
+
+        std::fs::write(file.path(), "token-2").unwrap();
+        assert_eq!(
+            provider.connection_credentials().await.unwrap(),
+            (None, "token-2".to_string())
+        );
+    }
+
+    #[tokio::test]
+    async fn test_file_credential_provider_fails_on_missing_or_empty_file() {
+        let file = tempfile::NamedTempFile::new().unwrap();
+        let provider = FileCredentialProvider::new(file.path(), None);
+        assert_eq!(
+            provider.get_credentials().await.unwrap_err().kind(),
+            ErrorKind::InvalidClientConfig
+        );
+
+        let path = file.path().to_path_buf();
+        drop(file);
+        let provider = FileCredentialProvider::new(path, None);
+        assert_eq!(
+            provider.get_credentials().await.unwrap_err().kind(),
+            ErrorKind::InvalidClientConfig
+        );
+    }
+}
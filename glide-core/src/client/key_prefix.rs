@@ -0,0 +1,257 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use super::{Middleware, MiddlewareRequest};
+use redis::cluster_routing::{Routable, key_indices};
+use redis::{Cmd, RedisResult, Value};
+
+/// Namespaces the keys of a client, by prepending a prefix to the keys of its commands,
+/// and stripping it from the key names in the responses of commands such as SCAN, KEYS, and BLPOP.
+#[derive(Debug)]
+pub(crate) struct KeyPrefix {
+    prefix: Vec<u8>,
+    // The prefix, with the characters that are special in glob-style patterns escaped.
+    pattern_prefix: Vec<u8>,
+}
+
+impl KeyPrefix {
+    pub(crate) fn new(prefix: Vec<u8>) -> Self {
+        let mut pattern_prefix = Vec::with_capacity(prefix.len());
+        for byte in &prefix {
+            if matches!(byte, b'*' | b'?' | b'[' | b']' | b'\\') {
+                pattern_prefix.push(b'\\');
+            }
+            pattern_prefix.push(*byte);
+        }
+        Self {
+            prefix,
+            pattern_prefix,
+        }
+    }
+
+    /// Returns `key`, with the prefix prepended.
+    pub(crate) fn prefixed(&self, key: &[u8]) -> Vec<u8> {
+        [self.prefix.as_slice(), key].concat()
+    }
+
+    fn prefix_pattern(&self, pattern: Option<&[u8]>) -> Vec<u8> {
+        [self.pattern_prefix.as_slice(), pattern.unwrap_or(b"*")].concat()
+    }
+
+    fn prefix_cmd(&self, cmd: &mut Cmd) {
+        match cmd.command().as_deref() {
+            Some(b"KEYS") => {
+                cmd.prefix_args(&[1], &self.pattern_prefix);
+            }
+            // SCAN cursor [MATCH pattern] [COUNT count] [TYPE type]
+            Some(b"SCAN") => match cmd.position(b"MATCH").filter(|idx| *idx > 1) {
+                Some(idx) => {
+                    cmd.prefix_args(&[idx + 1], &self.pattern_prefix);
+                }
+                None => {
+                    cmd.arg("MATCH").arg(self.prefix_pattern(None));
+                }
+            },
+            _ => {
+                cmd.prefix_args(&key_indices(cmd), &self.prefix);
+            }
+        }
+    }
+
+    fn strip_key(&self, key: Value) -> Value {
+        match key {
+            Value::BulkString(key) if key.starts_with(&self.prefix) => {
+                Value::BulkString(key[self.prefix.len()..].to_vec())
+            }
+            key => key,
+        }
+    }
+
+    fn strip_first_key(&self, value: Value) -> Value {
+        match value {
+            Value::Array(mut values) if !values.is_empty() => {
+                let key = std::mem::replace(&mut values[0], Value::Nil);
+                values[0] = self.strip_key(key);
+                Value::Array(values)
+            }
+            value => value,
+        }
+    }
+
+    fn strip_keys(&self, value: Value) -> Value {
+        match value {
+            Value::Array(keys) => {
+                Value::Array(keys.into_iter().map(|key| self.strip_key(key)).collect())
+            }
+            value => value,
+        }
+    }
+
+    // A scan response holds the cursor, and the keys.
+    fn strip_scan_response(&self, value: Value) -> Value {
+        match value {
+            Value::Array(mut values) if values.len() == 2 => {
+                let keys = values.pop().unwrap();
+                values.push(self.strip_keys(keys));
+                Value::Array(values)
+            }
+            value => value,
+        }
+    }
+
+    // Strips the prefix from the key names in the responses of the commands that return key names.
+    fn strip_response(&self, cmd: &Cmd, value: Value) -> Value {
+        match cmd.command().as_deref() {
+            Some(b"KEYS") => self.strip_keys(value),
+            Some(b"RANDOMKEY") => self.strip_key(value),
+            Some(b"SCAN") => self.strip_scan_response(value),
+            Some(
+                b"BLPOP" | b"BRPOP" | b"BZPOPMAX" | b"BZPOPMIN" | b"BLMPOP" | b"LMPOP" | b"BZMPOP"
+                | b"ZMPOP",
+            ) => self.strip_first_key(value),
+            // A map of streams in RESP3, and an array of stream and entries pairs in RESP2.
+            Some(b"XREAD" | b"XREADGROUP") => match value {
+                Value::Map(streams) => Value::Map(
+                    streams
+                        .into_iter()
+                        .map(|(key, entries)| (self.strip_key(key), entries))
+                        .collect(),
+                ),
+                Value::Array(streams) => Value::Array(
+                    streams
+                        .into_iter()
+                        .map(|stream| self.strip_first_key(stream))
+                        .collect(),
+                ),
+                value => value,
+            },
+            _ => value,
+        }
+    }
+}
+
+impl Middleware for KeyPrefix {
+    fn before(&self, request: &mut MiddlewareRequest) -> Option<RedisResult<Value>> {
+        match request {
+            MiddlewareRequest::Command { cmd, .. } => self.prefix_cmd(cmd),
+            MiddlewareRequest::Batch { pipeline, .. } => {
+                pipeline.cmd_iter_mut().for_each(|cmd| self.prefix_cmd(cmd))
+            }
+            MiddlewareRequest::ClusterScan { args } => {
+                args.match_pattern = Some(self.prefix_pattern(args.match_pattern.as_deref()));
+            }
+        }
+        None
+    }
+
+    fn after(&self, request: &MiddlewareRequest, result: RedisResult<Value>) -> RedisResult<Value> {
+        let value = result?;
+        Ok(match request {
+            MiddlewareRequest::Command { cmd, .. } => self.strip_response(cmd, value),
+            MiddlewareRequest::Batch { pipeline, .. } => match value {
+                Value::Array(values) => Value::Array(
+                    pipeline
+                        .cmd_iter()
+                        .zip(values)
+                        .map(|(cmd, value)| self.strip_response(cmd, value))
+                        .collect(),
+                ),
+                value => value,
+            },
+            MiddlewareRequest::ClusterScan { .. } => self.strip_scan_response(value),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn prefixed(prefix: &KeyPrefix, cmd: Cmd) -> Vec<Vec<u8>> {
+        let mut request = MiddlewareRequest::Command { cmd, routing: None };
+        prefix.before(&mut request);
+        let MiddlewareRequest::Command { cmd, .. } = request else {
+            unreachable!()
+        };
+        (0..)
+            .map_while(|idx| Routable::arg_idx(&cmd, idx).map(|arg| arg.to_vec()))
+            .collect()
+    }
+
+    #[test]
+    fn test_key_prefix_prepends_the_prefix_to_keys_and_patterns() {
+        let prefix = KeyPrefix::new(b"app[1]:".to_vec());
+        assert_eq!(
+            prefixed(&prefix, redis::cmd("MSET").arg("a").arg("1").clone()),
+            [b"MSET".to_vec(), b"app[1]:a".to_vec(), b"1".to_vec()]
+        );
+        assert_eq!(
+            prefixed(&prefix, redis::cmd("KEYS").arg("a*").clone()),
+            [b"KEYS".to_vec(), b"app\\[1\\]:a*".to_vec()]
+        );
+        assert_eq!(
+            prefixed(&prefix, redis::cmd("SCAN").arg("0").clone()),
+            [
+                b"SCAN".to_vec(),
+                b"0".to_vec(),
+                b"MATCH".to_vec(),
+                b"app\\[1\\]:*".to_vec()
+            ]
+        );
+        assert_eq!(
+            prefixed(
+                &prefix,
+                redis::cmd("PUBLISH").arg("channel").arg("message").clone()
+            ),
+            [
+                b"PUBLISH".to_vec(),
+                b"channel".to_vec(),
+                b"message".to_vec()
+            ]
+        );
+    }
+
+    #[test]
+    fn test_key_prefix_strips_the_prefix_from_key_names() {
+        let prefix = KeyPrefix::new(b"app:".to_vec());
+        let request = MiddlewareRequest::Command {
+            cmd: redis::cmd("BLPOP").arg("app:list").arg(0).clone(),
+            routing: None,
+        };
+        let result = prefix.after(
+            &request,
+            Ok(Value::Array(vec![
+                Value::BulkString(b"app:list".to_vec()),
+                Value::BulkString(b"app:value".to_vec()),
+            ])),
+        );
+        assert_eq!(
+            result,
+            Ok(Value::Array(vec![
+                Value::BulkString(b"list".to_vec()),
+                Value::BulkString(b"app:value".to_vec()),
+            ]))
+        );
+
+        let mut pipeline = redis::pipe();
+        pipeline.cmd("RANDOMKEY").cmd("GET").arg("app:key");
+        let request = MiddlewareRequest::Batch {
+            pipeline,
+            routing: None,
+            is_atomic: false,
+        };
+        let result = prefix.after(
+            &request,
+            Ok(Value::Array(vec![
+                Value::BulkString(b"app:key".to_vec()),
+                Value::BulkString(b"app:value".to_vec()),
+            ])),
+        );
+        assert_eq!(
+            result,
+            Ok(Value::Array(vec![
+                Value::BulkString(b"key".to_vec()),
+                Value::BulkString(b"app:value".to_vec()),
+            ]))
+        );
+    }
+}
@@ -0,0 +1,236 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use redis::cluster_routing::RoutingInfo;
+use redis::{ClusterScanArgs, Cmd, ErrorKind, Pipeline, RedisError, RedisResult, Value};
+use std::fmt;
+use std::mem;
+use std::sync::Arc;
+
+/// A request that passes through the [`Middleware`] chain of a [`Client`](super::Client).
+#[derive(Debug, Clone)]
+pub enum MiddlewareRequest {
+    /// A single command, including the commands that invoke scripts.
+    Command {
+        cmd: Cmd,
+        routing: Option<RoutingInfo>,
+    },
+    /// A pipeline, or a transaction if `is_atomic` is true.
+    Batch {
+        pipeline: Pipeline,
+        routing: Option<RoutingInfo>,
+        is_atomic: bool,
+    },
+    /// A single iteration of a cluster scan.
+    ClusterScan { args: ClusterScanArgs },
+}
+
+/// Code that runs around the requests of a [`Client`](super::Client), e.g. to audit, rewrite, or reject them.
+///
+/// The middlewares of a client run in the order they were registered before a request is sent, and in the reverse
+/// order once it completes. A middleware that responds to a request in [`Middleware::before`] short-circuits it:
+/// the request isn't sent, and only the middlewares that ran before it see the response.
+pub trait Middleware: fmt::Debug + Send + Sync {
+    /// Called before `request` is sent. The request may be rewritten, but not replaced with a request of another kind.
+    /// Returning a response short-circuits the request.
+    fn before(&self, _request: &mut MiddlewareRequest) -> Option<RedisResult<Value>> {
+        None
+    }
+
+    /// Called with the result of `request`, which may be inspected or replaced.
+    fn after(
+        &self,
+        _request: &MiddlewareRequest,
+        result: RedisResult<Value>,
+    ) -> RedisResult<Value> {
+        result
+    }
+}
+
+/// The middlewares of a client.
+#[derive(Debug, Clone, Default)]
+pub(crate) struct MiddlewareChain(Arc<Vec<Arc<dyn Middleware>>>);
+
+impl MiddlewareChain {
+    pub(crate) fn new(middlewares: Vec<Arc<dyn Middleware>>) -> Self {
+        Self(Arc::new(middlewares))
+    }
+
+    pub(crate) fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    /// Runs the `before` hooks, until one of them responds.
+    /// Returns the number of middlewares whose `after` hooks should run, and the response, if the request was short-circuited.
+    pub(crate) fn before(
+        &self,
+        request: &mut MiddlewareRequest,
+    ) -> (usize, Option<RedisResult<Value>>) {
+        let kind = mem::discriminant(request);
+        for (index, middleware) in self.0.iter().enumerate() {
+            if let Some(response) = middleware.before(request) {
+                return (index, Some(response));
+            }
+            if mem::discriminant(request) != kind {
+                return (
+                    index,
+                    Some(Err(RedisError::from((
+                        ErrorKind::ClientError,
+                        "A middleware replaced the request with a request of another kind",
+                        format!("{middleware:?}"),
+                    )))),
+                );
+            }
+        }
+        (self.0.len(), None)
+    }
+
+    /// Runs the `after` hooks of the first `entered` middlewares, in reverse order.
+    pub(crate) fn after(
+        &self,
+        entered: usize,
+        request: &MiddlewareRequest,
+        result: RedisResult<Value>,
+    ) -> RedisResult<Value> {
+        self.0[..entered]
+            .iter()
+            .rev()
+            .fold(result, |result, middleware| {
+                middleware.after(request, result)
+            })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::Mutex;
+
+    #[derive(Debug)]
+    struct Recorder {
+        name: &'static str,
+        calls: Arc<Mutex<Vec<String>>>,
+        respond: bool,
+    }
+
+    impl Middleware for Recorder {
+        fn before(&self, request: &mut MiddlewareRequest) -> Option<RedisResult<Value>> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("before {}", self.name));
+            if let MiddlewareRequest::Command { cmd, .. } = request {
+                cmd.arg(self.name);
+            }
+            self.respond
+                .then(|| Ok(Value::SimpleString(self.name.to_string())))
+        }
+
+        fn after(
+            &self,
+            _request: &MiddlewareRequest,
+            result: RedisResult<Value>,
+        ) -> RedisResult<Value> {
+            self.calls
+                .lock()
+                .unwrap()
+                .push(format!("after {}", self.name));
+            result
+        }
+    }
+
+    fn chain(calls: &Arc<Mutex<Vec<String>>>, responders: &[bool]) -> MiddlewareChain {
+        let names = ["first", "second", "third"];
+        MiddlewareChain::new(
+            responders
+                .iter()
+                .zip(names)
+                .map(|(respond, name)| {
+                    Arc::new(Recorder {
+                        name,
+                        calls: calls.clone(),
+                        respond: *respond,
+                    }) as Arc<dyn Middleware>
+                })
+                .collect(),
+        )
+    }
+
+    #[test]
+    fn test_middlewares_run_in_order_around_the_request() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let chain = chain(&calls, &[false, false]);
+        let mut request = MiddlewareRequest::Command {
+            cmd: redis::cmd("GET"),
+            routing: None,
+        };
+        let (entered, response) = chain.before(&mut request);
+        assert_eq!(entered, 2);
+        assert!(response.is_none());
+        let MiddlewareRequest::Command { cmd, .. } = &request else {
+            unreachable!()
+        };
+        assert_eq!(
+            cmd.get_packed_command(),
+            redis::cmd("GET")
+                .arg("first")
+                .arg("second")
+                .get_packed_command()
+        );
+
+        let result = chain.after(entered, &request, Ok(Value::Okay));
+        assert_eq!(result, Ok(Value::Okay));
+        assert_eq!(
+            *calls.lock().unwrap(),
+            [
+                "before first",
+                "before second",
+                "after second",
+                "after first"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_middleware_short_circuits_the_request() {
+        let calls = Arc::new(Mutex::new(Vec::new()));
+        let chain = chain(&calls, &[false, true, false]);
+        let mut request = MiddlewareRequest::Command {
+            cmd: redis::cmd("GET"),
+            routing: None,
+        };
+        let (entered, response) = chain.before(&mut request);
+        assert_eq!(entered, 1);
+        let result = chain.after(entered, &request, response.unwrap());
+        assert_eq!(result, Ok(Value::SimpleString("second".to_string())));
+        assert_eq!(
+            *calls.lock().unwrap(),
+            ["before first", "before second", "after first"]
+        );
+    }
+
+    #[test]
+    fn test_middleware_cant_replace_the_request_kind() {
+        #[derive(Debug)]
+        struct Replacer;
+        impl Middleware for Replacer {
+            fn before(&self, request: &mut MiddlewareRequest) -> Option<RedisResult<Value>> {
+                *request = MiddlewareRequest::ClusterScan {
+                    args: ClusterScanArgs::builder().build(),
+                };
+                None
+            }
+        }
+
+        let chain = MiddlewareChain::new(vec![Arc::new(Replacer)]);
+        let mut request = MiddlewareRequest::Command {
+            cmd: redis::cmd("GET"),
+            routing: None,
+        };
+        let (entered, response) = chain.before(&mut request);
+        assert_eq!(entered, 0);
+        assert_eq!(
+            response.unwrap().unwrap_err().kind(),
+            ErrorKind::ClientError
+        );
+    }
+}
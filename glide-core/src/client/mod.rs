@@ -10,12 +10,15 @@ use once_cell::sync::OnceCell;
 use redis::aio::ConnectionLike;
 use redis::cluster_async::ClusterConnection;
 use redis::cluster_routing::{
-    MultipleNodeRoutingInfo, ResponsePolicy, Routable, RoutingInfo, SingleNodeRoutingInfo,
+    CommandRoutingTable, MultipleNodeRoutingInfo, ResponsePolicy, Routable, RoutingInfo,
+    SingleNodeRoutingInfo,
 };
 use redis::cluster_slotmap::ReadFromReplicaStrategy;
+use redis::cluster_topology::get_slot;
 use redis::{
-    ClusterScanArgs, Cmd, ErrorKind, FromRedisValue, PipelineRetryStrategy, PushInfo, RedisError,
-    RedisResult, RetryStrategy, ScanStateRC, Value,
+    ClusterScanArgs, Cmd, ErrorKind, FromRedisValue, PipelineRetryStrategy, PubSubChannelOrPattern,
+    PubSubSubscriptionInfo, PubSubSubscriptionKind, PushInfo, RedisError, RedisResult,
+    RetryStrategy, ScanStateRC, Value,
 };
 pub use standalone_client::StandaloneClient;
 use std::io;
@@ -28,7 +31,23 @@ use tokio::runtime::{Builder, Handle};
 pub use types::*;
 
 use self::value_conversion::{convert_to_expected_type, expected_type_for_cmd, get_value_type};
+mod client_side_cache;
+pub use client_side_cache::ClientSideCacheStatistics;
+use client_side_cache::{CacheLookup, ClientSideCache};
+mod command_policy;
+pub use command_policy::CommandPolicy;
+mod credentials;
+mod key_prefix;
+mod middleware;
+pub use credentials::{
+    CredentialProvider, Credentials, DEFAULT_CREDENTIALS_FILE_REFRESH_INTERVAL,
+    FileCredentialProvider,
+};
+use key_prefix::KeyPrefix;
+use middleware::MiddlewareChain;
+pub use middleware::{Middleware, MiddlewareRequest};
 mod reconnecting_connection;
+mod sentinel;
 mod standalone_client;
 mod value_conversion;
 use redis::InfoDict;
@@ -161,14 +180,17 @@ pub(super) fn get_redis_connection_info(
 pub(super) fn get_connection_info(
     address: &NodeAddress,
     tls_mode: TlsMode,
+    tls_params: Option<redis::TlsConnParams>,
     redis_connection_info: redis::RedisConnectionInfo,
 ) -> redis::ConnectionInfo {
-    let addr = if tls_mode != TlsMode::NoTls {
+    let addr = if let Some(path) = &address.unix_socket_path {
+        redis::ConnectionAddr::Unix(path.clone())
+    } else if tls_mode != TlsMode::NoTls {
         redis::ConnectionAddr::TcpTls {
             host: address.host.to_string(),
             port: get_port(address),
             insecure: tls_mode == TlsMode::InsecureTls,
-            tls_params: None,
+            tls_params,
         }
     } else {
         redis::ConnectionAddr::Tcp(address.host.to_string(), get_port(address))
@@ -179,6 +201,64 @@ pub(super) fn get_connection_info(
     }
 }
 
+/// Converts the custom TLS configuration into the certificates used by the connections.
+/// Fails if the configuration can't be applied with the given TLS mode, or if it's incomplete.
+pub(super) fn get_tls_certificates(
+    tls_mode: TlsMode,
+    tls_config: Option<&TlsConfig>,
+) -> RedisResult<Option<redis::TlsCertificates>> {
+    let Some(tls_config) = tls_config else {
+        return Ok(None);
+    };
+    if tls_mode == TlsMode::NoTls {
+        return Err(RedisError::from((
+            ErrorKind::InvalidClientConfig,
+            "TLS configuration was provided, but TLS is disabled",
+        )));
+    }
+    tls_config_to_certificates(tls_config).map(Some)
+}
+
+fn tls_config_to_certificates(tls_config: &TlsConfig) -> RedisResult<redis::TlsCertificates> {
+    let client_tls = match (&tls_config.client_cert, &tls_config.client_key) {
+        (Some(client_cert), Some(client_key)) => Some(redis::ClientTlsConfig {
+            client_cert: client_cert.clone(),
+            client_key: client_key.clone(),
+        }),
+        (None, None) => None,
+        _ => {
+            return Err(RedisError::from((
+                ErrorKind::InvalidClientConfig,
+                "Client certificate and client key must be provided together",
+            )));
+        }
+    };
+    Ok(redis::TlsCertificates {
+        client_tls,
+        root_cert: tls_config.root_certs.clone(),
+        server_name: tls_config.server_name.clone(),
+    })
+}
+
+fn subscriptions_to_value(subscriptions: PubSubSubscriptionInfo) -> Value {
+    Value::Map(
+        subscriptions
+            .into_iter()
+            .map(|(kind, channels_or_patterns)| {
+                (
+                    Value::Int(usize::from(kind) as i64),
+                    Value::Set(
+                        channels_or_patterns
+                            .into_iter()
+                            .map(Value::BulkString)
+                            .collect(),
+                    ),
+                )
+            })
+            .collect(),
+    )
+}
+
 #[derive(Clone)]
 pub enum ClientWrapper {
     Standalone(StandaloneClient),
@@ -199,6 +279,11 @@ pub struct Client {
     request_timeout: Duration,
     // Setting this counter to limit the inflight requests, in case of any queue is blocked, so we return error to the customer.
     inflight_requests_allowed: Arc<AtomicIsize>,
+    client_side_cache: Option<Arc<ClientSideCache>>,
+    // Routes for the cluster commands that redis-rs doesn't know, shared with the cluster client that discovers them.
+    command_routing_table: Option<Arc<CommandRoutingTable>>,
+    key_prefix: Option<Arc<KeyPrefix>>,
+    middleware: MiddlewareChain,
 }
 
 async fn run_with_timeout<T>(
@@ -206,9 +291,10 @@ async fn run_with_timeout<T>(
     future: impl futures::Future<Output = RedisResult<T>> + Send,
 ) -> redis::RedisResult<T> {
     match timeout {
-        Some(duration) => match tokio::time::timeout(duration, future).await {
-            Ok(result) => result,
-            Err(_) => {
+        // The requests that time out count as failures of their nodes' circuit breakers.
+        Some(duration) => match redis::with_request_timeout(duration, future).await {
+            Some(result) => result,
+            None => {
                 // Record timeout error metric if telemetry is initialized
                 if let Err(e) = GlideOpenTelemetry::record_timeout_error() {
                     log_error(
@@ -381,13 +467,71 @@ impl Client {
         cmd: &'a Cmd,
         routing: Option<RoutingInfo>,
     ) -> redis::RedisFuture<'a, Value> {
+        self.send_command_with_timeout(cmd, routing, None)
+    }
+
+    /// Send a command to the server, like [`Client::send_command`].
+    /// If `request_timeout` is set, it overrides the client's request timeout for this command, in milliseconds,
+    /// including the timeout extension of blocking commands. The timeout covers all of the command's retries and redirects.
+    pub fn send_command_with_timeout<'a>(
+        &'a mut self,
+        cmd: &'a Cmd,
+        routing: Option<RoutingInfo>,
+        request_timeout: Option<u32>,
+    ) -> redis::RedisFuture<'a, Value> {
+        if self.middleware.is_empty() {
+            return self.dispatch_command(cmd, routing, request_timeout);
+        }
         Box::pin(async move {
+            let middleware = self.middleware.clone();
+            let mut request = MiddlewareRequest::Command {
+                cmd: cmd.clone(),
+                routing,
+            };
+            let (entered, response) = middleware.before(&mut request);
+            let result = match response {
+                Some(response) => response,
+                None => {
+                    let MiddlewareRequest::Command { cmd, routing } = &request else {
+                        unreachable!("The middlewares can't change the kind of a request")
+                    };
+                    self.dispatch_command(cmd, routing.clone(), request_timeout)
+                        .await
+                }
+            };
+            middleware.after(entered, &request, result)
+        })
+    }
+
+    /// Sends a command without passing it through the middlewares.
+    fn dispatch_command<'a>(
+        &'a mut self,
+        cmd: &'a Cmd,
+        routing: Option<RoutingInfo>,
+        request_timeout: Option<u32>,
+    ) -> redis::RedisFuture<'a, Value> {
+        Box::pin(async move {
+            // Explicitly routed commands bypass the cache, since they might target a specific node on purpose.
+            let cache_ticket = match self
+                .client_side_cache
+                .as_ref()
+                .filter(|_| routing.is_none())
+            {
+                Some(cache) => match cache.get(cmd) {
+                    CacheLookup::Hit(value) => return Ok(value),
+                    CacheLookup::Miss(ticket) => Some(ticket),
+                    CacheLookup::NotCacheable => None,
+                },
+                None => None,
+            };
+
             let client = self.get_or_initialize_client().await?;
+            let command_routing_table = self.command_routing_table.clone();
 
             let expected_type = expected_type_for_cmd(cmd);
-            let request_timeout = match get_request_timeout(cmd, self.request_timeout) {
-                Ok(request_timeout) => request_timeout,
-                Err(err) => return Err(err),
+            let request_timeout = match request_timeout {
+                Some(request_timeout) => Some(Duration::from_millis(request_timeout.into())),
+                None => get_request_timeout(cmd, self.request_timeout)?,
             };
 
             let value = run_with_timeout(request_timeout, async move {
@@ -416,7 +560,10 @@ impl Client {
                                 }
                             } else {
                                 routing
-                                    .or_else(|| RoutingInfo::for_routable(cmd))
+                                    .or_else(|| match &command_routing_table {
+                                        Some(table) => RoutingInfo::for_routable_with_table(cmd, table),
+                                        None => RoutingInfo::for_routable(cmd),
+                                    })
                                     .unwrap_or(RoutingInfo::SingleNode(SingleNodeRoutingInfo::Random))
                             };
                         client.route_command(cmd, final_routing).await
@@ -425,12 +572,31 @@ impl Client {
                 }
                 .and_then(|value| convert_to_expected_type(value, expected_type))
             })
-            .await?;
+            .await;
+
+            if let Some(cache) = self.client_side_cache.as_ref() {
+                // A write that failed, e.g. because it timed out, might have been executed anyway.
+                cache.handle_write(cmd);
+                match (cache_ticket, &value) {
+                    (Some(ticket), Ok(value)) => cache.insert(ticket, value),
+                    (None, Ok(_)) => cache.handle_select(cmd),
+                    (_, Err(_)) => {}
+                }
+            }
 
-            Ok(value)
+            value
         })
     }
 
+    // Removes the cached responses that read the keys written by the commands of `pipeline`, so the client reads its own writes.
+    fn invalidate_written_keys(&self, pipeline: &redis::Pipeline) {
+        if let Some(cache) = self.client_side_cache.as_ref() {
+            for cmd in pipeline.cmd_iter() {
+                cache.handle_write(cmd);
+            }
+        }
+    }
+
     // Cluster scan is not passed to redis-rs as a regular command, so we need to handle it separately.
     // We send the command to a specific function in the redis-rs cluster client, which internally handles the
     // the complication of a command scan, and generate the command base on the logic in the redis-rs library.
@@ -444,10 +610,38 @@ impl Client {
     //
     // The wrapper create an object contain the cursor-id with a drop function that will remove the cursor from the container.
     // When the ref is removed from the hash-map, there's no more references to the ScanState, and the GC will clean it.
+    //
+    // If `request_timeout` is set, it limits the whole scan iteration, in milliseconds.
     pub async fn cluster_scan<'a>(
         &'a mut self,
         scan_state_cursor: &'a ScanStateRC,
         cluster_scan_args: ClusterScanArgs,
+        request_timeout: Option<u32>,
+    ) -> RedisResult<Value> {
+        let middleware = self.middleware.clone();
+        let mut request = MiddlewareRequest::ClusterScan {
+            args: cluster_scan_args,
+        };
+        let (entered, response) = middleware.before(&mut request);
+        let result = match response {
+            Some(response) => response,
+            None => {
+                let MiddlewareRequest::ClusterScan { args } = &request else {
+                    unreachable!("The middlewares can't change the kind of a request")
+                };
+                self.dispatch_cluster_scan(scan_state_cursor, args.clone(), request_timeout)
+                    .await
+            }
+        };
+        middleware.after(entered, &request, result)
+    }
+
+    /// Runs a cluster scan iteration without passing it through the middlewares.
+    async fn dispatch_cluster_scan<'a>(
+        &'a mut self,
+        scan_state_cursor: &'a ScanStateRC,
+        cluster_scan_args: ClusterScanArgs,
+        request_timeout: Option<u32>,
     ) -> RedisResult<Value> {
         // Clone arguments before the async block (ScanStateRC is Arc, clone is cheap)
         let scan_state_cursor_clone = scan_state_cursor.clone();
@@ -461,9 +655,13 @@ impl Client {
                 unreachable!("Cluster scan is not supported in standalone mode")
             }
             ClientWrapper::Cluster { mut client } => {
-                let (cursor, keys) = client
-                    .cluster_scan(scan_state_cursor_clone, cluster_scan_args_clone) // Use clones
-                    .await?;
+                let request_timeout =
+                    request_timeout.map(|timeout| Duration::from_millis(timeout.into()));
+                let (cursor, keys) = run_with_timeout(
+                    request_timeout,
+                    client.cluster_scan(scan_state_cursor_clone, cluster_scan_args_clone), // Use clones
+                )
+                .await?;
                 let cluster_cursor_id = if cursor.is_finished() {
                     Value::BulkString(FINISHED_SCAN_CURSOR.into()) // Use constant
                 } else {
@@ -558,6 +756,52 @@ impl Client {
         routing: Option<RoutingInfo>,
         transaction_timeout: Option<u32>,
         raise_on_error: bool,
+    ) -> redis::RedisFuture<'a, Value> {
+        if self.middleware.is_empty() {
+            return self.dispatch_transaction(
+                pipeline,
+                routing,
+                transaction_timeout,
+                raise_on_error,
+            );
+        }
+        Box::pin(async move {
+            let middleware = self.middleware.clone();
+            let mut request = MiddlewareRequest::Batch {
+                pipeline: pipeline.clone(),
+                routing,
+                is_atomic: true,
+            };
+            let (entered, response) = middleware.before(&mut request);
+            let result = match response {
+                Some(response) => response,
+                None => {
+                    let MiddlewareRequest::Batch {
+                        pipeline, routing, ..
+                    } = &request
+                    else {
+                        unreachable!("The middlewares can't change the kind of a request")
+                    };
+                    self.dispatch_transaction(
+                        pipeline,
+                        routing.clone(),
+                        transaction_timeout,
+                        raise_on_error,
+                    )
+                    .await
+                }
+            };
+            middleware.after(entered, &request, result)
+        })
+    }
+
+    /// Sends a transaction without passing it through the middlewares.
+    fn dispatch_transaction<'a>(
+        &'a mut self,
+        pipeline: &'a redis::Pipeline,
+        routing: Option<RoutingInfo>,
+        transaction_timeout: Option<u32>,
+        raise_on_error: bool,
     ) -> redis::RedisFuture<'a, Value> {
         Box::pin(async move {
             let client = self.get_or_initialize_client().await?;
@@ -570,7 +814,7 @@ impl Client {
             // which is an array containing the results of all the commands in the pipeline.
             let offset = command_count + 1;
 
-            run_with_timeout(
+            let result = run_with_timeout(
                 Some(to_duration(transaction_timeout, self.request_timeout)),
                 async move {
                     match client {
@@ -611,7 +855,9 @@ impl Client {
                     }
                 },
             )
-            .await
+            .await;
+            self.invalidate_written_keys(pipeline);
+            result
         })
     }
 
@@ -635,6 +881,55 @@ impl Client {
         raise_on_error: bool,
         pipeline_timeout: Option<u32>,
         pipeline_retry_strategy: PipelineRetryStrategy,
+    ) -> redis::RedisFuture<'a, Value> {
+        if self.middleware.is_empty() {
+            return self.dispatch_pipeline(
+                pipeline,
+                routing,
+                raise_on_error,
+                pipeline_timeout,
+                pipeline_retry_strategy,
+            );
+        }
+        Box::pin(async move {
+            let middleware = self.middleware.clone();
+            let mut request = MiddlewareRequest::Batch {
+                pipeline: pipeline.clone(),
+                routing,
+                is_atomic: false,
+            };
+            let (entered, response) = middleware.before(&mut request);
+            let result = match response {
+                Some(response) => response,
+                None => {
+                    let MiddlewareRequest::Batch {
+                        pipeline, routing, ..
+                    } = &request
+                    else {
+                        unreachable!("The middlewares can't change the kind of a request")
+                    };
+                    self.dispatch_pipeline(
+                        pipeline,
+                        routing.clone(),
+                        raise_on_error,
+                        pipeline_timeout,
+                        pipeline_retry_strategy,
+                    )
+                    .await
+                }
+            };
+            middleware.after(entered, &request, result)
+        })
+    }
+
+    /// Sends a pipeline without passing it through the middlewares.
+    fn dispatch_pipeline<'a>(
+        &'a mut self,
+        pipeline: &'a redis::Pipeline,
+        routing: Option<RoutingInfo>,
+        raise_on_error: bool,
+        pipeline_timeout: Option<u32>,
+        pipeline_retry_strategy: PipelineRetryStrategy,
     ) -> redis::RedisFuture<'a, Value> {
         Box::pin(async move {
             let client = self.get_or_initialize_client().await?;
@@ -647,7 +942,7 @@ impl Client {
                 )));
             }
 
-            run_with_timeout(
+            let result = run_with_timeout(
                 Some(to_duration(pipeline_timeout, self.request_timeout)),
                 async move {
                     let values = match client {
@@ -691,33 +986,71 @@ impl Client {
                     )
                 },
             )
-            .await
+            .await;
+            self.invalidate_written_keys(pipeline);
+            result
         })
     }
 
+    /// Invokes a stored script. If `request_timeout` is set, it overrides the client's request timeout, in milliseconds,
+    /// and covers the whole invocation, including loading the script if the server doesn't have it.
     pub async fn invoke_script<'a>(
         &'a mut self,
         hash: &'a str,
         keys: &Vec<&[u8]>,
         args: &Vec<&[u8]>,
         routing: Option<RoutingInfo>,
+        request_timeout: Option<u32>,
     ) -> redis::RedisResult<Value> {
         let _ = self.get_or_initialize_client().await?;
 
-        let eval = eval_cmd(hash, keys, args);
-        let result = self.send_command(&eval, routing.clone()).await;
-        let Err(err) = result else {
-            return result;
+        // The middlewares see the invocation, and the loading of the script if the server doesn't have it, so the
+        // command policy can reject either.
+        let middleware = self.middleware.clone();
+        let mut request = MiddlewareRequest::Command {
+            cmd: eval_cmd(hash, keys, args),
+            routing,
         };
-        if err.kind() == ErrorKind::NoScriptError {
-            let Some(code) = get_script(hash) else {
-                return Err(err);
-            };
-            let load = load_cmd(&code);
-            self.send_command(&load, None).await?;
-            self.send_command(&eval, routing).await
-        } else {
-            Err(err)
+        let (entered, response) = middleware.before(&mut request);
+        let result = match response {
+            Some(response) => response,
+            None => {
+                let MiddlewareRequest::Command { cmd: eval, routing } = &request else {
+                    unreachable!("The middlewares can't change the kind of a request")
+                };
+                let invocation_timeout =
+                    request_timeout.map(|timeout| Duration::from_millis(timeout.into()));
+                let invocation = async {
+                    let result = self
+                        .dispatch_command(eval, routing.clone(), request_timeout)
+                        .await;
+                    let Err(err) = result else {
+                        return result;
+                    };
+                    if err.kind() == ErrorKind::NoScriptError {
+                        let Some(code) = get_script(hash) else {
+                            return Err(err);
+                        };
+                        let load = load_cmd(&code);
+                        self.send_command_with_timeout(&load, None, request_timeout)
+                            .await?;
+                        self.dispatch_command(eval, routing.clone(), request_timeout)
+                            .await
+                    } else {
+                        Err(err)
+                    }
+                };
+                run_with_timeout(invocation_timeout, invocation).await
+            }
+        };
+        middleware.after(entered, &request, result)
+    }
+
+    /// Returns the slot of `key`, after the key prefix of the client is prepended to it.
+    pub fn key_slot(&self, key: &[u8]) -> u16 {
+        match &self.key_prefix {
+            Some(key_prefix) => get_slot(&key_prefix.prefixed(key)),
+            None => get_slot(key),
         }
     }
 
@@ -781,7 +1114,7 @@ impl Client {
         {
             Ok(result) => {
                 if immediate_auth {
-                    self.send_immediate_auth(password).await
+                    self.send_immediate_auth(None, password).await
                 } else {
                     result
                 }
@@ -793,7 +1126,104 @@ impl Client {
         }
     }
 
-    async fn send_immediate_auth(&mut self, password: Option<String>) -> RedisResult<Value> {
+    /// Subscribes to the given channels or patterns on a live client. Requires RESP3.
+    /// The subscriptions are kept in the client's desired subscriptions state, so they are re-established after
+    /// reconnects and, in cluster mode, after slot migrations.
+    pub async fn subscribe(
+        &mut self,
+        kind: PubSubSubscriptionKind,
+        channels_or_patterns: Vec<PubSubChannelOrPattern>,
+    ) -> RedisResult<Value> {
+        match self.get_or_initialize_client().await? {
+            ClientWrapper::Standalone(client) => client.subscribe(kind, channels_or_patterns).await,
+            ClientWrapper::Cluster { mut client } => {
+                client.subscribe(kind, channels_or_patterns).await
+            }
+            ClientWrapper::Lazy(_) => unreachable!("Lazy client should have been initialized"),
+        }
+    }
+
+    /// Unsubscribes from the given channels or patterns on a live client, and removes them from the desired subscriptions state.
+    pub async fn unsubscribe(
+        &mut self,
+        kind: PubSubSubscriptionKind,
+        channels_or_patterns: Vec<PubSubChannelOrPattern>,
+    ) -> RedisResult<Value> {
+        match self.get_or_initialize_client().await? {
+            ClientWrapper::Standalone(client) => {
+                client.unsubscribe(kind, channels_or_patterns).await
+            }
+            ClientWrapper::Cluster { mut client } => {
+                client.unsubscribe(kind, channels_or_patterns).await
+            }
+            ClientWrapper::Lazy(_) => unreachable!("Lazy client should have been initialized"),
+        }
+    }
+
+    /// Returns the desired subscriptions and the currently established subscriptions, as an array of two maps.
+    /// Each map is keyed by the subscription kind, using the values of `PubSubChannelType`, and holds a set of the channels or patterns.
+    pub async fn get_subscriptions(&mut self) -> RedisResult<Value> {
+        let (desired, current) = match self.get_or_initialize_client().await? {
+            ClientWrapper::Standalone(client) => client.get_subscriptions(),
+            ClientWrapper::Cluster { mut client } => client.get_subscriptions().await?,
+            ClientWrapper::Lazy(_) => unreachable!("Lazy client should have been initialized"),
+        };
+        Ok(Value::Array(vec![
+            subscriptions_to_value(desired),
+            subscriptions_to_value(current),
+        ]))
+    }
+
+    /// Returns the hit and miss statistics of the client-side cache, or `None` if the cache isn't enabled.
+    pub fn client_side_cache_statistics(&self) -> Option<ClientSideCacheStatistics> {
+        self.client_side_cache
+            .as_ref()
+            .map(|cache| cache.statistics())
+    }
+
+    /// Update the TLS credentials used to connect to the servers, replacing the TLS configuration the client was created with.
+    /// The new credentials are used by every connection created from now on.
+    /// If `reconnect` is true, the existing connections are also replaced in the background. Each existing connection
+    /// keeps serving requests until its replacement is established, and is kept if the replacement fails.
+    /// Fails if the client doesn't use TLS.
+    pub async fn update_tls_credentials(
+        &mut self,
+        tls_config: TlsConfig,
+        reconnect: bool,
+    ) -> RedisResult<Value> {
+        let tls_params =
+            redis::retrieve_tls_certificates(tls_config_to_certificates(&tls_config)?)?;
+        let timeout = self.request_timeout;
+        // Like the password update, this operation doesn't go through the regular command pipeline, so it is wrapped in a timeout here.
+        match tokio::time::timeout(timeout, async {
+            let mut client = self.get_or_initialize_client().await?;
+            match client {
+                ClientWrapper::Standalone(ref mut client) => {
+                    client.update_tls_params(tls_params, reconnect).await
+                }
+                ClientWrapper::Cluster { ref mut client } => {
+                    client.update_tls_params(tls_params, reconnect).await
+                }
+                ClientWrapper::Lazy(_) => unreachable!("Lazy client should have been initialized"),
+            }
+        })
+        .await
+        {
+            Ok(result) => result,
+            Err(_elapsed) => Err(RedisError::from((
+                ErrorKind::IoError,
+                "TLS credentials update operation timed out, please check the connection",
+            ))),
+        }
+    }
+
+    /// Authenticates all connections with `password`, and with `username` if given, or else with the username
+    /// the client was configured with.
+    async fn send_immediate_auth(
+        &mut self,
+        username: Option<String>,
+        password: Option<String>,
+    ) -> RedisResult<Value> {
         match &password {
             Some(pw) if pw.is_empty() => Err(RedisError::from((
                 ErrorKind::UserOperationError,
@@ -808,12 +1238,16 @@ impl Client {
                     MultipleNodeRoutingInfo::AllNodes,
                     Some(ResponsePolicy::AllSucceeded),
                 ));
+                let username = match username {
+                    Some(username) => Some(username),
+                    None => self.get_username().await?,
+                };
                 let mut cmd = redis::cmd("AUTH");
-                if let Some(username) = self.get_username().await? {
+                if let Some(username) = username {
                     cmd.arg(username);
                 }
                 cmd.arg(password);
-                self.send_command(&cmd, Some(routing)).await
+                self.dispatch_command(&cmd, Some(routing), None).await
             }
         }
     }
@@ -872,12 +1306,26 @@ async fn create_cluster_client(
     push_sender: Option<mpsc::UnboundedSender<PushInfo>>,
 ) -> RedisResult<redis::cluster_async::ClusterConnection> {
     // TODO - implement timeout for each connection attempt
+    if let Some(address) = request
+        .addresses
+        .iter()
+        .find(|address| address.unix_socket_path.is_some())
+    {
+        return Err(RedisError::from((
+            ErrorKind::InvalidClientConfig,
+            "Unix socket addresses are not supported in cluster mode",
+            address.to_string(),
+        )));
+    }
     let tls_mode = request.tls_mode.unwrap_or_default();
     let redis_connection_info = get_redis_connection_info(&request);
     let initial_nodes: Vec<_> = request
         .addresses
         .into_iter()
-        .map(|address| get_connection_info(&address, tls_mode, redis_connection_info.clone()))
+        .map(|address| {
+            // The cluster applies the TLS certificates to all of its connections, including the initial nodes.
+            get_connection_info(&address, tls_mode, None, redis_connection_info.clone())
+        })
         .collect();
     let periodic_topology_checks = match request.periodic_checks {
         Some(PeriodicCheck::Disabled) => None,
@@ -896,6 +1344,7 @@ async fn create_cluster_client(
             ReadFromReplicaStrategy::AZAffinityReplicasAndPrimary(az)
         }
         ReadFrom::PreferReplica => ReadFromReplicaStrategy::RoundRobin,
+        ReadFrom::LowestLatency => ReadFromReplicaStrategy::LowestLatency,
         ReadFrom::Primary => ReadFromReplicaStrategy::AlwaysFromPrimary,
     });
     if let Some(interval_duration) = periodic_topology_checks {
@@ -905,6 +1354,10 @@ async fn create_cluster_client(
     if let Some(client_name) = redis_connection_info.client_name {
         builder = builder.client_name(client_name);
     }
+    // Must be set before the TLS mode, since it implies secure TLS.
+    if let Some(tls_certificates) = get_tls_certificates(tls_mode, request.tls_config.as_ref())? {
+        builder = builder.certs(tls_certificates);
+    }
     if tls_mode != TlsMode::NoTls {
         let tls = if tls_mode == TlsMode::SecureTls {
             redis::cluster::TlsMode::Secure
@@ -916,6 +1369,30 @@ async fn create_cluster_client(
     if let Some(pubsub_subscriptions) = redis_connection_info.pubsub_subscriptions.clone() {
         builder = builder.pubsub_subscriptions(pubsub_subscriptions);
     }
+    if let Some(client_side_cache) = request.client_side_cache.as_ref() {
+        builder = builder.client_tracking(client_side_cache.tracking_mode);
+    }
+    if let Some(provider) = request.credential_provider.clone() {
+        builder = builder.credentials_provider(credentials::for_connections(provider));
+    }
+    if let Some(connections_per_node) = request.connections_per_node {
+        builder = builder.connections_per_node(connections_per_node as usize);
+    }
+    if let Some(address_remap) = request.address_remap.clone() {
+        builder = builder.address_remap(address_remap);
+    }
+    if let Some(circuit_breaker_threshold) = request.circuit_breaker_threshold {
+        builder = builder.circuit_breaker(circuit_breaker_threshold);
+    }
+    if let Some(read_hedging) = request.read_hedging {
+        builder = builder.read_hedging(read_hedging);
+    }
+    if let Some(connection_error_retry) = request.connection_error_retry.clone() {
+        builder = builder.connection_error_retry(connection_error_retry);
+    }
+    if let Some(command_routing_table) = request.command_routing_table.clone() {
+        builder = builder.command_routing_table(command_routing_table);
+    }
 
     let retry_strategy = match request.connection_retry_strategy {
         Some(strategy) => RetryStrategy::new(
@@ -991,6 +1468,21 @@ pub enum ConnectionError {
     IoError(std::io::Error),
 }
 
+impl ConnectionError {
+    /// Wraps an error that occurred while preparing the client's configuration, before connecting.
+    fn from_setup_error(cluster_mode_enabled: bool, err: RedisError) -> Self {
+        if cluster_mode_enabled {
+            ConnectionError::Cluster(err)
+        } else {
+            ConnectionError::Standalone(
+                standalone_client::StandaloneClientConnectionError::FailedConnection(vec![(
+                    None, err,
+                )]),
+            )
+        }
+    }
+}
+
 impl std::fmt::Debug for ConnectionError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -1028,7 +1520,7 @@ fn sanitized_request_string(request: &ConnectionRequest) -> String {
     let addresses = request
         .addresses
         .iter()
-        .map(|address| format!("{}:{}", address.host, address.port))
+        .map(|address| address.address_string())
         .collect::<Vec<_>>()
         .join(", ");
     let tls_mode = request
@@ -1044,6 +1536,11 @@ fn sanitized_request_string(request: &ConnectionRequest) -> String {
             )
         })
         .unwrap_or_default();
+    let tls_config = request
+        .tls_config
+        .as_ref()
+        .map(|tls_config| format!("\nTLS configuration: {tls_config:?}"))
+        .unwrap_or_default();
     let cluster_mode = if request.cluster_mode_enabled {
         "\nCluster mode"
     } else {
@@ -1062,6 +1559,7 @@ fn sanitized_request_string(request: &ConnectionRequest) -> String {
                 match rfr {
                     ReadFrom::Primary => "Only primary",
                     ReadFrom::PreferReplica => "Prefer replica",
+                    ReadFrom::LowestLatency => "Prefer replica with the lowest latency",
                     ReadFrom::AZAffinity(_) => "Prefer replica in user's availability zone",
                     ReadFrom::AZAffinityReplicasAndPrimary(_) =>
                         "Prefer replica and primary in user's availability zone",
@@ -1108,12 +1606,213 @@ fn sanitized_request_string(request: &ConnectionRequest) -> String {
         request.inflight_requests_limit,
     );
 
+    let credential_provider = request
+        .credential_provider
+        .as_ref()
+        .map(|provider| format!("\nCredential provider: {provider:?}"))
+        .unwrap_or_default();
+
+    let client_side_cache = request
+        .client_side_cache
+        .as_ref()
+        .map(|cache| format!("\nClient-side cache: {cache:?}"))
+        .unwrap_or_default();
+
+    let connections_per_node =
+        format_optional_value("Connections per node", request.connections_per_node);
+
+    let address_remap = request
+        .address_remap
+        .as_ref()
+        .map(|address_remap| format!("\nAddress remap: {address_remap:?}"))
+        .unwrap_or_default();
+
+    let circuit_breaker_threshold = format_optional_value(
+        "Circuit breaker threshold",
+        request.circuit_breaker_threshold,
+    );
+
+    let read_hedging = request
+        .read_hedging
+        .as_ref()
+        .map(|read_hedging| format!("\nRead hedging: {read_hedging:?}"))
+        .unwrap_or_default();
+
+    let connection_error_retry = request
+        .connection_error_retry
+        .as_ref()
+        .map(|retry| format!("\nConnection error retry: {retry:?}"))
+        .unwrap_or_default();
+
+    let key_prefix = request
+        .key_prefix
+        .as_ref()
+        .map(|prefix| format!("\nKey prefix: {}", String::from_utf8_lossy(prefix)))
+        .unwrap_or_default();
+
+    let command_policy = request
+        .command_policy
+        .as_ref()
+        .map(|policy| format!("\nCommand policy: {policy:?}"))
+        .unwrap_or_default();
+
+    let middlewares = if request.middlewares.is_empty() {
+        String::new()
+    } else {
+        format!("\nMiddlewares: {:?}", request.middlewares)
+    };
+
+    let command_routing = request
+        .command_routing_table
+        .as_ref()
+        .map(|table| {
+            format!(
+                "\nCommand routing: discover routes: {}",
+                table.discovers_routes()
+            )
+        })
+        .unwrap_or_default();
+
+    let sentinel = request
+        .sentinel
+        .as_ref()
+        .map(|sentinel| {
+            format!(
+                "\nSentinel master name: {}, sentinel addresses: {}",
+                sentinel.master_name,
+                sentinel
+                    .addresses
+                    .iter()
+                    .map(|address| format!("{}:{}", address.host, address.port))
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            )
+        })
+        .unwrap_or_default();
+
     format!(
-        "\nAddresses: {addresses}{tls_mode}{cluster_mode}{request_timeout}{connection_timeout}{rfr_strategy}{connection_retry_strategy}{database_id}{protocol}{client_name}{periodic_checks}{pubsub_subscriptions}{inflight_requests_limit}",
+        "\nAddresses: {addresses}{tls_mode}{tls_config}{cluster_mode}{request_timeout}{connection_timeout}{rfr_strategy}{connection_retry_strategy}{database_id}{protocol}{client_name}{periodic_checks}{pubsub_subscriptions}{inflight_requests_limit}{sentinel}{credential_provider}{client_side_cache}{connections_per_node}{address_remap}{circuit_breaker_threshold}{read_hedging}{connection_error_retry}{command_routing}{key_prefix}{command_policy}{middlewares}",
     )
 }
 
+/// Saves the credentials in the request, so that the client connects with them.
+fn apply_credentials(request: &mut ConnectionRequest, credentials: Credentials) {
+    let authentication_info = request
+        .authentication_info
+        .get_or_insert_with(AuthenticationInfo::default);
+    if credentials.username.is_some() {
+        authentication_info.username = credentials.username;
+    }
+    authentication_info.password = Some(credentials.password);
+}
+
+/// The delay before retrying to refresh credentials after the provider failed to provide them.
+const CREDENTIALS_REFRESH_RETRY_DELAY: Duration = Duration::from_secs(1);
+
 impl Client {
+    /// Periodically fetches new credentials from `provider`, saves them for future connections, and re-authenticates
+    /// all connections with them. Stops once the client is dropped.
+    fn start_credentials_refresh_task(
+        internal_client: std::sync::Weak<RwLock<ClientWrapper>>,
+        request_timeout: Duration,
+        inflight_requests_allowed: Arc<AtomicIsize>,
+        client_side_cache: Option<Arc<ClientSideCache>>,
+        provider: Arc<dyn CredentialProvider>,
+        mut credentials: Credentials,
+    ) {
+        tokio::spawn(async move {
+            let mut delay = credentials.valid_for.map(credentials::refresh_delay);
+            while let Some(current_delay) = delay {
+                tokio::time::sleep(current_delay).await;
+                let Some(internal_client) = internal_client.upgrade() else {
+                    log_info("credentials refresh", "client was dropped, stopping");
+                    return;
+                };
+                let new_credentials = match provider.get_credentials().await {
+                    Ok(new_credentials) => new_credentials,
+                    Err(err) => {
+                        log_warn(
+                            "credentials refresh",
+                            format!("Failed to fetch credentials, retrying: {err}"),
+                        );
+                        delay = Some(CREDENTIALS_REFRESH_RETRY_DELAY.min(current_delay));
+                        continue;
+                    }
+                };
+                delay = new_credentials.valid_for.map(credentials::refresh_delay);
+                if new_credentials.username == credentials.username
+                    && new_credentials.password == credentials.password
+                {
+                    continue;
+                }
+
+                {
+                    // A lazy client that hasn't connected yet only needs the credentials it will connect with.
+                    let mut guard = internal_client.write().await;
+                    if let ClientWrapper::Lazy(lazy_client) = &mut *guard {
+                        apply_credentials(&mut lazy_client.config, new_credentials.clone());
+                        credentials = new_credentials;
+                        continue;
+                    }
+                }
+                let mut client = Client {
+                    internal_client,
+                    request_timeout,
+                    inflight_requests_allowed: inflight_requests_allowed.clone(),
+                    client_side_cache: client_side_cache.clone(),
+                    // Only used to re-authenticate, which doesn't route commands or pass through the middlewares.
+                    command_routing_table: None,
+                    key_prefix: None,
+                    middleware: MiddlewareChain::default(),
+                };
+                // The new password is saved before re-authenticating, and the username from the provider, if any,
+                // replaces the configured one in the AUTH command.
+                let password = Some(new_credentials.password.clone());
+                let result = match client
+                    .update_connection_password(password.clone(), false)
+                    .await
+                {
+                    Ok(_) => {
+                        client
+                            .send_immediate_auth(new_credentials.username.clone(), password)
+                            .await
+                    }
+                    Err(err) => Err(err),
+                };
+                match result {
+                    Ok(_) => log_info("credentials refresh", "re-authenticated all connections"),
+                    // Reconnections fetch their own credentials from the provider, so a failed re-authentication is only logged.
+                    Err(err) => log_warn(
+                        "credentials refresh",
+                        format!("Failed to re-authenticate with the new credentials: {err}"),
+                    ),
+                }
+                credentials = new_credentials;
+            }
+        });
+    }
+
+    /// Returns a push sender that passes the connections' push notifications to `cache`, and then forwards
+    /// the notifications that weren't consumed by the cache to `push_sender`.
+    fn start_client_side_cache_push_task(
+        cache: Arc<ClientSideCache>,
+        push_sender: Option<mpsc::UnboundedSender<PushInfo>>,
+    ) -> mpsc::UnboundedSender<PushInfo> {
+        let (cache_push_sender, mut cache_push_receiver) = mpsc::unbounded_channel::<PushInfo>();
+        // The task ends once all the connections, which hold the sender, are dropped.
+        tokio::spawn(async move {
+            while let Some(push) = cache_push_receiver.recv().await {
+                if cache.handle_push(&push) {
+                    continue;
+                }
+                if let Some(push_sender) = push_sender.as_ref() {
+                    let _ = push_sender.send(push);
+                }
+            }
+        });
+        cache_push_sender
+    }
+
     pub async fn new(
         request: ConnectionRequest,
         push_sender: Option<mpsc::UnboundedSender<PushInfo>>,
@@ -1133,6 +1832,67 @@ impl Client {
         ));
 
         tokio::time::timeout(DEFAULT_CLIENT_CREATION_TIMEOUT, async move {
+            let mut request = request;
+            // Sentinel manages standalone deployments, whose nodes it discovers in place of the addresses.
+            if request.sentinel.is_some() && request.cluster_mode_enabled {
+                return Err(ConnectionError::from_setup_error(
+                    request.cluster_mode_enabled,
+                    RedisError::from((
+                        ErrorKind::InvalidClientConfig,
+                        "Sentinel is only supported in standalone mode",
+                    )),
+                ));
+            }
+            let credentials = match request.credential_provider.clone() {
+                Some(provider) => {
+                    let credentials = provider.get_credentials().await.map_err(|err| {
+                        ConnectionError::from_setup_error(request.cluster_mode_enabled, err)
+                    })?;
+                    apply_credentials(&mut request, credentials.clone());
+                    Some((provider, credentials))
+                }
+                None => None,
+            };
+
+            let (client_side_cache, push_sender) = match request.client_side_cache.clone() {
+                Some(config) => {
+                    // With the auto protocol, the protocol is checked once it's negotiated: the handshake of a
+                    // connection that fell back to RESP2 fails, since it can't enable client tracking.
+                    if request.protocol == Some(redis::ProtocolVersion::RESP2) {
+                        return Err(ConnectionError::from_setup_error(
+                            request.cluster_mode_enabled,
+                            RedisError::from((
+                                ErrorKind::InvalidClientConfig,
+                                "Client-side caching requires the RESP3 protocol",
+                            )),
+                        ));
+                    }
+                    let cache = Arc::new(ClientSideCache::new(config, request.database_id));
+                    let push_sender =
+                        Self::start_client_side_cache_push_task(cache.clone(), push_sender);
+                    (Some(cache), Some(push_sender))
+                }
+                None => (None, push_sender),
+            };
+
+            let command_routing_table = request
+                .command_routing_table
+                .clone()
+                .filter(|_| request.cluster_mode_enabled);
+            let key_prefix = request
+                .key_prefix
+                .clone()
+                .map(|prefix| Arc::new(KeyPrefix::new(prefix)));
+            // The command policy runs after the other middlewares, so they can't bypass it,
+            // and the key prefix runs last, so the other middlewares see the keys without the prefix.
+            let mut middlewares = request.middlewares.clone();
+            if let Some(command_policy) = &request.command_policy {
+                middlewares.push(Arc::new(command_policy.clone()));
+            }
+            if let Some(key_prefix) = &key_prefix {
+                middlewares.push(key_prefix.clone());
+            }
+            let middleware = MiddlewareChain::new(middlewares);
             let internal_client = if request.lazy_connect {
                 ClientWrapper::Lazy(Box::new(LazyClient {
                     config: request,
@@ -1151,10 +1911,26 @@ impl Client {
                 )
             };
 
+            let internal_client = Arc::new(RwLock::new(internal_client));
+            if let Some((provider, credentials)) = credentials {
+                Self::start_credentials_refresh_task(
+                    Arc::downgrade(&internal_client),
+                    request_timeout,
+                    inflight_requests_allowed.clone(),
+                    client_side_cache.clone(),
+                    provider,
+                    credentials,
+                );
+            }
+
             Ok(Self {
-                internal_client: Arc::new(RwLock::new(internal_client)),
+                internal_client,
                 request_timeout,
                 inflight_requests_allowed,
+                client_side_cache,
+                command_routing_table,
+                key_prefix,
+                middleware,
             })
         })
         .await
@@ -1197,7 +1973,8 @@ mod tests {
     use redis::Cmd;
 
     use crate::client::{
-        BLOCKING_CMD_TIMEOUT_EXTENSION, RequestTimeoutOption, TimeUnit, get_request_timeout,
+        BLOCKING_CMD_TIMEOUT_EXTENSION, NodeAddress, RequestTimeoutOption, TimeUnit, TlsConfig,
+        TlsMode, get_connection_info, get_request_timeout, get_tls_certificates,
     };
 
     use super::get_timeout_from_cmd_arg;
@@ -1349,4 +2126,84 @@ mod tests {
         assert!(result.is_ok());
         assert_eq!(result.unwrap(), Some(Duration::from_millis(100)));
     }
+
+    #[test]
+    fn test_get_tls_certificates_requires_tls_mode() {
+        let tls_config = TlsConfig {
+            root_certs: Some(b"root".to_vec()),
+            ..Default::default()
+        };
+        assert!(get_tls_certificates(TlsMode::NoTls, Some(&tls_config)).is_err());
+
+        let certificates = get_tls_certificates(TlsMode::SecureTls, Some(&tls_config))
+            .unwrap()
+            .unwrap();
+        assert_eq!(certificates.root_cert, tls_config.root_certs);
+        assert!(certificates.client_tls.is_none());
+
+        assert!(
+            get_tls_certificates(TlsMode::NoTls, None)
+                .unwrap()
+                .is_none()
+        );
+    }
+
+    #[test]
+    fn test_get_tls_certificates_requires_client_cert_and_key_together() {
+        let tls_config = TlsConfig {
+            client_cert: Some(b"cert".to_vec()),
+            ..Default::default()
+        };
+        assert!(get_tls_certificates(TlsMode::SecureTls, Some(&tls_config)).is_err());
+
+        let tls_config = TlsConfig {
+            client_cert: Some(b"cert".to_vec()),
+            client_key: Some(b"key".to_vec()),
+            server_name: Some("valkey.internal".to_string()),
+            ..Default::default()
+        };
+        let certificates = get_tls_certificates(TlsMode::SecureTls, Some(&tls_config))
+            .unwrap()
+            .unwrap();
+        let client_tls = certificates.client_tls.unwrap();
+        assert_eq!(client_tls.client_cert, b"cert");
+        assert_eq!(client_tls.client_key, b"key");
+        assert_eq!(certificates.server_name.as_deref(), Some("valkey.internal"));
+    }
+
+    #[test]
+    fn test_get_connection_info_uses_unix_socket_path() {
+        let address = NodeAddress {
+            host: "localhost".to_string(),
+            port: 6379,
+            unix_socket_path: Some("/tmp/valkey.sock".into()),
+        };
+        let connection_info = get_connection_info(
+            &address,
+            TlsMode::NoTls,
+            None,
+            redis::RedisConnectionInfo::default(),
+        );
+        assert_eq!(
+            connection_info.addr,
+            redis::ConnectionAddr::Unix("/tmp/valkey.sock".into())
+        );
+        assert_eq!(address.address_string(), "/tmp/valkey.sock");
+
+        let address = NodeAddress {
+            unix_socket_path: None,
+            ..address
+        };
+        let connection_info = get_connection_info(
+            &address,
+            TlsMode::NoTls,
+            None,
+            redis::RedisConnectionInfo::default(),
+        );
+        assert_eq!(
+            connection_info.addr,
+            redis::ConnectionAddr::Tcp("localhost".to_string(), 6379)
+        );
+        assert_eq!(address.address_string(), "localhost:6379");
+    }
 }
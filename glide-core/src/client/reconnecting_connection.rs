@@ -4,9 +4,13 @@ use super::{NodeAddress, TlsMode};
 use async_trait::async_trait;
 use futures_intrusive::sync::ManualResetEvent;
 use logger_core::{log_debug, log_error, log_trace, log_warn};
-use redis::aio::{DisconnectNotifier, MultiplexedConnection};
+use redis::aio::{
+    ConnectionCredentialsProvider, ConnectionLike, DisconnectNotifier, MultiplexedConnection,
+};
 use redis::{
-    GlideConnectionOptions, PushInfo, RedisConnectionInfo, RedisError, RedisResult, RetryStrategy,
+    CircuitBreaker, CircuitBreakerAttempt, ClientTrackingMode, GlideConnectionOptions,
+    LatencyTracker, PubSubChannelOrPattern, PubSubSubscriptionInfo, PubSubSubscriptionKind,
+    PushInfo, RedisConnectionInfo, RedisError, RedisResult, RetryStrategy, TlsConnParams,
 };
 use std::fmt;
 use std::sync::Arc;
@@ -57,6 +61,12 @@ enum ConnectionState {
 struct InnerReconnectingConnection {
     state: Mutex<ConnectionState>,
     backend: ConnectionBackend,
+    /// Round-trip time moving average of the node, used by the `LowestLatency` read strategy.
+    latency: LatencyTracker,
+    /// Fails requests fast while the node is unresponsive. Shared by all the connections to the node.
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
+    /// The pubsub subscriptions that the server confirmed on the current connection.
+    confirmed_subscriptions: Mutex<PubSubSubscriptionInfo>,
 }
 
 #[derive(Clone)]
@@ -117,12 +127,18 @@ impl TokioDisconnectNotifier {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn create_connection(
     connection_backend: ConnectionBackend,
     retry_strategy: RetryStrategy,
     push_sender: Option<mpsc::UnboundedSender<PushInfo>>,
     discover_az: bool,
     connection_timeout: Duration,
+    client_tracking: Option<ClientTrackingMode>,
+    circuit_breaker: Option<Arc<CircuitBreaker>>,
+    credentials_provider: Option<Arc<dyn ConnectionCredentialsProvider>>,
+    // If false, a failed first attempt isn't retried before returning, and the connection is only retried in the background.
+    retry_first_attempt: bool,
 ) -> Result<ReconnectingConnection, (ReconnectingConnection, RedisError)> {
     let client = {
         let guard = connection_backend
@@ -140,6 +156,8 @@ async fn create_connection(
         discover_az,
         connection_timeout: Some(connection_timeout),
         connection_retry_strategy: Some(retry_strategy),
+        client_tracking,
+        credentials_provider,
     };
 
     let action = || async {
@@ -148,7 +166,15 @@ async fn create_connection(
             .map_err(RetryError::transient)
     };
 
-    match Retry::spawn(retry_strategy.get_bounded_backoff_dur_iterator(), action).await {
+    let retries = if retry_first_attempt { usize::MAX } else { 0 };
+    match Retry::spawn(
+        retry_strategy
+            .get_bounded_backoff_dur_iterator()
+            .take(retries),
+        action,
+    )
+    .await
+    {
         Ok(connection) => {
             log_debug(
                 "connection creation",
@@ -164,7 +190,10 @@ async fn create_connection(
             Ok(ReconnectingConnection {
                 inner: Arc::new(InnerReconnectingConnection {
                     state: Mutex::new(ConnectionState::Connected(connection)),
+                    confirmed_subscriptions: Mutex::new(pubsub_subscriptions(&client)),
                     backend: connection_backend,
+                    latency: LatencyTracker::default(),
+                    circuit_breaker,
                 }),
                 connection_options,
             })
@@ -184,6 +213,9 @@ async fn create_connection(
                 inner: Arc::new(InnerReconnectingConnection {
                     state: Mutex::new(ConnectionState::InitializedDisconnected),
                     backend: connection_backend,
+                    latency: LatencyTracker::default(),
+                    circuit_breaker,
+                    confirmed_subscriptions: Mutex::new(PubSubSubscriptionInfo::new()),
                 }),
                 connection_options,
             };
@@ -193,14 +225,27 @@ async fn create_connection(
     }
 }
 
+// Returns the pubsub subscriptions that a connection created by `client` is set up with.
+// The subscriptions are confirmed by the server while the connection is set up, and the connection fails otherwise.
+fn pubsub_subscriptions(client: &redis::Client) -> PubSubSubscriptionInfo {
+    client
+        .get_connection_info()
+        .redis
+        .pubsub_subscriptions
+        .clone()
+        .unwrap_or_default()
+}
+
 fn get_client(
     address: &NodeAddress,
     tls_mode: TlsMode,
+    tls_params: Option<TlsConnParams>,
     redis_connection_info: redis::RedisConnectionInfo,
 ) -> redis::Client {
     redis::Client::open(super::get_connection_info(
         address,
         tls_mode,
+        tls_params,
         redis_connection_info,
     ))
     .unwrap() // can unwrap, because [open] fails only on trying to convert input to ConnectionInfo, and we pass ConnectionInfo.
@@ -214,21 +259,29 @@ impl ConnectionBackend {
 }
 
 impl ReconnectingConnection {
+    #[allow(clippy::too_many_arguments)]
     pub(super) async fn new(
         address: &NodeAddress,
         connection_retry_strategy: RetryStrategy,
         redis_connection_info: RedisConnectionInfo,
         tls_mode: TlsMode,
+        tls_params: Option<TlsConnParams>,
         push_sender: Option<mpsc::UnboundedSender<PushInfo>>,
         discover_az: bool,
         connection_timeout: Duration,
+        client_tracking: Option<ClientTrackingMode>,
+        circuit_breaker_threshold: Option<u32>,
+        credentials_provider: Option<Arc<dyn ConnectionCredentialsProvider>>,
     ) -> Result<ReconnectingConnection, (ReconnectingConnection, RedisError)> {
         log_debug(
             "connection creation",
             format!("Attempting connection to {address}"),
         );
 
-        let connection_info = get_client(address, tls_mode, redis_connection_info);
+        let connection_info = get_client(address, tls_mode, tls_params, redis_connection_info);
+        let circuit_breaker = circuit_breaker_threshold.map(|failure_threshold| {
+            Arc::new(CircuitBreaker::new(address.to_string(), failure_threshold))
+        });
         let backend = ConnectionBackend {
             connection_info: RwLock::new(connection_info),
             connection_available_signal: ManualResetEvent::new(true),
@@ -240,6 +293,10 @@ impl ReconnectingConnection {
             push_sender,
             discover_az,
             connection_timeout,
+            client_tracking,
+            circuit_breaker,
+            credentials_provider,
+            true,
         )
         .await
     }
@@ -288,6 +345,57 @@ impl ReconnectingConnection {
         }
     }
 
+    /// Returns the number of requests that are waiting for a response over the connection.
+    /// A connection that isn't connected is considered fully loaded.
+    pub(super) fn pending_requests(&self) -> usize {
+        match &*self.inner.state.lock().unwrap() {
+            ConnectionState::Connected(connection) => connection.pending_requests(),
+            _ => usize::MAX,
+        }
+    }
+
+    /// Creates an additional connection to the same node, with the same configuration.
+    /// The new connection doesn't resubscribe to the pubsub subscriptions, since those are kept by this connection.
+    /// If the connection fails, it is still returned, and keeps reconnecting in the background. The first attempt is
+    /// retried before returning only if `retry_first_attempt` is true.
+    pub(super) async fn new_pool_connection(
+        &self,
+        retry_first_attempt: bool,
+    ) -> ReconnectingConnection {
+        let mut connection_info = self
+            .inner
+            .backend
+            .get_backend_client()
+            .get_connection_info()
+            .clone();
+        connection_info.redis.pubsub_subscriptions = None;
+        let backend = ConnectionBackend {
+            connection_info: RwLock::new(redis::Client::open(connection_info).unwrap()), // can unwrap, because [open] fails only on trying to convert input to ConnectionInfo, and we pass ConnectionInfo.
+            connection_available_signal: ManualResetEvent::new(true),
+            client_dropped_flagged: AtomicBool::new(false),
+        };
+        let connection_options = &self.connection_options;
+        match create_connection(
+            backend,
+            connection_options
+                .connection_retry_strategy
+                .unwrap_or_default(),
+            connection_options.push_sender.clone(),
+            false,
+            connection_options
+                .connection_timeout
+                .unwrap_or(DEFAULT_CONNECTION_TIMEOUT),
+            connection_options.client_tracking,
+            self.inner.circuit_breaker.clone(),
+            connection_options.credentials_provider.clone(),
+            retry_first_attempt,
+        )
+        .await
+        {
+            Ok(connection) | Err((connection, _)) => connection,
+        }
+    }
+
     /// Attempt to re-connect the connection.
     ///
     /// This function spawns a task to perform the reconnection in the background
@@ -301,6 +409,7 @@ impl ReconnectingConnection {
             }
             self.inner.backend.connection_available_signal.reset();
             *guard = ConnectionState::Reconnecting;
+            self.inner.confirmed_subscriptions.lock().unwrap().clear();
         };
         log_debug("reconnect", "starting");
 
@@ -315,11 +424,6 @@ impl ReconnectingConnection {
         // The reconnect task is spawned instead of awaited here, so that the reconnect attempt will continue in the
         // background, regardless of whether the calling task is dropped or not.
         task::spawn(async move {
-            let client = {
-                let guard = connection_clone.inner.backend.get_backend_client();
-                guard.clone()
-            };
-
             let infinite_backoff_dur_iterator = connection_clone
                 .connection_options
                 .connection_retry_strategy
@@ -334,6 +438,11 @@ impl ReconnectingConnection {
                     // Client was dropped, reconnection attempts can stop
                     return;
                 }
+                // The client is read on every attempt, since the connection might have been pointed to a new address meanwhile.
+                let client = {
+                    let guard = connection_clone.inner.backend.get_backend_client();
+                    guard.clone()
+                };
                 match get_multiplexed_connection(&client, &connection_clone.connection_options)
                     .await
                 {
@@ -355,8 +464,14 @@ impl ReconnectingConnection {
                                 .connection_available_signal
                                 .set();
                             *guard = ConnectionState::Connected(connection);
+                            *connection_clone
+                                .inner
+                                .confirmed_subscriptions
+                                .lock()
+                                .unwrap() = pubsub_subscriptions(&client);
                         }
                         Telemetry::incr_total_connections(1);
+                        Telemetry::record_node_reconnect(&connection_clone.node_address());
                         return;
                     }
                     Err(_) => tokio::time::sleep(sleep_duration).await,
@@ -383,6 +498,114 @@ impl ReconnectingConnection {
         }
     }
 
+    /// Adds a round-trip time sample to the node's latency moving average.
+    pub(super) fn record_latency(&self, round_trip_time: Duration) {
+        self.inner.latency.record(round_trip_time);
+    }
+
+    /// Returns the node's latency moving average, or `None` if it wasn't measured yet.
+    pub(super) fn average_latency(&self) -> Option<Duration> {
+        self.inner.latency.average()
+    }
+
+    /// Starts a request to the node, which is tracked by the node's circuit breaker if breakers are enabled.
+    /// Fails with `CircuitBreakerOpen` if requests to the node should fail fast.
+    pub(super) fn circuit_breaker_attempt(&self) -> RedisResult<Option<CircuitBreakerAttempt>> {
+        self.inner
+            .circuit_breaker
+            .as_ref()
+            .map(|circuit_breaker| circuit_breaker.try_acquire())
+            .transpose()
+    }
+
+    /// Moves an open circuit breaker to half-open, so the next request tests whether the node recovered.
+    pub(super) fn half_open_circuit_breaker(&self) {
+        if let Some(circuit_breaker) = &self.inner.circuit_breaker {
+            circuit_breaker.half_open();
+        }
+    }
+
+    /// Points the connection to a new address, and reconnects to it in the background.
+    /// Used when a node is replaced, e.g. when Sentinel promotes a new primary.
+    pub(super) fn update_address(&self, address: &NodeAddress) {
+        {
+            let mut client = self
+                .inner
+                .backend
+                .connection_info
+                .write()
+                .expect(WRITE_LOCK_ERR);
+            let mut connection_info = client.get_connection_info().clone();
+            connection_info.addr = match (&address.unix_socket_path, connection_info.addr) {
+                (Some(path), _) => redis::ConnectionAddr::Unix(path.clone()),
+                (
+                    None,
+                    redis::ConnectionAddr::TcpTls {
+                        insecure,
+                        tls_params,
+                        ..
+                    },
+                ) => redis::ConnectionAddr::TcpTls {
+                    host: address.host.clone(),
+                    port: address.port,
+                    insecure,
+                    tls_params,
+                },
+                (None, _) => redis::ConnectionAddr::Tcp(address.host.clone(), address.port),
+            };
+            *client = redis::Client::open(connection_info).unwrap(); // can unwrap, because [open] fails only on trying to convert input to ConnectionInfo, and we pass ConnectionInfo.
+        }
+        log_debug(
+            "connection redirect",
+            format!("Connection redirected to {address}"),
+        );
+        self.reconnect(ReconnectReason::ConnectionDropped);
+    }
+
+    /// Updates the TLS parameters that are saved inside connection_info, that will be used for every new connection to the server.
+    /// Fails if the connection doesn't use TLS.
+    pub(super) fn update_tls_params(&self, tls_params: TlsConnParams) -> RedisResult<()> {
+        let mut client = self
+            .inner
+            .backend
+            .connection_info
+            .write()
+            .expect(WRITE_LOCK_ERR);
+        let mut connection_info = client.get_connection_info().clone();
+        match &mut connection_info.addr {
+            redis::ConnectionAddr::TcpTls {
+                tls_params: current_tls_params,
+                ..
+            } => *current_tls_params = Some(tls_params),
+            _ => {
+                return Err(RedisError::from((
+                    redis::ErrorKind::InvalidClientConfig,
+                    "Cannot update TLS parameters of a connection that doesn't use TLS",
+                )));
+            }
+        }
+        *client = redis::Client::open(connection_info).unwrap(); // can unwrap, because [open] fails only on trying to convert input to ConnectionInfo, and we pass ConnectionInfo.
+        Ok(())
+    }
+
+    /// Replaces the current connection with a new one, created with the current connection_info.
+    /// The current connection keeps serving requests until the new connection is established, and is kept if the new connection fails.
+    pub(super) async fn replace_connection(&self) -> RedisResult<()> {
+        let client = {
+            let guard = self.inner.backend.get_backend_client();
+            guard.clone()
+        };
+        let connection = get_multiplexed_connection(&client, &self.connection_options).await?;
+        let mut guard = self.inner.state.lock().unwrap();
+        // If the connection was dropped meanwhile, the reconnect task already creates a new connection with the current connection_info.
+        if matches!(*guard, ConnectionState::Connected(_)) {
+            *guard = ConnectionState::Connected(connection);
+            *self.inner.confirmed_subscriptions.lock().unwrap() = pubsub_subscriptions(&client);
+            log_debug("replace connection", "completed successfully");
+        }
+        Ok(())
+    }
+
     /// Updates the password that's saved inside connection_info, that will be used in case of disconnection from the server.
     pub(crate) fn update_connection_password(&self, new_password: Option<String>) {
         let mut client = self
@@ -394,6 +617,74 @@ impl ReconnectingConnection {
         client.update_password(new_password);
     }
 
+    /// Updates the pubsub subscriptions that are saved inside connection_info, that will be re-established in case of disconnection from the server.
+    pub(super) fn update_pubsub_subscriptions(
+        &self,
+        update: impl FnOnce(&mut PubSubSubscriptionInfo),
+    ) {
+        let mut client = self
+            .inner
+            .backend
+            .connection_info
+            .write()
+            .expect(WRITE_LOCK_ERR);
+        let mut connection_info = client.get_connection_info().clone();
+        update(
+            connection_info
+                .redis
+                .pubsub_subscriptions
+                .get_or_insert_with(PubSubSubscriptionInfo::new),
+        );
+        *client = redis::Client::open(connection_info).unwrap(); // can unwrap, because [open] fails only on trying to convert input to ConnectionInfo, and we pass ConnectionInfo.
+    }
+
+    /// Returns the protocol that the connection uses.
+    /// If the protocol is negotiated with the server, the negotiated protocol is returned once the connection is established.
+    pub(super) fn protocol(&self) -> redis::ProtocolVersion {
+        if let ConnectionState::Connected(connection) = &*self.inner.state.lock().unwrap() {
+            return connection.get_protocol();
+        }
+        let client = self.inner.backend.get_backend_client();
+        client.get_connection_info().redis.protocol
+    }
+
+    /// Records a subscription or unsubscription that the server confirmed on the current connection.
+    pub(super) fn confirm_subscription(
+        &self,
+        kind: PubSubSubscriptionKind,
+        channel_pattern: PubSubChannelOrPattern,
+        subscribed: bool,
+    ) {
+        let mut confirmed_subscriptions = self.inner.confirmed_subscriptions.lock().unwrap();
+        if subscribed {
+            confirmed_subscriptions
+                .entry(kind)
+                .or_default()
+                .insert(channel_pattern);
+        } else if let Some(channels_patterns) = confirmed_subscriptions.get_mut(&kind) {
+            channels_patterns.remove(&channel_pattern);
+            if channels_patterns.is_empty() {
+                confirmed_subscriptions.remove(&kind);
+            }
+        }
+    }
+
+    /// Returns the pubsub subscriptions that the server confirmed on the current connection.
+    pub(super) fn confirmed_subscriptions(&self) -> PubSubSubscriptionInfo {
+        self.inner.confirmed_subscriptions.lock().unwrap().clone()
+    }
+
+    /// Returns the pubsub subscriptions that are saved inside connection_info.
+    pub(super) fn get_pubsub_subscriptions(&self) -> PubSubSubscriptionInfo {
+        let client = self.inner.backend.get_backend_client();
+        client
+            .get_connection_info()
+            .redis
+            .pubsub_subscriptions
+            .clone()
+            .unwrap_or_default()
+    }
+
     /// Returns the username if one was configured during client creation. Otherwise, returns None.
     pub(crate) fn get_username(&self) -> Option<String> {
         let client = self.inner.backend.get_backend_client();
@@ -0,0 +1,404 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+use super::{NodeAddress, SentinelConfig, TlsMode, get_connection_info};
+use futures::{Stream, StreamExt, future};
+use redis::sentinel::{Sentinel, SentinelNodeConnectionInfo};
+use redis::{
+    ConnectionAddr, ConnectionInfo, ErrorKind, RedisConnectionInfo, RedisError, RedisResult,
+    TlsConnParams,
+};
+use std::io;
+use std::pin::Pin;
+use std::time::Duration;
+
+/// The port sentinels listen on when an address doesn't specify one.
+const DEFAULT_SENTINEL_PORT: u16 = 26379;
+
+/// The channel on which sentinels announce the new primary after a failover.
+const SWITCH_MASTER_CHANNEL: &str = "+switch-master";
+
+/// A stream of the new primary addresses announced by a sentinel.
+pub(super) type FailoverStream = Pin<Box<dyn Stream<Item = NodeAddress> + Send>>;
+
+/// Discovers the nodes of a Sentinel-managed deployment, and watches it for failovers.
+pub(super) struct SentinelMonitor {
+    sentinel: Sentinel,
+    sentinels_connection_info: Vec<ConnectionInfo>,
+    master_name: String,
+    node_connection_info: SentinelNodeConnectionInfo,
+}
+
+impl std::fmt::Debug for SentinelMonitor {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("SentinelMonitor")
+            .field("master_name", &self.master_name)
+            .finish_non_exhaustive()
+    }
+}
+
+impl SentinelMonitor {
+    pub(super) fn new(
+        config: &SentinelConfig,
+        tls_mode: TlsMode,
+        tls_params: Option<TlsConnParams>,
+        redis_connection_info: &RedisConnectionInfo,
+    ) -> RedisResult<Self> {
+        let sentinel_redis_connection_info = RedisConnectionInfo {
+            username: config
+                .authentication_info
+                .as_ref()
+                .and_then(|info| info.username.clone()),
+            password: config
+                .authentication_info
+                .as_ref()
+                .and_then(|info| info.password.clone()),
+            ..Default::default()
+        };
+        let sentinels_connection_info: Vec<_> = config
+            .addresses
+            .iter()
+            .map(|address| {
+                let address = NodeAddress {
+                    host: address.host.clone(),
+                    port: if address.port == 0 {
+                        DEFAULT_SENTINEL_PORT
+                    } else {
+                        address.port
+                    },
+                    unix_socket_path: None,
+                };
+                get_connection_info(
+                    &address,
+                    tls_mode,
+                    tls_params.clone(),
+                    sentinel_redis_connection_info.clone(),
+                )
+            })
+            .collect();
+        let sentinel = Sentinel::build(sentinels_connection_info.clone())?;
+
+        // The sentinel checks the role of the nodes it reports, so it must be able to authenticate with them.
+        // Subscriptions are left to the client's own connections.
+        let node_connection_info = SentinelNodeConnectionInfo {
+            tls_mode: match tls_mode {
+                TlsMode::NoTls => None,
+                TlsMode::SecureTls => Some(redis::TlsMode::Secure),
+                TlsMode::InsecureTls => Some(redis::TlsMode::Insecure),
+            },
+            tls_params,
+            redis_connection_info: Some(RedisConnectionInfo {
+                pubsub_subscriptions: None,
+                ..redis_connection_info.clone()
+            }),
+        };
+
+        Ok(Self {
+            sentinel,
+            sentinels_connection_info,
+            master_name: config.master_name.clone(),
+            node_connection_info,
+        })
+    }
+
+    /// Replaces the TLS parameters used to connect to the sentinels, and to the nodes they report.
+    /// Connections to the sentinels that are created from now on use the new parameters.
+    pub(super) fn update_tls_params(&mut self, tls_params: TlsConnParams) -> RedisResult<()> {
+        for connection_info in self.sentinels_connection_info.iter_mut() {
+            if let ConnectionAddr::TcpTls {
+                tls_params: current_tls_params,
+                ..
+            } = &mut connection_info.addr
+            {
+                *current_tls_params = Some(tls_params.clone());
+            }
+        }
+        self.sentinel = Sentinel::build(self.sentinels_connection_info.clone())?;
+        self.node_connection_info.tls_params = Some(tls_params);
+        Ok(())
+    }
+
+    /// Returns the address of the current primary, followed by the addresses of its replicas.
+    pub(super) async fn discover_nodes(&mut self) -> RedisResult<Vec<NodeAddress>> {
+        let primary = self.discover_primary().await?;
+        let replicas = self.discover_replicas().await?;
+        Ok(std::iter::once(primary).chain(replicas).collect())
+    }
+
+    /// Returns the addresses of the current primary's replicas.
+    pub(super) async fn discover_replicas(&mut self) -> RedisResult<Vec<NodeAddress>> {
+        let replicas = self
+            .sentinel
+            .async_replica_addresses_for(&self.master_name, Some(&self.node_connection_info))
+            .await?;
+        Ok(replicas.iter().filter_map(to_node_address).collect())
+    }
+
+    /// Returns the address of the current primary.
+    pub(super) async fn discover_primary(&mut self) -> RedisResult<NodeAddress> {
+        let client = self
+            .sentinel
+            .async_master_for(&self.master_name, Some(&self.node_connection_info))
+            .await?;
+        to_node_address(client.get_connection_info()).ok_or_else(|| {
+            RedisError::from((
+                ErrorKind::ClientError,
+                "Sentinel returned an unsupported primary address",
+            ))
+        })
+    }
+
+    /// Subscribes to failover announcements on the first reachable sentinel.
+    /// The returned stream yields the new primary's address after each failover of the monitored primary,
+    /// and ends once the connection to the sentinel is lost.
+    pub(super) async fn subscribe_to_failovers(
+        &self,
+        connection_timeout: Duration,
+    ) -> RedisResult<FailoverStream> {
+        let mut last_err = None;
+        for connection_info in self.sentinels_connection_info.iter() {
+            match subscribe_to_switch_master(connection_info, connection_timeout).await {
+                Ok(pubsub) => {
+                    let master_name = self.master_name.clone();
+                    return Ok(pubsub
+                        .into_on_message()
+                        .filter_map(move |msg| {
+                            future::ready(msg.get_payload::<String>().ok().and_then(|payload| {
+                                parse_switch_master_payload(&payload, &master_name)
+                            }))
+                        })
+                        .boxed());
+                }
+                Err(err) => last_err = Some(err),
+            }
+        }
+
+        // We can unwrap here because the sentinel can only be built with at least one address.
+        Err(last_err.expect("There should be at least one sentinel address"))
+    }
+}
+
+async fn subscribe_to_switch_master(
+    connection_info: &ConnectionInfo,
+    connection_timeout: Duration,
+) -> RedisResult<redis::aio::PubSub> {
+    let client = redis::Client::open(connection_info.clone())?;
+    let mut pubsub = tokio::time::timeout(connection_timeout, client.get_async_pubsub())
+        .await
+        .map_err(|_| RedisError::from(io::Error::from(io::ErrorKind::TimedOut)))??;
+    pubsub.subscribe(SWITCH_MASTER_CHANNEL).await?;
+    Ok(pubsub)
+}
+
+fn to_node_address(connection_info: &ConnectionInfo) -> Option<NodeAddress> {
+    match &connection_info.addr {
+        ConnectionAddr::Tcp(host, port) | ConnectionAddr::TcpTls { host, port, .. } => {
+            Some(NodeAddress {
+                host: host.clone(),
+                port: *port,
+                unix_socket_path: None,
+            })
+        }
+        ConnectionAddr::Unix(_) => None,
+    }
+}
+
+/// Pairs the client's replica connections whose nodes the sentinels no longer report with the reported replicas that the
+/// client isn't connected to, so the connections can be redirected to them. `replicas` holds the index and address of
+/// each of the client's replica connections. Returns the index of each connection to redirect, with its new address.
+pub(super) fn replica_redirects(
+    replicas: &[(usize, String)],
+    reported_replicas: &[NodeAddress],
+) -> Vec<(usize, NodeAddress)> {
+    let reported_addresses: Vec<_> = reported_replicas
+        .iter()
+        .map(NodeAddress::address_string)
+        .collect();
+    let new_replicas = reported_replicas
+        .iter()
+        .zip(&reported_addresses)
+        .filter(|(_, reported_address)| {
+            !replicas
+                .iter()
+                .any(|(_, address)| address == *reported_address)
+        })
+        .map(|(replica, _)| replica.clone());
+    replicas
+        .iter()
+        .filter(|(_, address)| !reported_addresses.contains(address))
+        .map(|(index, _)| *index)
+        .zip(new_replicas)
+        .collect()
+}
+
+/// Parses a `+switch-master` payload, which has the format `<master name> <old ip> <old port> <new ip> <new port>`.
+/// Returns the new primary's address if the payload refers to `master_name`.
+fn parse_switch_master_payload(payload: &str, master_name: &str) -> Option<NodeAddress> {
+    match payload.split_whitespace().collect::<Vec<_>>().as_slice() {
+        [name, _old_host, _old_port, host, port] if *name == master_name => Some(NodeAddress {
+            host: host.to_string(),
+            port: port.parse().ok()?,
+            unix_socket_path: None,
+        }),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::client::AuthenticationInfo;
+
+    fn sentinel_config() -> SentinelConfig {
+        SentinelConfig {
+            addresses: vec![
+                NodeAddress {
+                    host: "sentinel1".to_string(),
+                    port: 0,
+                    unix_socket_path: None,
+                },
+                NodeAddress {
+                    host: "sentinel2".to_string(),
+                    port: 26380,
+                    unix_socket_path: None,
+                },
+            ],
+            master_name: "mymaster".to_string(),
+            authentication_info: Some(AuthenticationInfo {
+                username: None,
+                password: Some("sentinel-password".to_string()),
+            }),
+        }
+    }
+
+    #[test]
+    fn test_sentinel_monitor_connects_to_the_sentinels_with_their_own_credentials() {
+        let redis_connection_info = RedisConnectionInfo {
+            password: Some("node-password".to_string()),
+            ..Default::default()
+        };
+        let monitor = SentinelMonitor::new(
+            &sentinel_config(),
+            TlsMode::NoTls,
+            None,
+            &redis_connection_info,
+        )
+        .unwrap();
+
+        let addresses: Vec<_> = monitor
+            .sentinels_connection_info
+            .iter()
+            .map(|connection_info| connection_info.addr.clone())
+            .collect();
+        assert_eq!(
+            addresses,
+            vec![
+                ConnectionAddr::Tcp("sentinel1".to_string(), DEFAULT_SENTINEL_PORT),
+                ConnectionAddr::Tcp("sentinel2".to_string(), 26380),
+            ]
+        );
+        for connection_info in monitor.sentinels_connection_info.iter() {
+            assert_eq!(
+                connection_info.redis.password.as_deref(),
+                Some("sentinel-password")
+            );
+        }
+        // The nodes that the sentinels report are checked with the client's own credentials.
+        assert_eq!(
+            monitor
+                .node_connection_info
+                .redis_connection_info
+                .and_then(|info| info.password)
+                .as_deref(),
+            Some("node-password")
+        );
+    }
+
+    #[test]
+    fn test_sentinel_monitor_updates_tls_params() {
+        let mut monitor = SentinelMonitor::new(
+            &sentinel_config(),
+            TlsMode::SecureTls,
+            None,
+            &RedisConnectionInfo::default(),
+        )
+        .unwrap();
+        let tls_params = redis::retrieve_tls_certificates(redis::TlsCertificates {
+            client_tls: None,
+            root_cert: None,
+            server_name: Some("sentinel.example".to_string()),
+        })
+        .unwrap();
+
+        monitor.update_tls_params(tls_params).unwrap();
+
+        for connection_info in monitor.sentinels_connection_info.iter() {
+            assert!(matches!(
+                connection_info.addr,
+                ConnectionAddr::TcpTls {
+                    tls_params: Some(_),
+                    ..
+                }
+            ));
+        }
+        assert!(monitor.node_connection_info.tls_params.is_some());
+    }
+
+    fn node_address(host: &str, port: u16) -> NodeAddress {
+        NodeAddress {
+            host: host.to_string(),
+            port,
+            unix_socket_path: None,
+        }
+    }
+
+    #[test]
+    fn test_replica_redirects_replace_the_replicas_that_are_no_longer_reported() {
+        // After a failover, the old primary became a replica, and another replica was removed.
+        let replicas = vec![
+            (1, "127.0.0.2:6380".to_string()),
+            (2, "127.0.0.3:6381".to_string()),
+        ];
+        let reported_replicas = vec![
+            node_address("127.0.0.2", 6380),
+            node_address("127.0.0.1", 6379),
+        ];
+
+        let redirects = replica_redirects(&replicas, &reported_replicas);
+
+        assert_eq!(redirects.len(), 1);
+        assert_eq!(redirects[0].0, 2);
+        assert_eq!(redirects[0].1.address_string(), "127.0.0.1:6379");
+    }
+
+    #[test]
+    fn test_replica_redirects_keep_the_reported_replicas() {
+        let replicas = vec![(0, "127.0.0.2:6380".to_string())];
+        assert!(replica_redirects(&replicas, &[node_address("127.0.0.2", 6380)]).is_empty());
+        // A replica that isn't reported is kept if there's no new replica to redirect it to.
+        assert!(replica_redirects(&replicas, &[]).is_empty());
+        // The connections are fixed, so the reported replicas beyond them are ignored.
+        assert!(replica_redirects(&[], &[node_address("127.0.0.2", 6380)]).is_empty());
+    }
+
+    #[test]
+    fn test_parse_switch_master_payload() {
+        let address =
+            parse_switch_master_payload("mymaster 127.0.0.1 6379 127.0.0.2 6380", "mymaster")
+                .unwrap();
+        assert_eq!(address.host, "127.0.0.2");
+        assert_eq!(address.port, 6380);
+    }
+
+    #[test]
+    fn test_parse_switch_master_payload_ignores_other_masters_and_malformed_payloads() {
+        assert!(
+            parse_switch_master_payload("othermaster 127.0.0.1 6379 127.0.0.2 6380", "mymaster")
+                .is_none()
+        );
+        assert!(parse_switch_master_payload("mymaster 127.0.0.1 6379", "mymaster").is_none());
+        assert!(
+            parse_switch_master_payload("mymaster 127.0.0.1 6379 127.0.0.2 port", "mymaster")
+                .is_none()
+        );
+    }
+}
@@ -1,23 +1,32 @@
 // Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
 
-use super::get_redis_connection_info;
+use super::credentials;
 use super::reconnecting_connection::{ReconnectReason, ReconnectingConnection};
+use super::sentinel::{self, SentinelMonitor};
 use super::{ConnectionRequest, NodeAddress, TlsMode};
 use super::{DEFAULT_CONNECTION_TIMEOUT, to_duration};
+use super::{get_redis_connection_info, get_tls_certificates};
 use crate::client::types::ReadFrom as ClientReadFrom;
 use futures::{StreamExt, future, stream};
 use logger_core::log_debug;
+use logger_core::log_info;
 use logger_core::log_warn;
 use rand::Rng;
-use redis::aio::ConnectionLike;
-use redis::cluster_routing::{self, ResponsePolicy, Routable, RoutingInfo, is_readonly_cmd};
-use redis::{PushInfo, RedisError, RedisResult, RetryStrategy, Value};
-use std::sync::Arc;
+use redis::aio::{ConnectionCredentialsProvider, ConnectionLike};
+use redis::cluster_routing::{
+    self, ConnectionErrorRetryPolicy, ResponsePolicy, Routable, RoutingInfo, is_readonly_cmd,
+};
+use redis::{
+    NodeRequest, PubSubChannelOrPattern, PubSubSubscriptionInfo, PubSubSubscriptionKind, PushInfo,
+    PushKind, ReadHedging, RedisError, RedisResult, RetryStrategy, Value,
+};
+use std::future::Future;
 use std::sync::atomic::AtomicUsize;
 use std::sync::atomic::Ordering;
-use std::time::Duration;
+use std::sync::{Arc, Weak};
+use std::time::{Duration, Instant};
 use telemetrylib::Telemetry;
-use tokio::sync::mpsc;
+use tokio::sync::{Mutex as TokioMutex, OnceCell, mpsc};
 use tokio::task;
 
 #[derive(Debug)]
@@ -26,6 +35,9 @@ enum ReadFrom {
     PreferReplica {
         latest_read_replica_index: Arc<AtomicUsize>,
     },
+    LowestLatency {
+        latest_read_replica_index: Arc<AtomicUsize>,
+    },
     AZAffinity {
         client_az: String,
         last_read_replica_index: Arc<AtomicUsize>,
@@ -39,14 +51,102 @@ enum ReadFrom {
 #[derive(Debug)]
 struct DropWrapper {
     /// Connection to the primary node in the client.
-    primary_index: usize,
+    /// Can change when a Sentinel-managed deployment fails over.
+    primary_index: AtomicUsize,
+    /// Connection to the node that holds the client's pubsub subscriptions.
+    pubsub_node_index: usize,
     nodes: Vec<ReconnectingConnection>,
+    /// Additional connections to each node, indexed like `nodes`.
+    /// When a node has additional connections, the last one is reserved for blocking commands.
+    connection_pools: Vec<Vec<ReconnectingConnection>>,
+    /// Connections dedicated to blocking commands, indexed like `nodes`, for the nodes without additional connections.
+    /// Each is created on its first use.
+    blocking_connections: Vec<OnceCell<ReconnectingConnection>>,
     read_from: ReadFrom,
+    /// Sends reads that weren't answered in time to another node as well.
+    read_hedging: Option<ReadHedging>,
+    /// Retries idempotent commands whose connection dropped mid-request.
+    connection_error_retry: Option<ConnectionErrorRetryPolicy>,
+    /// Watches the sentinels of a Sentinel-managed deployment for failovers.
+    sentinel_monitor: Option<Arc<TokioMutex<SentinelMonitor>>>,
+}
+
+impl DropWrapper {
+    /// Points the client to a new primary. If the new primary is one of the client's nodes, that node is used as the primary.
+    /// Otherwise, the connection to the previous primary is redirected to the new primary.
+    fn update_primary(&self, primary: &NodeAddress) {
+        let primary_address = primary.address_string();
+        let current_index = self.primary_index.load(Ordering::Relaxed);
+        match self
+            .nodes
+            .iter()
+            .position(|node| node.node_address() == primary_address)
+        {
+            Some(index) if index == current_index => {}
+            Some(index) => {
+                log_info(
+                    "StandaloneClient",
+                    format!("Primary changed to the existing node {primary_address}"),
+                );
+                self.primary_index.store(index, Ordering::Relaxed);
+            }
+            None => {
+                log_info(
+                    "StandaloneClient",
+                    format!("Primary changed to the new node {primary_address}"),
+                );
+                self.update_node_address(current_index, primary);
+            }
+        }
+    }
+
+    /// Redirects the connections to the replicas that the sentinels no longer report to the reported replicas that the
+    /// client isn't connected to, e.g. to the previous primary after a failover.
+    fn update_replicas(&self, replicas: &[NodeAddress]) {
+        let primary_index = self.primary_index.load(Ordering::Relaxed);
+        let current_replicas: Vec<_> = self
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| *index != primary_index)
+            .map(|(index, node)| (index, node.node_address()))
+            .collect();
+        for (index, replica) in sentinel::replica_redirects(&current_replicas, replicas) {
+            log_info(
+                "StandaloneClient",
+                format!(
+                    "Replica {} replaced by {replica}",
+                    self.nodes[index].node_address()
+                ),
+            );
+            self.update_node_address(index, &replica);
+        }
+    }
+
+    /// Redirects all the connections to the node at `index` to `address`.
+    fn update_node_address(&self, index: usize, address: &NodeAddress) {
+        self.nodes[index].update_address(address);
+        for connection in self.connection_pools[index]
+            .iter()
+            .chain(self.blocking_connections[index].get())
+        {
+            connection.update_address(address);
+        }
+    }
+
+    /// Returns the main connections to the nodes, followed by the nodes' additional connections,
+    /// and by the blocking connections that were already created.
+    fn all_connections(&self) -> impl Iterator<Item = &ReconnectingConnection> {
+        self.nodes
+            .iter()
+            .chain(self.connection_pools.iter().flatten())
+            .chain(self.blocking_connections.iter().filter_map(OnceCell::get))
+    }
 }
 
 impl Drop for DropWrapper {
     fn drop(&mut self) {
-        for node in self.nodes.iter() {
+        for node in self.all_connections() {
             node.mark_as_dropped();
         }
     }
@@ -117,10 +217,59 @@ impl StandaloneClient {
         connection_request: ConnectionRequest,
         push_sender: Option<mpsc::UnboundedSender<PushInfo>>,
     ) -> Result<Self, StandaloneClientConnectionError> {
-        if connection_request.addresses.is_empty() {
+        let mut redis_connection_info = get_redis_connection_info(&connection_request);
+        let tls_mode = connection_request.tls_mode.unwrap_or(TlsMode::NoTls);
+        let tls_params = get_tls_certificates(tls_mode, connection_request.tls_config.as_ref())
+            .and_then(|certificates| {
+                certificates
+                    .map(redis::retrieve_tls_certificates)
+                    .transpose()
+            })
+            .map_err(|err| StandaloneClientConnectionError::FailedConnection(vec![(None, err)]))?;
+        let read_hedging = connection_request
+            .read_hedging
+            .map(ReadHedging::new)
+            .transpose()
+            .map_err(|err| StandaloneClientConnectionError::FailedConnection(vec![(None, err)]))?;
+
+        // With Sentinel, the nodes are discovered from the sentinels instead of being provided by the user.
+        let mut sentinel_monitor = None;
+        let addresses = match &connection_request.sentinel {
+            Some(sentinel_config) => {
+                let mut monitor = SentinelMonitor::new(
+                    sentinel_config,
+                    tls_mode,
+                    tls_params.clone(),
+                    &redis_connection_info,
+                )
+                .map_err(|err| {
+                    StandaloneClientConnectionError::FailedConnection(vec![(None, err)])
+                })?;
+                let addresses = monitor.discover_nodes().await.map_err(|err| {
+                    StandaloneClientConnectionError::FailedConnection(vec![(None, err)])
+                })?;
+                sentinel_monitor = Some(Arc::new(TokioMutex::new(monitor)));
+                addresses
+            }
+            None => connection_request.addresses,
+        };
+        if addresses.is_empty() {
             return Err(StandaloneClientConnectionError::NoAddressesProvided);
         }
-        let mut redis_connection_info = get_redis_connection_info(&connection_request);
+        if tls_mode != TlsMode::NoTls
+            && let Some(address) = addresses
+                .iter()
+                .find(|address| address.unix_socket_path.is_some())
+        {
+            return Err(StandaloneClientConnectionError::FailedConnection(vec![(
+                Some(address.address_string()),
+                RedisError::from((
+                    redis::ErrorKind::InvalidClientConfig,
+                    "TLS is not supported over unix socket addresses",
+                )),
+            )]));
+        }
+
         let pubsub_connection_info = redis_connection_info.clone();
         redis_connection_info.pubsub_subscriptions = None;
         let retry_strategy = match connection_request.connection_retry_strategy {
@@ -133,11 +282,11 @@ impl StandaloneClient {
             None => RetryStrategy::default(),
         };
 
-        let tls_mode = connection_request.tls_mode;
-        let node_count = connection_request.addresses.len();
+        let node_count = addresses.len();
         // randomize pubsub nodes, maybe a batter option is to always use the primary
         let pubsub_node_index = rand::thread_rng().gen_range(0..node_count);
-        let pubsub_addr = connection_request.addresses[pubsub_node_index].clone();
+        let pubsub_addr = addresses[pubsub_node_index].clone();
+        let pubsub_address = pubsub_addr.address_string();
         let discover_az = matches!(
             connection_request.read_from,
             Some(ClientReadFrom::AZAffinity(_))
@@ -148,25 +297,47 @@ impl StandaloneClient {
             connection_request.connection_timeout,
             DEFAULT_CONNECTION_TIMEOUT,
         );
+        let client_tracking = connection_request
+            .client_side_cache
+            .as_ref()
+            .map(|cache| cache.tracking_mode);
+        let circuit_breaker_threshold = connection_request.circuit_breaker_threshold;
+        let credentials_provider = connection_request
+            .credential_provider
+            .clone()
+            .map(credentials::for_connections);
 
-        let mut stream = stream::iter(connection_request.addresses.into_iter())
+        let pubsub_address_clone = pubsub_address.clone();
+        let mut stream = stream::iter(addresses.into_iter())
             .map(move |address| {
-                let info = if address.host != pubsub_addr.host || address.port != pubsub_addr.port {
+                let info = if address.address_string() != pubsub_address_clone {
                     redis_connection_info.clone()
                 } else {
                     pubsub_connection_info.clone()
                 };
                 let retry = retry_strategy;
                 let sender = push_sender.clone();
-                let tls = tls_mode.unwrap_or(TlsMode::NoTls);
+                let tls = tls_mode;
+                let tls_params = tls_params.clone();
                 let discover = discover_az;
                 let timeout = connection_timeout;
+                let credentials_provider = credentials_provider.clone();
                 async move {
                     get_connection_and_replication_info(
-                        &address, &retry, &info, tls, &sender, discover, timeout,
+                        &address,
+                        &retry,
+                        &info,
+                        tls,
+                        tls_params,
+                        &sender,
+                        discover,
+                        timeout,
+                        client_tracking,
+                        circuit_breaker_threshold,
+                        credentials_provider,
                     )
                     .await
-                    .map_err(|err| (format!("{}:{}", address.host, address.port), err))
+                    .map_err(|err| (address.address_string(), err))
                 }
             })
             .buffer_unordered(node_count);
@@ -223,7 +394,20 @@ impl StandaloneClient {
                 ),
             );
         }
+        // The nodes are ordered by the time their connection was established, so the pubsub node is looked up by its address.
+        let pubsub_node_index = nodes
+            .iter()
+            .position(|node| node.node_address() == pubsub_address)
+            .unwrap_or(primary_index);
         let read_from = get_read_from(connection_request.read_from);
+        let latency_check_timeout =
+            matches!(read_from, ReadFrom::LowestLatency { .. }).then_some(connection_timeout);
+
+        let connections_per_node = connection_request.connections_per_node.unwrap_or(1) as usize;
+        let connection_pools = future::join_all(nodes.iter().map(|node| {
+            future::join_all((1..connections_per_node).map(|_| node.new_pool_connection(true)))
+        }))
+        .await;
 
         #[cfg(feature = "standalone_heartbeat")]
         for node in nodes.iter() {
@@ -231,23 +415,50 @@ impl StandaloneClient {
         }
 
         for node in nodes.iter() {
-            Self::start_periodic_connection_check(node.clone());
+            Self::start_periodic_connection_check(node.clone(), latency_check_timeout);
+        }
+
+        // The latency is measured only over the nodes' main connections.
+        for connection in connection_pools.iter().flatten() {
+            #[cfg(feature = "standalone_heartbeat")]
+            Self::start_heartbeat(connection.clone());
+            Self::start_periodic_connection_check(connection.clone(), None);
         }
 
         // Successfully created new client. Update the telemetry
         Telemetry::incr_total_clients(1);
 
-        Ok(Self {
+        let client = Self {
             inner: Arc::new(DropWrapper {
-                primary_index,
+                primary_index: AtomicUsize::new(primary_index),
+                pubsub_node_index,
+                blocking_connections: nodes.iter().map(|_| OnceCell::new()).collect(),
                 nodes,
+                connection_pools,
                 read_from,
+                read_hedging,
+                connection_error_retry: connection_request.connection_error_retry,
+                sentinel_monitor: sentinel_monitor.clone(),
             }),
-        })
+        };
+
+        if let Some(sentinel_monitor) = sentinel_monitor {
+            Self::start_sentinel_failover_watch(
+                Arc::downgrade(&client.inner),
+                sentinel_monitor,
+                connection_timeout,
+            );
+        }
+
+        Ok(client)
+    }
+
+    fn get_primary_index(&self) -> usize {
+        self.inner.primary_index.load(Ordering::Relaxed)
     }
 
     fn get_primary_connection(&self) -> &ReconnectingConnection {
-        self.inner.nodes.get(self.inner.primary_index).unwrap()
+        self.inner.nodes.get(self.get_primary_index()).unwrap()
     }
 
     fn round_robin_read_from_replica(
@@ -255,6 +466,7 @@ impl StandaloneClient {
         latest_read_replica_index: &Arc<AtomicUsize>,
     ) -> &ReconnectingConnection {
         let initial_index = latest_read_replica_index.load(Ordering::Relaxed);
+        let primary_index = self.get_primary_index();
         let mut check_count = 0;
         loop {
             check_count += 1;
@@ -264,7 +476,7 @@ impl StandaloneClient {
                 return self.get_primary_connection();
             }
             let index = (initial_index + check_count) % self.inner.nodes.len();
-            if index == self.inner.primary_index {
+            if index == primary_index {
                 continue;
             }
             let Some(connection) = self.inner.nodes.get(index) else {
@@ -282,6 +494,24 @@ impl StandaloneClient {
         }
     }
 
+    /// Returns the connected replica with the lowest measured round-trip time.
+    /// If no replica was measured yet, falls back to round robin between the replicas, or to the primary if needed.
+    fn lowest_latency_read_from_replica(
+        &self,
+        latest_read_replica_index: &Arc<AtomicUsize>,
+    ) -> &ReconnectingConnection {
+        let primary_index = self.get_primary_index();
+        self.inner
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(index, node)| *index != primary_index && node.is_connected())
+            .filter_map(|(_, node)| node.average_latency().map(|latency| (latency, node)))
+            .min_by_key(|(latency, _)| *latency)
+            .map(|(_, node)| node)
+            .unwrap_or_else(|| self.round_robin_read_from_replica(latest_read_replica_index))
+    }
+
     async fn round_robin_read_from_replica_az_awareness(
         &self,
         latest_read_replica_index: &Arc<AtomicUsize>,
@@ -381,6 +611,9 @@ impl StandaloneClient {
             ReadFrom::PreferReplica {
                 latest_read_replica_index,
             } => self.round_robin_read_from_replica(latest_read_replica_index),
+            ReadFrom::LowestLatency {
+                latest_read_replica_index,
+            } => self.lowest_latency_read_from_replica(latest_read_replica_index),
             ReadFrom::AZAffinity {
                 client_az,
                 last_read_replica_index,
@@ -404,9 +637,81 @@ impl StandaloneClient {
         }
     }
 
+    fn node_index(&self, node: &ReconnectingConnection) -> Option<usize> {
+        self.inner
+            .nodes
+            .iter()
+            .position(|candidate| std::ptr::eq(candidate, node))
+    }
+
+    /// Returns the least loaded of the connections to `node`, excluding the connection reserved for blocking commands.
+    fn select_node_connection<'a>(
+        &'a self,
+        node: &'a ReconnectingConnection,
+    ) -> &'a ReconnectingConnection {
+        let Some((_, pool)) = self
+            .node_index(node)
+            .and_then(|index| self.inner.connection_pools[index].split_last())
+        else {
+            return node;
+        };
+        std::iter::once(node)
+            .chain(pool)
+            .min_by_key(|connection| connection.pending_requests())
+            .unwrap_or(node)
+    }
+
+    /// Returns the connection to `node` that blocking commands are sent over, so they don't delay other requests.
+    /// A node with additional connections reserves the last of them. Otherwise, a dedicated connection is created on
+    /// first use, and from then on reconnects like the node's other connections. Until the dedicated connection is
+    /// connected, blocking commands are sent over the node's other connections.
+    async fn get_blocking_connection<'a>(
+        &'a self,
+        node: &'a ReconnectingConnection,
+    ) -> &'a ReconnectingConnection {
+        let Some(index) = self.node_index(node) else {
+            return node;
+        };
+        if let Some(reserved_connection) = self.inner.connection_pools[index].last() {
+            return reserved_connection;
+        }
+        // Concurrent first uses wait for the same connection. A failed attempt isn't retried inline, so it doesn't delay
+        // the request.
+        let blocking_connection = self.inner.blocking_connections[index]
+            .get_or_init(|| async {
+                let connection = node.new_pool_connection(false).await;
+                #[cfg(feature = "standalone_heartbeat")]
+                Self::start_heartbeat(connection.clone());
+                Self::start_periodic_connection_check(connection.clone(), None);
+                connection
+            })
+            .await;
+        if blocking_connection.is_connected() {
+            blocking_connection
+        } else {
+            self.select_node_connection(node)
+        }
+    }
+
     async fn send_request(
         cmd: &redis::Cmd,
         reconnecting_connection: &ReconnectingConnection,
+    ) -> RedisResult<Value> {
+        // Blocking commands legitimately wait for long periods, so they don't indicate whether the node is responsive.
+        let attempt = if cluster_routing::is_blocking(cmd) {
+            None
+        } else {
+            reconnecting_connection.circuit_breaker_attempt()?
+        };
+        let node_request = NodeRequest::new(&reconnecting_connection.node_address(), attempt);
+        let result = Self::send_request_to_connection(cmd, reconnecting_connection).await;
+        node_request.complete(&result);
+        result
+    }
+
+    async fn send_request_to_connection(
+        cmd: &redis::Cmd,
+        reconnecting_connection: &ReconnectingConnection,
     ) -> RedisResult<Value> {
         let mut connection = reconnecting_connection.get_connection().await?;
         let result = connection.send_packed_command(cmd).await;
@@ -491,13 +796,166 @@ impl StandaloneClient {
         }
     }
 
+    /// Sends the request to a single node. If the connection drops mid-request, an idempotent command is sent again
+    /// once the connection is restored, as allowed by the client's connection error retry policy.
     async fn send_request_to_single_node(
         &mut self,
         cmd: &redis::Cmd,
         readonly: bool,
     ) -> RedisResult<Value> {
-        let reconnecting_connection = self.get_connection(readonly).await;
-        Self::send_request(cmd, reconnecting_connection).await
+        let mut connection_error_retries = 0;
+        loop {
+            let result = self.send_request_to_node(cmd, readonly).await;
+            match (&result, &self.inner.connection_error_retry) {
+                (Err(err), Some(policy))
+                    if err.is_connection_dropped()
+                        && connection_error_retries < policy.max_retries()
+                        && policy.is_idempotent(cmd) =>
+                {
+                    connection_error_retries += 1;
+                    log_debug(
+                        "send request",
+                        format!(
+                            "retrying after connection error `{err}`, attempt {connection_error_retries}"
+                        ),
+                    );
+                }
+                _ => return result,
+            }
+        }
+    }
+
+    async fn send_request_to_node(
+        &mut self,
+        cmd: &redis::Cmd,
+        readonly: bool,
+    ) -> RedisResult<Value> {
+        let node = self.get_connection(readonly).await;
+        let reconnecting_connection = if cluster_routing::is_blocking(cmd) {
+            self.get_blocking_connection(node).await
+        } else {
+            self.select_node_connection(node)
+        };
+        match &self.inner.read_hedging {
+            Some(read_hedging)
+                if readonly
+                    && self.inner.nodes.len() > 1
+                    && !matches!(self.inner.read_from, ReadFrom::Primary)
+                    && !cluster_routing::is_blocking(cmd) =>
+            {
+                self.send_hedged_request(cmd, reconnecting_connection, read_hedging)
+                    .await
+            }
+            _ => Self::send_request(cmd, reconnecting_connection).await,
+        }
+    }
+
+    /// Returns a connected node other than `node` to hedge a read with, preferring replicas over the primary.
+    fn hedge_node(&self, node: &ReconnectingConnection) -> Option<&ReconnectingConnection> {
+        let primary_index = self.get_primary_index();
+        let node_address = node.node_address();
+        self.inner
+            .nodes
+            .iter()
+            .enumerate()
+            .filter(|(_, candidate)| {
+                candidate.is_connected() && candidate.node_address() != node_address
+            })
+            .min_by_key(|(index, _)| *index == primary_index)
+            .map(|(_, candidate)| self.select_node_connection(candidate))
+    }
+
+    /// Sends a read to `reconnecting_connection`, and if it isn't answered within the hedging delay, also to another node.
+    /// The first response is returned, and the other request is dropped.
+    async fn send_hedged_request(
+        &self,
+        cmd: &redis::Cmd,
+        reconnecting_connection: &ReconnectingConnection,
+        read_hedging: &ReadHedging,
+    ) -> RedisResult<Value> {
+        let node_request = NodeRequest::new(
+            &reconnecting_connection.node_address(),
+            reconnecting_connection.circuit_breaker_attempt()?,
+        );
+        let start = Instant::now();
+        let mut request = Box::pin(Self::send_request_to_connection(
+            cmd,
+            reconnecting_connection,
+        ));
+        let early_result = match read_hedging.delay() {
+            Some(delay) => tokio::time::timeout(delay, &mut request).await.ok(),
+            None => Some((&mut request).await),
+        };
+
+        let hedge = if early_result.is_none() {
+            // Nodes whose requests fail fast aren't used for hedging.
+            self.hedge_node(reconnecting_connection)
+                .and_then(|hedge_node| {
+                    let hedge_attempt = hedge_node.circuit_breaker_attempt().ok()?;
+                    Some((
+                        hedge_node,
+                        NodeRequest::new(&hedge_node.node_address(), hedge_attempt),
+                    ))
+                })
+        } else {
+            None
+        };
+        let result = match (early_result, hedge) {
+            (Some(result), _) => Self::complete_node_request(node_request, result),
+            (None, Some((hedge_node, hedge_node_request))) => {
+                let hedge_request = Box::pin(Self::send_request_to_connection(cmd, hedge_node));
+                match future::select(request, hedge_request).await {
+                    future::Either::Left((result, hedge_request)) => {
+                        Self::settle_hedged_read(
+                            (node_request, result),
+                            (hedge_node_request, hedge_request),
+                        )
+                        .await
+                    }
+                    future::Either::Right((result, request)) => {
+                        Self::settle_hedged_read(
+                            (hedge_node_request, result),
+                            (node_request, request),
+                        )
+                        .await
+                    }
+                }
+            }
+            (None, None) => Self::complete_node_request(node_request, request.await),
+        };
+        if result.is_ok() {
+            read_hedging.record(start.elapsed());
+        }
+        result
+    }
+
+    fn complete_node_request(
+        node_request: NodeRequest,
+        result: RedisResult<Value>,
+    ) -> RedisResult<Value> {
+        node_request.complete(&result);
+        result
+    }
+
+    /// Returns the result of the hedged request that finished first if it succeeded, and drops the other request.
+    /// If it failed, the other request is awaited, so a node that fails fast doesn't fail a read that the other node
+    /// can serve. The error of the first request is returned only if both failed.
+    async fn settle_hedged_read(
+        (node_request, result): (NodeRequest, RedisResult<Value>),
+        (other_node_request, other_request): (
+            NodeRequest,
+            impl Future<Output = RedisResult<Value>>,
+        ),
+    ) -> RedisResult<Value> {
+        let result = Self::complete_node_request(node_request, result);
+        if result.is_ok() {
+            other_node_request.abandon();
+            return result;
+        }
+        match Self::complete_node_request(other_node_request, other_request.await) {
+            Ok(value) => Ok(value),
+            Err(_) => result,
+        }
     }
 
     pub async fn send_command(&mut self, cmd: &redis::Cmd) -> RedisResult<Value> {
@@ -519,11 +977,16 @@ impl StandaloneClient {
         offset: usize,
         count: usize,
     ) -> RedisResult<Vec<Value>> {
-        let reconnecting_connection = self.get_primary_connection();
+        let reconnecting_connection = self.select_node_connection(self.get_primary_connection());
+        let node_request = NodeRequest::new(
+            &reconnecting_connection.node_address(),
+            reconnecting_connection.circuit_breaker_attempt()?,
+        );
         let mut connection = reconnecting_connection.get_connection().await?;
         let result = connection
             .send_packed_commands(pipeline, offset, count)
             .await;
+        node_request.complete(&result);
         match result {
             Err(err) if err.is_unrecoverable_error() => {
                 log_warn(
@@ -576,7 +1039,12 @@ impl StandaloneClient {
     // Monitors passive connection status and reconnects if necessary.
     // This function is cheaper alternative to start_heartbeat(),
     // as it avoids sending PING commands to the server, checking only the connection state.
-    fn start_periodic_connection_check(reconnecting_connection: ReconnectingConnection) {
+    // The exception is when `latency_check_timeout` is set: then each check also measures the node's
+    // round-trip time with a PING, to be used by the `LowestLatency` read strategy.
+    fn start_periodic_connection_check(
+        reconnecting_connection: ReconnectingConnection,
+        latency_check_timeout: Option<Duration>,
+    ) {
         task::spawn(async move {
             loop {
                 reconnecting_connection
@@ -592,8 +1060,11 @@ impl StandaloneClient {
                     // Client was dropped, checker can stop.
                     return;
                 }
+                // Let a trial request through if the node failed fast, to test whether it recovered.
+                reconnecting_connection.half_open_circuit_breaker();
 
-                let Some(connection) = reconnecting_connection.try_get_connection().await else {
+                let Some(mut connection) = reconnecting_connection.try_get_connection().await
+                else {
                     log_debug(
                         "StandaloneClient",
                         "connection checker is skipping a connections since its reconnecting",
@@ -608,6 +1079,107 @@ impl StandaloneClient {
                         "connection checker has triggered reconnect",
                     );
                     reconnecting_connection.reconnect(ReconnectReason::ConnectionDropped);
+                } else if let Some(latency_check_timeout) = latency_check_timeout {
+                    let start = Instant::now();
+                    if let Ok(Ok(_)) = tokio::time::timeout(
+                        latency_check_timeout,
+                        connection.send_packed_command(&redis::cmd("PING")),
+                    )
+                    .await
+                    {
+                        reconnecting_connection.record_latency(start.elapsed());
+                    }
+                }
+            }
+        });
+    }
+
+    // Watches the sentinels for failovers, and points the client to the new primary once one is promoted,
+    // and to the replicas that the sentinels report after the failover.
+    // After every (re)subscription the current nodes are also queried, so failovers that happened
+    // while no sentinel was being watched aren't missed.
+    fn start_sentinel_failover_watch(
+        client: Weak<DropWrapper>,
+        sentinel_monitor: Arc<TokioMutex<SentinelMonitor>>,
+        connection_timeout: Duration,
+    ) {
+        task::spawn(async move {
+            loop {
+                if client.strong_count() == 0 {
+                    log_debug(
+                        "StandaloneClient",
+                        "sentinel failover watch stopped after client was dropped",
+                    );
+                    return;
+                }
+
+                let mut failovers = match sentinel_monitor
+                    .lock()
+                    .await
+                    .subscribe_to_failovers(connection_timeout)
+                    .await
+                {
+                    Ok(failovers) => failovers,
+                    Err(err) => {
+                        log_warn(
+                            "StandaloneClient",
+                            format!("Failed to subscribe to sentinel failovers: {err}"),
+                        );
+                        tokio::time::sleep(super::CONNECTION_CHECKS_INTERVAL).await;
+                        continue;
+                    }
+                };
+
+                let nodes = sentinel_monitor.lock().await.discover_nodes().await;
+                match nodes {
+                    Ok(nodes) => {
+                        let Some(inner) = client.upgrade() else {
+                            return;
+                        };
+                        // The primary is always the first node.
+                        inner.update_primary(&nodes[0]);
+                        inner.update_replicas(&nodes[1..]);
+                    }
+                    Err(err) => log_warn(
+                        "StandaloneClient",
+                        format!("Failed to discover the nodes from sentinel: {err}"),
+                    ),
+                }
+
+                loop {
+                    match tokio::time::timeout(super::CONNECTION_CHECKS_INTERVAL, failovers.next())
+                        .await
+                    {
+                        Ok(Some(primary)) => {
+                            let Some(inner) = client.upgrade() else {
+                                return;
+                            };
+                            inner.update_primary(&primary);
+                            drop(inner);
+                            let replicas = sentinel_monitor.lock().await.discover_replicas().await;
+                            match (replicas, client.upgrade()) {
+                                (Ok(replicas), Some(inner)) => inner.update_replicas(&replicas),
+                                (Ok(_), None) => return,
+                                (Err(err), _) => log_warn(
+                                    "StandaloneClient",
+                                    format!("Failed to discover the replicas from sentinel: {err}"),
+                                ),
+                            }
+                        }
+                        Ok(None) => {
+                            log_debug(
+                                "StandaloneClient",
+                                "sentinel failover subscription was lost, resubscribing",
+                            );
+                            break;
+                        }
+                        Err(_) => {
+                            // No failover during the interval, only check whether the client was dropped.
+                            if client.strong_count() == 0 {
+                                break;
+                            }
+                        }
+                    }
                 }
             }
         });
@@ -619,13 +1191,159 @@ impl StandaloneClient {
         &self,
         new_password: Option<String>,
     ) -> RedisResult<Value> {
-        for node in self.inner.nodes.iter() {
+        for node in self.inner.all_connections() {
             node.update_connection_password(new_password.clone());
         }
 
         Ok(Value::Okay)
     }
 
+    /// Update the TLS parameters used to connect to the server.
+    /// If `reconnect` is true, the existing connections are also replaced in the background,
+    /// and each of them keeps serving requests until its replacement is established.
+    pub async fn update_tls_params(
+        &self,
+        tls_params: redis::TlsConnParams,
+        reconnect: bool,
+    ) -> RedisResult<Value> {
+        // All nodes share the same TLS mode, so either all of them are updated, or the first one fails.
+        for node in self.inner.all_connections() {
+            node.update_tls_params(tls_params.clone())?;
+        }
+        // The sentinels are connected with the same TLS configuration as the nodes.
+        if let Some(sentinel_monitor) = &self.inner.sentinel_monitor {
+            sentinel_monitor
+                .lock()
+                .await
+                .update_tls_params(tls_params.clone())?;
+        }
+
+        if reconnect {
+            for node in self.inner.all_connections() {
+                let node = node.clone();
+                tokio::spawn(async move {
+                    if let Err(err) = node.replace_connection().await {
+                        log_warn(
+                            "update TLS credentials",
+                            format!(
+                                "Failed to replace the connection to {node:?}, keeping the existing connection. Error: {err}"
+                            ),
+                        );
+                    }
+                });
+            }
+        }
+
+        Ok(Value::Okay)
+    }
+
+    /// Subscribes to the given channels or patterns at runtime. Requires RESP3.
+    /// The subscriptions are saved in the pubsub node's connection info, so they are re-established after reconnects.
+    pub async fn subscribe(
+        &self,
+        kind: PubSubSubscriptionKind,
+        channels_or_patterns: Vec<PubSubChannelOrPattern>,
+    ) -> RedisResult<Value> {
+        let node = self.get_pubsub_connection()?;
+        node.update_pubsub_subscriptions(|subscriptions| {
+            subscriptions
+                .entry(kind)
+                .or_default()
+                .extend(channels_or_patterns.iter().cloned());
+        });
+        Self::send_subscription_commands(node, kind, true, channels_or_patterns).await
+    }
+
+    /// Unsubscribes from the given channels or patterns at runtime, and removes them from the pubsub node's connection info.
+    pub async fn unsubscribe(
+        &self,
+        kind: PubSubSubscriptionKind,
+        channels_or_patterns: Vec<PubSubChannelOrPattern>,
+    ) -> RedisResult<Value> {
+        let node = self.get_pubsub_connection()?;
+        node.update_pubsub_subscriptions(|subscriptions| {
+            if let Some(subscribed) = subscriptions.get_mut(&kind) {
+                subscribed
+                    .retain(|channel_pattern| !channels_or_patterns.contains(channel_pattern));
+                if subscribed.is_empty() {
+                    subscriptions.remove(&kind);
+                }
+            }
+        });
+        Self::send_subscription_commands(node, kind, false, channels_or_patterns).await
+    }
+
+    /// Returns the desired subscriptions, followed by the subscriptions that the server confirmed on the pubsub node's connection.
+    pub fn get_subscriptions(&self) -> (PubSubSubscriptionInfo, PubSubSubscriptionInfo) {
+        let node = &self.inner.nodes[self.inner.pubsub_node_index];
+        (
+            node.get_pubsub_subscriptions(),
+            node.confirmed_subscriptions(),
+        )
+    }
+
+    fn get_pubsub_connection(&self) -> RedisResult<&ReconnectingConnection> {
+        let node = &self.inner.nodes[self.inner.pubsub_node_index];
+        if node.protocol() == redis::ProtocolVersion::RESP2 {
+            return Err(RedisError::from((
+                redis::ErrorKind::InvalidClientConfig,
+                "RESP3 is required for runtime subscriptions",
+            )));
+        }
+        Ok(node)
+    }
+
+    // Sends a subscription command per channel or pattern, since each of them is confirmed with its own push notification.
+    // If the connection is lost meanwhile, the subscriptions state is applied when the connection is re-established.
+    async fn send_subscription_commands(
+        node: &ReconnectingConnection,
+        kind: PubSubSubscriptionKind,
+        subscribe: bool,
+        channels_or_patterns: Vec<PubSubChannelOrPattern>,
+    ) -> RedisResult<Value> {
+        let (command_name, expected_kind) = match (kind, subscribe) {
+            (PubSubSubscriptionKind::Exact, true) => {
+                (kind.subscribe_command(), PushKind::Subscribe)
+            }
+            (PubSubSubscriptionKind::Exact, false) => {
+                (kind.unsubscribe_command(), PushKind::Unsubscribe)
+            }
+            (PubSubSubscriptionKind::Pattern, true) => {
+                (kind.subscribe_command(), PushKind::PSubscribe)
+            }
+            (PubSubSubscriptionKind::Pattern, false) => {
+                (kind.unsubscribe_command(), PushKind::PUnsubscribe)
+            }
+            (PubSubSubscriptionKind::Sharded, true) => {
+                (kind.subscribe_command(), PushKind::SSubscribe)
+            }
+            (PubSubSubscriptionKind::Sharded, false) => {
+                (kind.unsubscribe_command(), PushKind::SUnsubscribe)
+            }
+        };
+        let mut connection = node.get_connection().await?;
+        for channel_pattern in channels_or_patterns {
+            let mut command = redis::cmd(command_name);
+            command.arg(&channel_pattern);
+            let reply = connection.send_packed_command(&command).await?;
+            let confirmed = matches!(
+                &reply,
+                Value::Push { kind, data }
+                    if *kind == expected_kind
+                        && data.first() == Some(&Value::BulkString(channel_pattern.clone()))
+            );
+            if !confirmed {
+                return Err(RedisError::from((
+                    redis::ErrorKind::ResponseError,
+                    "The server didn't confirm the subscription change",
+                    format!("{command_name} replied with {reply:?}"),
+                )));
+            }
+            node.confirm_subscription(kind, channel_pattern, subscribe);
+        }
+        Ok(Value::Okay)
+    }
+
     /// Retrieve the username used to authenticate with the server.
     pub fn get_username(&self) -> Option<String> {
         // All nodes in the client should have the same username configured, thus any connection would work here.
@@ -633,23 +1351,32 @@ impl StandaloneClient {
     }
 }
 
+#[allow(clippy::too_many_arguments)]
 async fn get_connection_and_replication_info(
     address: &NodeAddress,
     retry_strategy: &RetryStrategy,
     connection_info: &redis::RedisConnectionInfo,
     tls_mode: TlsMode,
+    tls_params: Option<redis::TlsConnParams>,
     push_sender: &Option<mpsc::UnboundedSender<PushInfo>>,
     discover_az: bool,
     connection_timeout: Duration,
+    client_tracking: Option<redis::ClientTrackingMode>,
+    circuit_breaker_threshold: Option<u32>,
+    credentials_provider: Option<Arc<dyn ConnectionCredentialsProvider>>,
 ) -> Result<(ReconnectingConnection, Value), (ReconnectingConnection, RedisError)> {
     let result = ReconnectingConnection::new(
         address,
         *retry_strategy,
         connection_info.clone(),
         tls_mode,
+        tls_params,
         push_sender.clone(),
         discover_az,
         connection_timeout,
+        client_tracking,
+        circuit_breaker_threshold,
+        credentials_provider,
     )
     .await;
     let reconnecting_connection = match result {
@@ -684,6 +1411,9 @@ fn get_read_from(read_from: Option<super::ReadFrom>) -> ReadFrom {
         Some(super::ReadFrom::PreferReplica) => ReadFrom::PreferReplica {
             latest_read_replica_index: Default::default(),
         },
+        Some(super::ReadFrom::LowestLatency) => ReadFrom::LowestLatency {
+            latest_read_replica_index: Default::default(),
+        },
         Some(super::ReadFrom::AZAffinity(az)) => ReadFrom::AZAffinity {
             client_az: az,
             last_read_replica_index: Default::default(),
@@ -4,8 +4,12 @@
 use logger_core::log_warn;
 #[allow(unused_imports)]
 use std::collections::HashSet;
+use std::path::PathBuf;
+use std::sync::Arc;
 use std::time::Duration;
 
+use super::{CommandPolicy, CredentialProvider, Middleware};
+
 #[cfg(feature = "proto")]
 use crate::connection_request as protobuf;
 
@@ -26,6 +30,35 @@ pub struct ConnectionRequest {
     pub pubsub_subscriptions: Option<redis::PubSubSubscriptionInfo>,
     pub inflight_requests_limit: Option<u32>,
     pub lazy_connect: bool,
+    pub sentinel: Option<SentinelConfig>,
+    pub tls_config: Option<TlsConfig>,
+    /// Fetches the credentials before connecting, and refreshes them before they expire.
+    /// Overrides the password in `authentication_info`.
+    pub credential_provider: Option<Arc<dyn CredentialProvider>>,
+    pub client_side_cache: Option<ClientSideCacheConfig>,
+    /// Number of multiplexed connections opened to each node. Requests are sent over the least
+    /// loaded one, and when there is more than one, the last is reserved for blocking commands.
+    /// With a single connection, blocking commands are sent over a separate connection, which is opened on first use.
+    pub connections_per_node: Option<u32>,
+    /// Translates the node addresses announced by a cluster into reachable addresses. Only used in cluster mode.
+    pub address_remap: Option<redis::cluster::AddressRemap>,
+    /// The number of consecutive timeouts or IO errors after which requests to a node fail fast,
+    /// until a periodic connection check lets a trial request through. If `None`, the circuit breakers are disabled.
+    pub circuit_breaker_threshold: Option<u32>,
+    /// Sends reads that weren't answered in time to another node as well. If `None`, reads aren't hedged.
+    pub read_hedging: Option<redis::HedgePolicy>,
+    /// Retries idempotent commands whose connection dropped mid-request. If `None`, such commands fail.
+    pub connection_error_retry: Option<redis::cluster_routing::ConnectionErrorRetryPolicy>,
+    /// Routes for the commands that the client doesn't know, such as module commands. Only used in cluster mode.
+    /// The table is shared with the cluster client, which fills it from the cluster if it discovers routes.
+    pub command_routing_table: Option<Arc<redis::cluster_routing::CommandRoutingTable>>,
+    /// Prepended to the keys of the commands, to namespace the keys of the client, and stripped from the key names in
+    /// the responses of commands such as SCAN and KEYS.
+    pub key_prefix: Option<Vec<u8>>,
+    /// Restricts the commands that the client may send, including the commands in batches and scripts.
+    pub command_policy: Option<CommandPolicy>,
+    /// Middlewares that run around the requests of the client, in this order. Only set in-process, and not through the protobuf request.
+    pub middlewares: Vec<Arc<dyn Middleware>>,
 }
 
 #[derive(PartialEq, Eq, Clone, Default, Debug)]
@@ -34,6 +67,17 @@ pub struct AuthenticationInfo {
     pub password: Option<String>,
 }
 
+/// Sentinel deployment from which a standalone client discovers its primary and replicas.
+#[derive(Clone, Debug)]
+pub struct SentinelConfig {
+    /// Addresses of the sentinel nodes.
+    pub addresses: Vec<NodeAddress>,
+    /// The name under which the sentinels monitor the primary.
+    pub master_name: String,
+    /// Credentials used to connect to the sentinel nodes, which may differ from the data nodes' credentials.
+    pub authentication_info: Option<AuthenticationInfo>,
+}
+
 #[derive(Default, Clone, Copy, Debug)]
 pub enum PeriodicCheck {
     #[default]
@@ -46,11 +90,27 @@ pub enum PeriodicCheck {
 pub struct NodeAddress {
     pub host: String,
     pub port: u16,
+    /// The path of a unix domain socket to connect through, instead of the host and port.
+    /// Only supported in standalone mode, without TLS.
+    pub unix_socket_path: Option<PathBuf>,
+}
+
+impl NodeAddress {
+    /// Returns the address in the format used by the connections, either `host:port` or the unix socket path.
+    pub(crate) fn address_string(&self) -> String {
+        match &self.unix_socket_path {
+            Some(path) => path.display().to_string(),
+            None => format!("{}:{}", self.host, super::get_port(self)),
+        }
+    }
 }
 
 impl ::std::fmt::Display for NodeAddress {
     fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
-        write!(f, "Host: `{}`, Port: {}", self.host, self.port)
+        match &self.unix_socket_path {
+            Some(path) => write!(f, "Unix socket: `{}`", path.display()),
+            None => write!(f, "Host: `{}`, Port: {}", self.host, self.port),
+        }
     }
 }
 
@@ -59,6 +119,7 @@ pub enum ReadFrom {
     #[default]
     Primary,
     PreferReplica,
+    LowestLatency,
     AZAffinity(String),
     AZAffinityReplicasAndPrimary(String),
 }
@@ -72,6 +133,47 @@ pub enum TlsMode {
     SecureTls,
 }
 
+/// The number of responses a client-side cache holds, when no maximum is configured.
+pub const DEFAULT_CLIENT_SIDE_CACHE_MAX_ENTRIES: usize = 10_000;
+
+/// Settings of the in-process cache for read commands.
+#[derive(Clone, Debug)]
+pub struct ClientSideCacheConfig {
+    /// The maximum number of cached responses.
+    pub max_entries: usize,
+    /// The maximum estimated memory of the cached responses, in bytes.
+    pub max_memory: Option<usize>,
+    /// The time after which a cached response expires, even if it wasn't invalidated.
+    pub ttl: Option<Duration>,
+    /// The `CLIENT TRACKING` mode that is enabled on every connection.
+    pub tracking_mode: redis::ClientTrackingMode,
+}
+
+/// Custom TLS settings, applied when `tls_mode` enables TLS.
+#[derive(PartialEq, Eq, Clone, Default)]
+pub struct TlsConfig {
+    /// PEM encoded CA certificates to trust instead of the platform's trust store.
+    pub root_certs: Option<Vec<u8>>,
+    /// PEM encoded client certificate chain, used for mutual TLS.
+    pub client_cert: Option<Vec<u8>>,
+    /// PEM encoded private key of the client certificate.
+    pub client_key: Option<Vec<u8>>,
+    /// The name to verify the servers' certificates against, instead of the host we connect to.
+    pub server_name: Option<String>,
+}
+
+impl ::std::fmt::Debug for TlsConfig {
+    fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+        // Certificates are omitted to keep logs short, and the private key must never be logged.
+        f.debug_struct("TlsConfig")
+            .field("root_certs", &self.root_certs.is_some())
+            .field("client_cert", &self.client_cert.is_some())
+            .field("client_key", &self.client_key.is_some())
+            .field("server_name", &self.server_name)
+            .finish()
+    }
+}
+
 #[derive(PartialEq, Eq, Clone, Copy, Debug)]
 #[repr(C)]
 pub struct ConnectionRetryStrategy {
@@ -90,18 +192,117 @@ fn chars_to_string_option(chars: &::protobuf::Chars) -> Option<String> {
     }
 }
 
+#[cfg(feature = "proto")]
+fn bytes_to_vec_option(bytes: &::bytes::Bytes) -> Option<Vec<u8>> {
+    if bytes.is_empty() {
+        None
+    } else {
+        Some(bytes.to_vec())
+    }
+}
+
+#[cfg(feature = "proto")]
+impl From<&crate::command_request::UpdateTlsCredentials> for TlsConfig {
+    fn from(value: &crate::command_request::UpdateTlsCredentials) -> Self {
+        TlsConfig {
+            root_certs: bytes_to_vec_option(&value.root_certs),
+            client_cert: bytes_to_vec_option(&value.client_cert),
+            client_key: bytes_to_vec_option(&value.client_key),
+            server_name: chars_to_string_option(&value.server_name),
+        }
+    }
+}
+
 #[cfg(feature = "proto")]
 pub(crate) fn none_if_zero(value: u32) -> Option<u32> {
     if value == 0 { None } else { Some(value) }
 }
 
+#[cfg(feature = "proto")]
+fn node_addresses_from_proto(addresses: Vec<protobuf::NodeAddress>) -> Vec<NodeAddress> {
+    addresses
+        .into_iter()
+        .map(|addr| NodeAddress {
+            host: addr.host.to_string(),
+            port: addr.port as u16,
+            unix_socket_path: chars_to_string_option(&addr.unix_socket_path).map(PathBuf::from),
+        })
+        .collect()
+}
+
+#[cfg(feature = "proto")]
+fn command_route_from_proto(
+    route: &protobuf::CommandRoute,
+) -> redis::cluster_routing::CommandRoute {
+    use protobuf::command_route::{Keys, MultiShardKeys, RequestPolicy, ResponsePolicy};
+    use redis::cluster_routing::{
+        AggregateOp, CommandKeys, CommandRoute, LogicalAggregateOp, MultiSlotArgPattern,
+        ResponsePolicy as Policy,
+    };
+
+    let response_policy = match route.response_policy.enum_value_or_default() {
+        ResponsePolicy::DefaultResponse => None,
+        ResponsePolicy::OneSucceeded => Some(Policy::OneSucceeded),
+        ResponsePolicy::AllSucceeded => Some(Policy::AllSucceeded),
+        ResponsePolicy::AggregateLogicalAnd => {
+            Some(Policy::AggregateLogical(LogicalAggregateOp::And))
+        }
+        ResponsePolicy::AggregateMin => Some(Policy::Aggregate(AggregateOp::Min)),
+        ResponsePolicy::AggregateSum => Some(Policy::Aggregate(AggregateOp::Sum)),
+        ResponsePolicy::CombineArrays => Some(Policy::CombineArrays),
+        ResponsePolicy::CombineMaps => Some(Policy::CombineMaps),
+        ResponsePolicy::Special => Some(Policy::Special),
+    };
+    let keys = match &route.keys {
+        Some(Keys::KeyIndex(index)) => CommandKeys::Index(*index as usize),
+        Some(Keys::KeyCountIndex(index)) => CommandKeys::AfterKeyCount(*index as usize),
+        Some(Keys::KeyKeyword(keyword)) => CommandKeys::AfterKeyword(keyword.as_bytes().to_vec()),
+        None => CommandKeys::None,
+    };
+    match route.request_policy.enum_value_or_default() {
+        RequestPolicy::ByKeys => CommandRoute::Keys {
+            keys,
+            readonly: route.readonly,
+        },
+        RequestPolicy::AllNodes => CommandRoute::AllNodes(response_policy),
+        RequestPolicy::AllPrimaries => CommandRoute::AllPrimaries(response_policy),
+        RequestPolicy::MultiShard => CommandRoute::MultiShard {
+            first_key: match keys {
+                CommandKeys::Index(index) => index,
+                _ => 1,
+            },
+            pattern: match route.multi_shard_keys.enum_value_or_default() {
+                MultiShardKeys::KeysOnly => MultiSlotArgPattern::KeysOnly,
+                MultiShardKeys::KeyValuePairs => MultiSlotArgPattern::KeyValuePairs,
+                MultiShardKeys::KeysAndLastArg => MultiSlotArgPattern::KeysAndLastArg,
+                MultiShardKeys::KeyWithTwoArgTriples => MultiSlotArgPattern::KeyWithTwoArgTriples,
+            },
+            readonly: route.readonly,
+            response_policy: response_policy.or(Some(Policy::CombineArrays)),
+        },
+    }
+}
+
+#[cfg(feature = "proto")]
+fn authentication_info_from_proto(
+    authentication_info: &protobuf::AuthenticationInfo,
+) -> Option<AuthenticationInfo> {
+    let password = chars_to_string_option(&authentication_info.password);
+    let username = chars_to_string_option(&authentication_info.username);
+    if password.is_none() && username.is_none() {
+        return None;
+    }
+
+    Some(AuthenticationInfo { password, username })
+}
+
 #[cfg(feature = "proto")]
 impl From<protobuf::ConnectionRequest> for ConnectionRequest {
     fn from(value: protobuf::ConnectionRequest) -> Self {
         let read_from = value.read_from.enum_value().ok().map(|val| match val {
             protobuf::ReadFrom::Primary => ReadFrom::Primary,
             protobuf::ReadFrom::PreferReplica => ReadFrom::PreferReplica,
-            protobuf::ReadFrom::LowestLatency => todo!(),
+            protobuf::ReadFrom::LowestLatency => ReadFrom::LowestLatency,
             protobuf::ReadFrom::AZAffinity => {
                 if let Some(client_az) = chars_to_string_option(&value.client_az) {
                     ReadFrom::AZAffinity(client_az)
@@ -133,20 +334,17 @@ impl From<protobuf::ConnectionRequest> for ConnectionRequest {
         });
 
         let client_name = chars_to_string_option(&value.client_name);
-        let authentication_info = value.authentication_info.0.and_then(|authentication_info| {
-            let password = chars_to_string_option(&authentication_info.password);
-            let username = chars_to_string_option(&authentication_info.username);
-            if password.is_none() && username.is_none() {
-                return None;
-            }
-
-            Some(AuthenticationInfo { password, username })
-        });
+        let authentication_info = value
+            .authentication_info
+            .0
+            .as_deref()
+            .and_then(authentication_info_from_proto);
 
         let database_id = value.database_id as i64;
         let protocol = value.protocol.enum_value().ok().map(|val| match val {
             protobuf::ProtocolVersion::RESP3 => redis::ProtocolVersion::RESP3,
             protobuf::ProtocolVersion::RESP2 => redis::ProtocolVersion::RESP2,
+            protobuf::ProtocolVersion::AUTO => redis::ProtocolVersion::Auto,
         });
 
         let tls_mode = value.tls_mode.enum_value().ok().map(|val| match val {
@@ -155,14 +353,7 @@ impl From<protobuf::ConnectionRequest> for ConnectionRequest {
             protobuf::TlsMode::InsecureTls => TlsMode::InsecureTls,
         });
 
-        let addresses = value
-            .addresses
-            .into_iter()
-            .map(|addr| NodeAddress {
-                host: addr.host.to_string(),
-                port: addr.port as u16,
-            })
-            .collect();
+        let addresses = node_addresses_from_proto(value.addresses);
         let cluster_mode_enabled = value.cluster_mode_enabled;
         let request_timeout = none_if_zero(value.request_timeout);
         let connection_timeout = none_if_zero(value.connection_timeout);
@@ -224,6 +415,102 @@ impl From<protobuf::ConnectionRequest> for ConnectionRequest {
 
         let inflight_requests_limit = none_if_zero(value.inflight_requests_limit);
         let lazy_connect = value.lazy_connect;
+        let sentinel = value.sentinel.0.map(|sentinel| SentinelConfig {
+            addresses: node_addresses_from_proto(sentinel.addresses),
+            master_name: sentinel.master_name.to_string(),
+            authentication_info: sentinel
+                .sentinel_authentication_info
+                .0
+                .as_deref()
+                .and_then(authentication_info_from_proto),
+        });
+        let tls_config = value.tls_config.0.map(|tls_config| TlsConfig {
+            root_certs: bytes_to_vec_option(&tls_config.root_certs),
+            client_cert: bytes_to_vec_option(&tls_config.client_cert),
+            client_key: bytes_to_vec_option(&tls_config.client_key),
+            server_name: chars_to_string_option(&tls_config.server_name),
+        });
+        let credential_provider = value.credentials_file.0.map(|credentials_file| {
+            Arc::new(super::FileCredentialProvider::new(
+                credentials_file.path.to_string(),
+                none_if_zero(credentials_file.refresh_interval)
+                    .map(|interval| Duration::from_millis(interval as u64)),
+            )) as Arc<dyn CredentialProvider>
+        });
+
+        let client_side_cache = value
+            .client_side_cache
+            .0
+            .map(|cache| ClientSideCacheConfig {
+                max_entries: none_if_zero(cache.max_entries)
+                    .map(|max_entries| max_entries as usize)
+                    .unwrap_or(DEFAULT_CLIENT_SIDE_CACHE_MAX_ENTRIES),
+                max_memory: (cache.max_memory != 0).then_some(cache.max_memory as usize),
+                ttl: none_if_zero(cache.ttl).map(|ttl| Duration::from_millis(ttl as u64)),
+                tracking_mode: match cache.tracking_mode.enum_value_or_default() {
+                    protobuf::ClientTrackingMode::Default => redis::ClientTrackingMode::Default,
+                    protobuf::ClientTrackingMode::Broadcast => redis::ClientTrackingMode::Broadcast,
+                },
+            });
+        let connections_per_node = none_if_zero(value.connections_per_node);
+        let address_remap = value.address_remap.0.map(|address_remap| {
+            let remap = address_remap.addresses.iter().fold(
+                redis::cluster::AddressRemap::new(),
+                |remap, (announced, reachable)| {
+                    remap.address(announced.to_string(), reachable.to_string())
+                },
+            );
+            address_remap.host_rules.iter().fold(remap, |remap, rule| {
+                remap.host_rule(rule.pattern.to_string(), rule.host.to_string())
+            })
+        });
+        let circuit_breaker_threshold = none_if_zero(value.circuit_breaker_threshold);
+        let read_hedging = value
+            .read_hedging
+            .0
+            .and_then(|read_hedging| read_hedging.delay)
+            .map(|delay| match delay {
+                protobuf::read_hedging::Delay::FixedDelay(delay) => {
+                    redis::HedgePolicy::Delay(Duration::from_millis(delay as u64))
+                }
+                protobuf::read_hedging::Delay::Percentile(percentile) => {
+                    redis::HedgePolicy::Percentile(percentile)
+                }
+            });
+        let connection_error_retry = value.connection_error_retry.0.map(|retry| {
+            retry.idempotent_commands.iter().fold(
+                redis::cluster_routing::ConnectionErrorRetryPolicy::new(retry.max_retries),
+                |policy, command| policy.idempotent_command(command),
+            )
+        });
+        let command_routing_table = value.command_routing.0.map(|command_routing| {
+            Arc::new(command_routing.routes.iter().fold(
+                redis::cluster_routing::CommandRoutingTable::new(command_routing.discover),
+                |table, route| table.register(&route.command, command_route_from_proto(route)),
+            ))
+        });
+
+        let key_prefix = (!value.key_prefix.is_empty()).then(|| value.key_prefix.to_vec());
+        let command_policy = value.command_policy.0.map(|policy| {
+            let list = if policy.allowlist {
+                CommandPolicy::allowlist()
+            } else {
+                CommandPolicy::denylist()
+            };
+            let list = policy
+                .request_types
+                .iter()
+                .fold(list, |list, request_type| {
+                    list.request_type(crate::request_type::RequestType::from(
+                        ::protobuf::EnumOrUnknown::from_i32(*request_type as i32),
+                    ))
+                });
+            policy
+                .commands
+                .iter()
+                .fold(list, |list, command| list.command(command))
+                .read_only(policy.read_only)
+        });
 
         ConnectionRequest {
             read_from,
@@ -241,6 +528,19 @@ impl From<protobuf::ConnectionRequest> for ConnectionRequest {
             pubsub_subscriptions,
             inflight_requests_limit,
             lazy_connect,
+            sentinel,
+            tls_config,
+            credential_provider,
+            client_side_cache,
+            connections_per_node,
+            address_remap,
+            circuit_breaker_threshold,
+            read_hedging,
+            connection_error_retry,
+            command_routing_table,
+            key_prefix,
+            command_policy,
+            middlewares: Vec::new(),
         }
     }
 }
@@ -25,6 +25,7 @@ pub(crate) enum ExpectedReturnType<'a> {
     JsonToggleReturnType,
     ArrayOfStrings,
     ArrayOfBools,
+    ArrayOfInts,
     ArrayOfDoubleOrNull,
     FTAggregateReturnType,
     FTSearchReturnType,
@@ -193,6 +194,21 @@ pub(crate) fn convert_to_expected_type(
             )
                 .into()),
         },
+        // Hash field expiration commands reply with an integer per field, e.g. its TTL or a status code.
+        ExpectedReturnType::ArrayOfInts => match value {
+            Value::Nil => Ok(value),
+            Value::Array(array) => array
+                .into_iter()
+                .map(|item| Ok(Value::Int(from_owned_redis_value::<i64>(item)?)))
+                .collect::<RedisResult<_>>()
+                .map(Value::Array),
+            _ => Err((
+                ErrorKind::TypeError,
+                "Response couldn't be converted to an array of integers",
+                format!("(response was {:?})", get_value_type(&value)),
+            )
+                .into()),
+        },
         ExpectedReturnType::ArrayOfStrings => match value {
             Value::Array(array) => convert_array_elements(array, ExpectedReturnType::BulkString),
             _ => Err((
@@ -1466,6 +1482,8 @@ pub(crate) fn expected_type_for_cmd(cmd: &Cmd) -> Option<ExpectedReturnType> {
         | b"XGROUP DESTROY"
         | b"XGROUP CREATECONSUMER" => Some(ExpectedReturnType::Boolean),
         b"SMISMEMBER" | b"SCRIPT EXISTS" => Some(ExpectedReturnType::ArrayOfBools),
+        b"HEXPIRE" | b"HPEXPIRE" | b"HEXPIREAT" | b"HPEXPIREAT" | b"HPERSIST" | b"HTTL"
+        | b"HPTTL" | b"HEXPIRETIME" | b"HPEXPIRETIME" => Some(ExpectedReturnType::ArrayOfInts),
         b"SMEMBERS" | b"SINTER" | b"SDIFF" | b"SUNION" => Some(ExpectedReturnType::Set),
         b"ZSCORE" | b"GEODIST" => Some(ExpectedReturnType::DoubleOrNull),
         b"ZMSCORE" => Some(ExpectedReturnType::ArrayOfDoubleOrNull),
@@ -2729,6 +2747,51 @@ mod tests {
         assert_eq!(expected_response, converted_response);
     }
 
+    #[test]
+    fn convert_hash_field_expiration_commands() {
+        for command in [
+            "HEXPIRE",
+            "HPEXPIRE",
+            "HEXPIREAT",
+            "HPEXPIREAT",
+            "HPERSIST",
+            "HTTL",
+            "HPTTL",
+            "HEXPIRETIME",
+            "HPEXPIRETIME",
+        ] {
+            assert!(matches!(
+                expected_type_for_cmd(redis::cmd(command).arg("key").arg("FIELDS").arg(1)),
+                Some(ExpectedReturnType::ArrayOfInts)
+            ));
+        }
+        assert!(expected_type_for_cmd(redis::cmd("HGETEX").arg("key")).is_none());
+
+        let response = Value::Array(vec![
+            Value::Int(-2),
+            Value::BulkString(b"100".to_vec()),
+            Value::SimpleString("1".to_string()),
+        ]);
+        assert_eq!(
+            convert_to_expected_type(response, Some(ExpectedReturnType::ArrayOfInts)).unwrap(),
+            Value::Array(vec![Value::Int(-2), Value::Int(100), Value::Int(1)])
+        );
+        assert_eq!(
+            convert_to_expected_type(Value::Nil, Some(ExpectedReturnType::ArrayOfInts)).unwrap(),
+            Value::Nil
+        );
+        assert!(
+            convert_to_expected_type(
+                Value::Array(vec![Value::BulkString(b"field".to_vec())]),
+                Some(ExpectedReturnType::ArrayOfInts)
+            )
+            .is_err()
+        );
+        assert!(
+            convert_to_expected_type(Value::Int(1), Some(ExpectedReturnType::ArrayOfInts)).is_err()
+        );
+    }
+
     #[test]
     fn convert_to_array_of_pairs_return_type() {
         assert!(matches!(
@@ -9,6 +9,10 @@ pub enum RequestErrorType {
     ExecAbort = 1,
     Timeout = 2,
     Disconnect = 3,
+    /// The request was cancelled by the caller before it completed.
+    Cancelled = 4,
+    /// The command was rejected by the command policy of the client, and wasn't sent.
+    CommandNotAllowed = 5,
 }
 
 pub fn error_type(error: &RedisError) -> RequestErrorType {
@@ -18,6 +22,8 @@ pub fn error_type(error: &RedisError) -> RequestErrorType {
         RequestErrorType::Disconnect
     } else if matches!(error.kind(), redis::ErrorKind::ExecAbortError) {
         RequestErrorType::ExecAbort
+    } else if matches!(error.kind(), redis::ErrorKind::CommandNotAllowed) {
+        RequestErrorType::CommandNotAllowed
     } else {
         RequestErrorType::Unspecified
     }
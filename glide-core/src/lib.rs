@@ -13,6 +13,7 @@ pub mod errors;
 pub mod scripts_container;
 pub use client::ConnectionRequest;
 pub mod cluster_scan_container;
+pub mod request_statistics;
 pub mod request_type;
 pub use telemetrylib::{
     DEFAULT_FLUSH_SIGNAL_INTERVAL_MS, DEFAULT_TRACE_SAMPLE_PERCENTAGE, GlideOpenTelemetry,
@@ -0,0 +1,276 @@
+// Copyright Valkey GLIDE Project Contributors - SPDX Identifier: Apache-2.0
+
+//! Statistics of the requests that the clients of this process send, comparable to the ones that
+//! `INFO commandstats` and `INFO latencystats` report, and of the nodes that the requests are sent to.
+//! The statistics are only collected after [`set_request_statistics_enabled`] enabled them.
+
+use crate::errors::{RequestErrorType, error_type};
+use crate::request_type::RequestType;
+use dashmap::DashMap;
+use once_cell::sync::Lazy;
+use redis::RedisResult;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::future::Future;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::Instant;
+use telemetrylib::{NodeStatistics, Telemetry};
+
+/// The latency percentiles that are reported for each request kind.
+const LATENCY_PERCENTILES: [(&str, f64); 4] =
+    [("p50", 50.0), ("p90", 90.0), ("p99", 99.0), ("p99.9", 99.9)];
+
+/// The error types that the failed requests are counted by, indexed by their values.
+const ERROR_TYPES: [RequestErrorType; 6] = [
+    RequestErrorType::Unspecified,
+    RequestErrorType::ExecAbort,
+    RequestErrorType::Timeout,
+    RequestErrorType::Disconnect,
+    RequestErrorType::Cancelled,
+    RequestErrorType::CommandNotAllowed,
+];
+
+// Every power of two is split into 2^SUB_BUCKET_BITS buckets, so a latency is recorded within 1/64 of its value.
+const SUB_BUCKET_BITS: u32 = 6;
+const SUB_BUCKET_COUNT: u64 = 1 << SUB_BUCKET_BITS;
+
+/// A histogram of latencies in microseconds, with log-linear buckets like an HDR histogram.
+/// Only the buckets that were recorded are stored.
+#[derive(Debug, Default)]
+struct LatencyHistogram {
+    buckets: BTreeMap<u32, u64>,
+    count: u64,
+}
+
+impl LatencyHistogram {
+    fn bucket_index(value: u64) -> u32 {
+        if value < SUB_BUCKET_COUNT {
+            return value as u32;
+        }
+        let magnitude = u64::BITS - 1 - value.leading_zeros() - SUB_BUCKET_BITS;
+        let sub_bucket = (value >> magnitude) - SUB_BUCKET_COUNT;
+        (magnitude + 1) * SUB_BUCKET_COUNT as u32 + sub_bucket as u32
+    }
+
+    // Returns the highest value that is recorded in the bucket at `index`.
+    fn bucket_highest_value(index: u32) -> u64 {
+        let index = u64::from(index);
+        if index < SUB_BUCKET_COUNT {
+            return index;
+        }
+        let magnitude = index / SUB_BUCKET_COUNT - 1;
+        let lowest = (SUB_BUCKET_COUNT + index % SUB_BUCKET_COUNT) << magnitude;
+        lowest + (1 << magnitude) - 1
+    }
+
+    fn record(&mut self, usec: u64) {
+        *self.buckets.entry(Self::bucket_index(usec)).or_default() += 1;
+        self.count += 1;
+    }
+
+    // Returns the latency, in microseconds, that `percentile` percent of the recorded latencies don't exceed,
+    // or 0 if no latency was recorded.
+    fn percentile(&self, percentile: f64) -> u64 {
+        let rank = ((percentile / 100.0) * self.count as f64).ceil().max(1.0) as u64;
+        let mut seen = 0;
+        for (index, count) in &self.buckets {
+            seen += count;
+            if seen >= rank {
+                return Self::bucket_highest_value(*index);
+            }
+        }
+        self.buckets
+            .last_key_value()
+            .map_or(0, |(index, _)| Self::bucket_highest_value(*index))
+    }
+}
+
+/// The kind of a request, which the request statistics are collected by.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum RequestKind {
+    /// A single command, including cluster scans and script invocations
+    Command(RequestType),
+    /// An atomic batch
+    Transaction,
+    /// A non-atomic batch
+    Pipeline,
+}
+
+impl RequestKind {
+    fn name(&self) -> String {
+        match self {
+            RequestKind::Command(request_type) => format!("{request_type:?}"),
+            RequestKind::Transaction => "Transaction".to_string(),
+            RequestKind::Pipeline => "Pipeline".to_string(),
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct RequestCounters {
+    calls: AtomicU64,
+    errors: [AtomicU64; ERROR_TYPES.len()],
+    usec: AtomicU64,
+    latency: Mutex<LatencyHistogram>,
+}
+
+/// The statistics of a request kind.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct CommandStatistics {
+    /// The number of calls, including the failed ones
+    pub calls: u64,
+    /// The number of failed calls, by the type of their errors
+    pub errors: BTreeMap<String, u64>,
+    /// The total latency of the calls, in microseconds
+    pub usec: u64,
+    /// The average latency of a call, in microseconds
+    pub usec_per_call: f64,
+    /// The latency percentiles of the calls in microseconds, e.g. `p99`
+    pub latency_percentiles_usec: BTreeMap<String, u64>,
+}
+
+impl From<&RequestCounters> for CommandStatistics {
+    fn from(counters: &RequestCounters) -> Self {
+        let calls = counters.calls.load(Ordering::Relaxed);
+        let usec = counters.usec.load(Ordering::Relaxed);
+        let latency = counters
+            .latency
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        Self {
+            calls,
+            errors: ERROR_TYPES
+                .iter()
+                .zip(&counters.errors)
+                .map(|(error_type, count)| (error_type, count.load(Ordering::Relaxed)))
+                .filter(|(_, count)| *count > 0)
+                .map(|(error_type, count)| (format!("{error_type:?}"), count))
+                .collect(),
+            usec,
+            usec_per_call: if calls == 0 {
+                0.0
+            } else {
+                usec as f64 / calls as f64
+            },
+            latency_percentiles_usec: LATENCY_PERCENTILES
+                .iter()
+                .map(|(name, percentile)| (name.to_string(), latency.percentile(*percentile)))
+                .collect(),
+        }
+    }
+}
+
+/// The request and node statistics collected thus far.
+#[derive(Debug, Default, Clone, PartialEq, Serialize)]
+pub struct RequestStatistics {
+    /// The statistics of the requests, by the names of their kinds, e.g. `Get`, `CustomCommand` or `Transaction`
+    pub commands: BTreeMap<String, CommandStatistics>,
+    /// The statistics of the nodes, by their addresses
+    pub nodes: BTreeMap<String, NodeStatistics>,
+}
+
+impl RequestStatistics {
+    /// Returns the statistics as a JSON object, for the wrappers to parse
+    pub fn to_json(&self) -> String {
+        serde_json::to_string(self).expect("Failed to serialize the request statistics")
+    }
+}
+
+static REQUEST_STATISTICS: Lazy<DashMap<RequestKind, RequestCounters>> = Lazy::new(DashMap::new);
+
+/// Enables or disables the collection of the request statistics. They aren't collected by default.
+pub fn set_request_statistics_enabled(enabled: bool) {
+    Telemetry::set_statistics_enabled(enabled);
+}
+
+/// Sends `request` and records it in the request statistics under `kind`, with its latency and error type.
+pub async fn record_request<T>(
+    kind: RequestKind,
+    request: impl Future<Output = RedisResult<T>>,
+) -> RedisResult<T> {
+    if !Telemetry::statistics_enabled() {
+        return request.await;
+    }
+    let started = Instant::now();
+    let result = request.await;
+    record(kind, started, result.as_ref().err().map(error_type));
+    result
+}
+
+/// Records a request that was cancelled before it completed under `kind`, with the time since it `started` as its
+/// latency. A cancelled request isn't recorded by [`record_request`], since its future is dropped.
+pub fn record_cancelled_request(kind: RequestKind, started: Instant) {
+    if Telemetry::statistics_enabled() {
+        record(kind, started, Some(RequestErrorType::Cancelled));
+    }
+}
+
+fn record(kind: RequestKind, started: Instant, error_type: Option<RequestErrorType>) {
+    let usec = u64::try_from(started.elapsed().as_micros()).unwrap_or(u64::MAX);
+    let counters = REQUEST_STATISTICS.entry(kind).or_default();
+    counters.calls.fetch_add(1, Ordering::Relaxed);
+    counters.usec.fetch_add(usec, Ordering::Relaxed);
+    if let Some(error_type) = error_type {
+        counters.errors[error_type as usize].fetch_add(1, Ordering::Relaxed);
+    }
+    counters
+        .latency
+        .lock()
+        .unwrap_or_else(|poisoned| poisoned.into_inner())
+        .record(usec);
+}
+
+/// Returns the request and node statistics collected thus far.
+pub fn request_statistics() -> RequestStatistics {
+    RequestStatistics {
+        commands: REQUEST_STATISTICS
+            .iter()
+            .map(|entry| (entry.key().name(), entry.value().into()))
+            .collect(),
+        nodes: Telemetry::node_statistics(),
+    }
+}
+
+/// Resets the request and node statistics collected thus far.
+pub fn reset_request_statistics() {
+    REQUEST_STATISTICS.clear();
+    Telemetry::reset_node_statistics();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_latency_histogram_buckets() {
+        for value in [0, 1, 63, 64, 65, 127, 128, 1000, 123_456, u64::MAX / 2] {
+            let highest =
+                LatencyHistogram::bucket_highest_value(LatencyHistogram::bucket_index(value));
+            assert!(highest >= value);
+            assert!(highest - value <= value / SUB_BUCKET_COUNT);
+        }
+        assert_eq!(
+            LatencyHistogram::bucket_index(128),
+            LatencyHistogram::bucket_index(129)
+        );
+        assert_ne!(
+            LatencyHistogram::bucket_index(127),
+            LatencyHistogram::bucket_index(128)
+        );
+    }
+
+    #[test]
+    fn test_latency_histogram_percentiles() {
+        let mut histogram = LatencyHistogram::default();
+        assert_eq!(histogram.percentile(50.0), 0);
+        for usec in 1..=1000 {
+            histogram.record(usec);
+        }
+        assert_eq!(histogram.count, 1000);
+        for (percentile, expected) in [(50.0, 500), (99.0, 990), (100.0, 1000)] {
+            let value = histogram.percentile(percentile);
+            assert!(value >= expected && value - expected <= expected / SUB_BUCKET_COUNT);
+        }
+    }
+}
@@ -6,7 +6,7 @@ use redis::{Cmd, cmd};
 use crate::command_request::RequestType as ProtobufRequestType;
 
 #[repr(C)]
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 pub enum RequestType {
     /// Invalid request type
     InvalidRequest = 0,
@@ -144,6 +144,17 @@ pub enum RequestType {
     HSetNX = 614,
     HStrlen = 615,
     HVals = 616,
+    HExpire = 617,
+    HExpireAt = 618,
+    HExpireTime = 619,
+    HGetEx = 620,
+    HPersist = 621,
+    HPExpire = 622,
+    HPExpireAt = 623,
+    HPExpireTime = 624,
+    HPTTL = 625,
+    HSetEx = 626,
+    HTTL = 627,
 
     //// HyperLogLog commands
     PfAdd = 701,
@@ -520,6 +531,17 @@ impl From<::protobuf::EnumOrUnknown<ProtobufRequestType>> for RequestType {
             ProtobufRequestType::HSetNX => RequestType::HSetNX,
             ProtobufRequestType::SIsMember => RequestType::SIsMember,
             ProtobufRequestType::HVals => RequestType::HVals,
+            ProtobufRequestType::HExpire => RequestType::HExpire,
+            ProtobufRequestType::HExpireAt => RequestType::HExpireAt,
+            ProtobufRequestType::HExpireTime => RequestType::HExpireTime,
+            ProtobufRequestType::HGetEx => RequestType::HGetEx,
+            ProtobufRequestType::HPersist => RequestType::HPersist,
+            ProtobufRequestType::HPExpire => RequestType::HPExpire,
+            ProtobufRequestType::HPExpireAt => RequestType::HPExpireAt,
+            ProtobufRequestType::HPExpireTime => RequestType::HPExpireTime,
+            ProtobufRequestType::HPTTL => RequestType::HPTTL,
+            ProtobufRequestType::HSetEx => RequestType::HSetEx,
+            ProtobufRequestType::HTTL => RequestType::HTTL,
             ProtobufRequestType::PTTL => RequestType::PTTL,
             ProtobufRequestType::ZRemRangeByRank => RequestType::ZRemRangeByRank,
             ProtobufRequestType::Persist => RequestType::Persist,
@@ -782,6 +804,17 @@ impl RequestType {
             RequestType::HSetNX => Some(cmd("HSETNX")),
             RequestType::SIsMember => Some(cmd("SISMEMBER")),
             RequestType::HVals => Some(cmd("HVALS")),
+            RequestType::HExpire => Some(cmd("HEXPIRE")),
+            RequestType::HExpireAt => Some(cmd("HEXPIREAT")),
+            RequestType::HExpireTime => Some(cmd("HEXPIRETIME")),
+            RequestType::HGetEx => Some(cmd("HGETEX")),
+            RequestType::HPersist => Some(cmd("HPERSIST")),
+            RequestType::HPExpire => Some(cmd("HPEXPIRE")),
+            RequestType::HPExpireAt => Some(cmd("HPEXPIREAT")),
+            RequestType::HPExpireTime => Some(cmd("HPEXPIRETIME")),
+            RequestType::HPTTL => Some(cmd("HPTTL")),
+            RequestType::HSetEx => Some(cmd("HSETEX")),
+            RequestType::HTTL => Some(cmd("HTTL")),
             RequestType::PTTL => Some(cmd("PTTL")),
             RequestType::ZRemRangeByRank => Some(cmd("ZREMRANGEBYRANK")),
             RequestType::Persist => Some(cmd("PERSIST")),
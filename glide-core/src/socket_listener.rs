@@ -5,10 +5,13 @@ use crate::client::Client;
 use crate::client::get_or_init_runtime;
 use crate::cluster_scan_container::get_cluster_scan_cursor;
 use crate::command_request::{
-    Batch, ClusterScan, Command, CommandRequest, Routes, SlotTypes, command, command_request,
+    Batch, ClusterScan, Command, CommandRequest, PubSubChannelKind, Routes, SlotTypes, command,
+    command_request,
 };
 use crate::connection_request::ConnectionRequest;
 use crate::errors::{RequestErrorType, error_message, error_type};
+use crate::request_statistics::{RequestKind, record_cancelled_request, record_request};
+use crate::request_type::RequestType;
 use crate::response;
 use crate::response::Response;
 use ClosingReason::*;
@@ -23,10 +26,11 @@ use redis::cluster_routing::{
 };
 use redis::cluster_routing::{ResponsePolicy, Routable};
 use redis::{
-    ClusterScanArgs, Cmd, PipelineRetryStrategy, PushInfo, RedisError, ScanStateRC, Value,
+    ClusterScanArgs, Cmd, PipelineRetryStrategy, PubSubSubscriptionKind, PushInfo, RedisError,
+    ScanStateRC, Value,
 };
-use std::cell::Cell;
-use std::collections::HashSet;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
 use std::fs;
 use std::io;
 use std::os::unix::fs::PermissionsExt;
@@ -34,6 +38,7 @@ use std::ptr::from_mut;
 use std::rc::Rc;
 use std::str;
 use std::sync::{Arc, RwLock};
+use std::time::Instant;
 use telemetrylib::{GlideSpan, GlideSpanStatus};
 use thiserror::Error;
 use tokio::net::{UnixListener, UnixStream};
@@ -73,6 +78,28 @@ struct Writer {
     closing_sender: Sender<ClosingReason>,
 }
 
+/// A request that is being handled, and may be cancelled.
+struct InflightRequest {
+    task: task::JoinHandle<()>,
+    root_span_ptr: Option<u64>,
+    /// The kind that the request is recorded under in the request statistics, if it's recorded.
+    kind: Option<RequestKind>,
+    started: Instant,
+}
+
+/// The requests of a socket's client that are being handled, by their callback index.
+type InflightRequests = Rc<RefCell<HashMap<u32, InflightRequest>>>;
+
+/// Holds one of the client's inflight request slots, and releases it when dropped,
+/// including when the request's task is aborted.
+struct InflightRequestSlot(Client);
+
+impl Drop for InflightRequestSlot {
+    fn drop(&mut self) {
+        self.0.release_inflight_request();
+    }
+}
+
 enum PipeListeningResult<TRequest: Message> {
     Closed(ClosingReason),
     ReceivedValues(Vec<TRequest>),
@@ -233,6 +260,22 @@ async fn write_result(
             };
             Some(response::response::Value::RequestError(request_error))
         }
+        Err(ClientUsageError::Cancelled) => {
+            log_debug(
+                "request cancelled",
+                format!("for callback {callback_index}"),
+            );
+            let error_message = ClientUsageError::Cancelled.to_string();
+            if let Some(span) = otel_command_span {
+                span.set_status(GlideSpanStatus::Error((&error_message).into()));
+            }
+            let request_error = response::RequestError {
+                type_: response::RequestErrorType::Cancelled.into(),
+                message: error_message.into(),
+                ..Default::default()
+            };
+            Some(response::response::Value::RequestError(request_error))
+        }
         Err(ClientUsageError::Redis(err)) => {
             let error_message = error_message(&err);
             log_warn("received error", error_message.as_str());
@@ -246,6 +289,10 @@ async fn write_result(
                     RequestErrorType::ExecAbort => response::RequestErrorType::ExecAbort,
                     RequestErrorType::Timeout => response::RequestErrorType::Timeout,
                     RequestErrorType::Disconnect => response::RequestErrorType::Disconnect,
+                    RequestErrorType::Cancelled => response::RequestErrorType::Cancelled,
+                    RequestErrorType::CommandNotAllowed => {
+                        response::RequestErrorType::CommandNotAllowed
+                    }
                 }
                 .into(),
                 message: error_message.into(),
@@ -324,12 +371,16 @@ async fn send_command(
     cmd: Cmd,
     mut client: Client,
     routing: Option<RoutingInfo>,
+    timeout: Option<u32>,
+    request_type: RequestType,
 ) -> ClientUsageResult<Value> {
     let child_span = create_child_span(cmd.span().as_ref(), "send_command");
-    let res = client
-        .send_command(&cmd, routing)
-        .await
-        .map_err(|err| err.into());
+    let res = record_request(
+        RequestKind::Command(request_type),
+        client.send_command_with_timeout(&cmd, routing, timeout),
+    )
+    .await
+    .map_err(|err| err.into());
 
     if let Some(c) = child_span {
         c.end()
@@ -338,7 +389,11 @@ async fn send_command(
 }
 
 // Parse the cluster scan command parameters from protobuf and send the command to redis-rs.
-async fn cluster_scan(cluster_scan: ClusterScan, mut client: Client) -> ClientUsageResult<Value> {
+async fn cluster_scan(
+    cluster_scan: ClusterScan,
+    mut client: Client,
+    timeout: Option<u32>,
+) -> ClientUsageResult<Value> {
     // Since we don't send the cluster scan as a usual command, but through a special function in redis-rs library,
     // we need to handle the command separately.
     // Specifically, we need to handle the cursor, which is not the cursor returned from the server,
@@ -365,10 +420,12 @@ async fn cluster_scan(cluster_scan: ClusterScan, mut client: Client) -> ClientUs
     }
     let cluster_scan_args = cluster_scan_args_builder.build();
 
-    client
-        .cluster_scan(&cluster_scan_cursor, cluster_scan_args)
-        .await
-        .map_err(|err| err.into())
+    record_request(
+        RequestKind::Command(RequestType::Scan),
+        client.cluster_scan(&cluster_scan_cursor, cluster_scan_args, timeout),
+    )
+    .await
+    .map_err(|err| err.into())
 }
 
 async fn invoke_script(
@@ -377,6 +434,7 @@ async fn invoke_script(
     args: Option<Vec<Bytes>>,
     mut client: Client,
     routing: Option<RoutingInfo>,
+    timeout: Option<u32>,
 ) -> ClientUsageResult<Value> {
     // convert Vec<bytes> to vec<[u8]>
     let keys: Vec<&[u8]> = keys
@@ -388,10 +446,12 @@ async fn invoke_script(
         .map(|keys| keys.iter().map(|e| e.as_ref()).collect())
         .unwrap_or_default();
 
-    client
-        .invoke_script(&hash, &keys, &args, routing)
-        .await
-        .map_err(|err| err.into())
+    record_request(
+        RequestKind::Command(RequestType::EvalSha),
+        client.invoke_script(&hash, &keys, &args, routing, timeout),
+    )
+    .await
+    .map_err(|err| err.into())
 }
 
 /// Creates a child span for telemetry if telemetry is enabled
@@ -429,17 +489,20 @@ async fn send_batch(
     }
 
     let res = match request.is_atomic {
-        true => client
-            .send_transaction(
+        true => record_request(
+            RequestKind::Transaction,
+            client.send_transaction(
                 &pipeline,
                 routing,
                 request.timeout,
                 request.raise_on_error.unwrap_or_default(),
-            )
-            .await
-            .map_err(|err| err.into()),
-        false => client
-            .send_pipeline(
+            ),
+        )
+        .await
+        .map_err(|err| err.into()),
+        false => record_request(
+            RequestKind::Pipeline,
+            client.send_pipeline(
                 &pipeline,
                 routing,
                 request.raise_on_error.unwrap_or_default(),
@@ -448,9 +511,10 @@ async fn send_batch(
                     retry_server_error: request.retry_server_error.unwrap_or_default(),
                     retry_connection_error: request.retry_connection_error.unwrap_or_default(),
                 },
-            )
-            .await
-            .map_err(|err| err.into()),
+            ),
+        )
+        .await
+        .map_err(|err| err.into()),
     };
 
     if let Some(c) = child_span {
@@ -469,9 +533,43 @@ fn get_slot_addr(slot_type: &protobuf::EnumOrUnknown<SlotTypes>) -> ClientUsageR
         .map_err(|id| ClientUsageError::Internal(format!("Received unexpected slot id type {id}")))
 }
 
+fn get_pubsub_kind(
+    kind: &protobuf::EnumOrUnknown<PubSubChannelKind>,
+) -> ClientUsageResult<PubSubSubscriptionKind> {
+    kind.enum_value()
+        .map(|kind| match kind {
+            PubSubChannelKind::Exact => PubSubSubscriptionKind::Exact,
+            PubSubChannelKind::Pattern => PubSubSubscriptionKind::Pattern,
+            PubSubChannelKind::Sharded => PubSubSubscriptionKind::Sharded,
+        })
+        .map_err(|id| {
+            ClientUsageError::Internal(format!("Received unexpected pubsub channel kind {id}"))
+        })
+}
+
+async fn update_subscriptions(
+    kind: &protobuf::EnumOrUnknown<PubSubChannelKind>,
+    channels_or_patterns: Vec<Bytes>,
+    subscribe: bool,
+    mut client: Client,
+) -> ClientUsageResult<Value> {
+    let kind = get_pubsub_kind(kind)?;
+    let channels_or_patterns = channels_or_patterns
+        .into_iter()
+        .map(|channel| channel.to_vec())
+        .collect();
+    let result = if subscribe {
+        client.subscribe(kind, channels_or_patterns).await
+    } else {
+        client.unsubscribe(kind, channels_or_patterns).await
+    };
+    result.map_err(|err| err.into())
+}
+
 fn get_route(
     route: Option<Box<Routes>>,
     cmd: Option<&Cmd>,
+    client: &Client,
 ) -> ClientUsageResult<Option<RoutingInfo>> {
     use crate::command_request::routes::Value;
     let Some(route) = route.and_then(|route| route.value) else {
@@ -505,7 +603,7 @@ fn get_route(
         }
         Value::SlotKeyRoute(slot_key_route) => Ok(Some(RoutingInfo::SingleNode(
             SingleNodeRoutingInfo::SpecificNode(Route::new(
-                redis::cluster_topology::get_slot(slot_key_route.slot_key.as_bytes()),
+                client.key_slot(slot_key_route.slot_key.as_bytes()),
                 get_slot_addr(&slot_key_route.slot_type)?,
             )),
         ))),
@@ -530,30 +628,54 @@ fn get_route(
     }
 }
 
-fn handle_request(request: CommandRequest, mut client: Client, writer: Rc<Writer>) {
-    task::spawn_local(async move {
-        let mut updated_inflight_counter = true;
-        let client_clone = client.clone();
-
-        let result = match client.reserve_inflight_request() {
-            false => {
-                updated_inflight_counter = false;
-                Err(ClientUsageError::User(
-                    "Reached maximum inflight requests".to_string(),
-                ))
-            }
-            true => match request.command {
+fn handle_request(
+    request: CommandRequest,
+    mut client: Client,
+    writer: Rc<Writer>,
+    inflight_requests: InflightRequests,
+) {
+    if let Some(command_request::Command::CancelRequest(cancel_request)) = &request.command {
+        cancel_inflight_request(
+            cancel_request.callback_idx,
+            request.callback_idx,
+            &inflight_requests,
+            writer,
+        );
+        return;
+    }
+
+    let callback_idx = request.callback_idx;
+    let root_span_ptr = request.root_span_ptr;
+    let kind = request.command.as_ref().and_then(request_kind);
+    let requests = inflight_requests.clone();
+    let task = task::spawn_local(async move {
+        let inflight_slot = client
+            .reserve_inflight_request()
+            .then(|| InflightRequestSlot(client.clone()));
+
+        let result = match &inflight_slot {
+            None => Err(ClientUsageError::User(
+                "Reached maximum inflight requests".to_string(),
+            )),
+            Some(_) => match request.command {
                 Some(action) => match action {
                     command_request::Command::ClusterScan(cluster_scan_command) => {
                         //TODO: handle scan command - https://github.com/valkey-io/valkey-glide/issues/3506
-                        cluster_scan(cluster_scan_command, client).await
+                        cluster_scan(cluster_scan_command, client, request.timeout).await
                     }
                     command_request::Command::SingleCommand(command) => {
                         match get_redis_command(&command) {
-                            Ok(mut cmd) => match get_route(request.route.0, Some(&cmd)) {
+                            Ok(mut cmd) => match get_route(request.route.0, Some(&cmd), &client) {
                                 Ok(routes) => {
                                     cmd.set_span(get_unsafe_span_from_ptr(request.root_span_ptr));
-                                    send_command(cmd, client, routes).await
+                                    send_command(
+                                        cmd,
+                                        client,
+                                        routes,
+                                        request.timeout,
+                                        command.request_type.into(),
+                                    )
+                                    .await
                                 }
                                 Err(e) => Err(e),
                             },
@@ -561,7 +683,7 @@ fn handle_request(request: CommandRequest, mut client: Client, writer: Rc<Writer
                         }
                     }
                     command_request::Command::Batch(batch) => {
-                        match get_route(request.route.0, None) {
+                        match get_route(request.route.0, None, &client) {
                             Ok(routes) => {
                                 let otel_command_span =
                                     get_unsafe_span_from_ptr(request.root_span_ptr);
@@ -572,7 +694,7 @@ fn handle_request(request: CommandRequest, mut client: Client, writer: Rc<Writer
                     }
 
                     command_request::Command::ScriptInvocation(script) => {
-                        match get_route(request.route.0, None) {
+                        match get_route(request.route.0, None, &client) {
                             Ok(routes) => {
                                 invoke_script(
                                     script.hash,
@@ -580,6 +702,7 @@ fn handle_request(request: CommandRequest, mut client: Client, writer: Rc<Writer
                                     Some(script.args),
                                     client,
                                     routes,
+                                    request.timeout,
                                 )
                                 .await
                             }
@@ -593,9 +716,17 @@ fn handle_request(request: CommandRequest, mut client: Client, writer: Rc<Writer
                         let args = script
                             .args_pointer
                             .map(|pointer| *unsafe { Box::from_raw(pointer as *mut Vec<Bytes>) });
-                        match get_route(request.route.0, None) {
+                        match get_route(request.route.0, None, &client) {
                             Ok(routes) => {
-                                invoke_script(script.hash, keys, args, client, routes).await
+                                invoke_script(
+                                    script.hash,
+                                    keys,
+                                    args,
+                                    client,
+                                    routes,
+                                    request.timeout,
+                                )
+                                .await
                             }
                             Err(e) => Err(e),
                         }
@@ -611,6 +742,39 @@ fn handle_request(request: CommandRequest, mut client: Client, writer: Rc<Writer
                         )
                         .await
                         .map_err(|err| err.into()),
+                    command_request::Command::UpdateTlsCredentials(
+                        update_tls_credentials_command,
+                    ) => client
+                        .update_tls_credentials(
+                            (&update_tls_credentials_command).into(),
+                            update_tls_credentials_command.reconnect,
+                        )
+                        .await
+                        .map_err(|err| err.into()),
+                    command_request::Command::SubscribeChannels(subscribe_command) => {
+                        update_subscriptions(
+                            &subscribe_command.kind,
+                            subscribe_command.channels_or_patterns,
+                            true,
+                            client,
+                        )
+                        .await
+                    }
+                    command_request::Command::UnsubscribeChannels(unsubscribe_command) => {
+                        update_subscriptions(
+                            &unsubscribe_command.kind,
+                            unsubscribe_command.channels_or_patterns,
+                            false,
+                            client,
+                        )
+                        .await
+                    }
+                    command_request::Command::GetSubscriptions(_) => {
+                        client.get_subscriptions().await.map_err(|err| err.into())
+                    }
+                    command_request::Command::CancelRequest(_) => {
+                        unreachable!("Cancel requests are handled without spawning a task")
+                    }
                 },
                 None => {
                     log_debug(
@@ -627,21 +791,82 @@ fn handle_request(request: CommandRequest, mut client: Client, writer: Rc<Writer
             },
         };
 
-        if updated_inflight_counter {
-            client_clone.release_inflight_request();
-        }
+        drop(inflight_slot);
+        // Once the request is unregistered it can't be cancelled, so writing its response isn't interrupted.
+        inflight_requests.borrow_mut().remove(&request.callback_idx);
 
         let _res = write_result(result, request.callback_idx, &writer, request.root_span_ptr).await;
     });
+    requests.borrow_mut().insert(
+        callback_idx,
+        InflightRequest {
+            task,
+            root_span_ptr,
+            kind,
+            started: Instant::now(),
+        },
+    );
+}
+
+/// Returns the kind that a request with `command` is recorded under in the request statistics, if it's recorded.
+fn request_kind(command: &command_request::Command) -> Option<RequestKind> {
+    match command {
+        command_request::Command::SingleCommand(command) => {
+            Some(RequestKind::Command(command.request_type.into()))
+        }
+        command_request::Command::Batch(batch) if batch.is_atomic => Some(RequestKind::Transaction),
+        command_request::Command::Batch(_) => Some(RequestKind::Pipeline),
+        command_request::Command::ScriptInvocation(_)
+        | command_request::Command::ScriptInvocationPointers(_) => {
+            Some(RequestKind::Command(RequestType::EvalSha))
+        }
+        command_request::Command::ClusterScan(_) => Some(RequestKind::Command(RequestType::Scan)),
+        _ => None,
+    }
+}
+
+/// Aborts the request with `callback_idx` and answers it with a cancellation error. Aborting the request's task
+/// releases its inflight slot. The cancel request itself is answered with OK, even if the request already completed.
+fn cancel_inflight_request(
+    callback_idx: u32,
+    cancel_callback_idx: u32,
+    inflight_requests: &InflightRequests,
+    writer: Rc<Writer>,
+) {
+    let cancelled_request = inflight_requests.borrow_mut().remove(&callback_idx);
+    if let Some(cancelled_request) = &cancelled_request {
+        cancelled_request.task.abort();
+        if let Some(kind) = cancelled_request.kind {
+            record_cancelled_request(kind, cancelled_request.started);
+        }
+    }
+    task::spawn_local(async move {
+        if let Some(cancelled_request) = cancelled_request {
+            let _res = write_result(
+                Err(ClientUsageError::Cancelled),
+                callback_idx,
+                &writer,
+                cancelled_request.root_span_ptr,
+            )
+            .await;
+        }
+        let _res = write_result(Ok(Value::Okay), cancel_callback_idx, &writer, None).await;
+    });
 }
 
 async fn handle_requests(
     received_requests: Vec<CommandRequest>,
     client: &Client,
     writer: &Rc<Writer>,
+    inflight_requests: &InflightRequests,
 ) {
     for request in received_requests {
-        handle_request(request, client.clone(), writer.clone());
+        handle_request(
+            request,
+            client.clone(),
+            writer.clone(),
+            inflight_requests.clone(),
+        );
     }
     // Yield to ensure that the subtasks aren't starved.
     task::yield_now().await;
@@ -718,13 +943,14 @@ async fn read_values_loop(
     client: &Client,
     writer: Rc<Writer>,
 ) -> ClosingReason {
+    let inflight_requests = InflightRequests::default();
     loop {
         match client_listener.next_values().await {
             Closed(reason) => {
                 return reason;
             }
             ReceivedValues(received_requests) => {
-                handle_requests(received_requests, client, &writer).await;
+                handle_requests(received_requests, client, &writer, &inflight_requests).await;
             }
         }
     }
@@ -877,6 +1103,9 @@ enum ClientUsageError {
     /// An error that stems from wrong behavior of the user.
     #[error("User error: {0}")]
     User(String),
+    /// The request was cancelled by the user.
+    #[error("The request was cancelled")]
+    Cancelled,
 }
 
 type ClientUsageResult<T> = Result<T, ClientUsageError>;
@@ -4,10 +4,12 @@ use std::sync::RwLock as StdRwLock;
 mod metrics_exporter_file;
 mod open_telemetry;
 mod span_exporter_file;
+mod statistics;
 
 pub use metrics_exporter_file::FileMetricExporter;
 pub use open_telemetry::*;
 pub use span_exporter_file::SpanExporterFile;
+pub use statistics::NodeStatistics;
 
 #[derive(Default, Serialize)]
 #[allow(dead_code)]
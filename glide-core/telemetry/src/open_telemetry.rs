@@ -25,6 +25,7 @@ const TRACE_SCOPE: &str = "valkey_glide";
 const TIMEOUT_ERROR_METRIC: &str = "glide.timeout_errors";
 const RETRIES_METRIC: &str = "glide.retry_attempts";
 const MOVED_ERROR_METRIC: &str = "glide.moved_errors";
+const CIRCUIT_BREAKER_METRIC: &str = "glide.circuit_breaker_transitions";
 
 /// Custom error type for OpenTelemetry errors in Glide
 #[derive(Debug, Error)]
@@ -472,6 +473,7 @@ pub struct GlideOpenTelemetry {}
 static TIMEOUT_COUNTER: OnceLock<opentelemetry::metrics::Counter<u64>> = OnceLock::new();
 static RETRIES_COUNTER: OnceLock<opentelemetry::metrics::Counter<u64>> = OnceLock::new();
 static MOVED_COUNTER: OnceLock<opentelemetry::metrics::Counter<u64>> = OnceLock::new();
+static CIRCUIT_BREAKER_COUNTER: OnceLock<opentelemetry::metrics::Counter<u64>> = OnceLock::new();
 
 /// Singleton instance of GlideOpenTelemetry. Ensures that telemetry setup happens only once across the application.
 static OTEL: OnceCell<RwLock<GlideOpenTelemetry>> = OnceCell::new();
@@ -669,6 +671,23 @@ impl GlideOpenTelemetry {
                 )
             })?;
 
+        // Create circuit breaker transitions counter
+        CIRCUIT_BREAKER_COUNTER
+            .set(
+                meter
+                    .u64_counter(CIRCUIT_BREAKER_METRIC)
+                    .with_description(
+                        "Number of circuit breaker state transitions, by the new state",
+                    )
+                    .with_unit("1")
+                    .build(),
+            )
+            .map_err(|_| {
+                GlideOTELError::Other(
+                    "OpenTelemetry error: Failed to initialize circuit breaker counter".to_owned(),
+                )
+            })?;
+
         Ok(())
     }
 
@@ -723,6 +742,26 @@ impl GlideOpenTelemetry {
         Ok(())
     }
 
+    /// Record a state transition of a node's circuit breaker
+    ///
+    /// If OpenTelemetry is not initialized, this method will do nothing.
+    pub fn record_circuit_breaker_transition(state: &str) -> Result<(), GlideOTELError> {
+        if GlideOpenTelemetry::is_initialized() {
+            CIRCUIT_BREAKER_COUNTER
+                .get()
+                .ok_or_else(|| {
+                    GlideOTELError::Other(
+                        "OpenTelemetry error: Circuit breaker counter not initialized".to_string(),
+                    )
+                })?
+                .add(
+                    1,
+                    &[opentelemetry::KeyValue::new("state", state.to_string())],
+                );
+        }
+        Ok(())
+    }
+
     /// Get the flush interval milliseconds
     pub fn get_flush_interval_ms(config: GlideOpenTelemetryConfig) -> Duration {
         config.flush_interval_ms
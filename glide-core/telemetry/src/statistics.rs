@@ -0,0 +1,115 @@
+use crate::Telemetry;
+use dashmap::DashMap;
+use lazy_static::lazy_static;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// The statistics of a node, by its address.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct NodeStatistics {
+    /// The number of requests that were sent to the node
+    pub requests: u64,
+    /// The number of MOVED redirects that the node replied with
+    pub moved_redirects: u64,
+    /// The number of ASK redirects that the node replied with
+    pub ask_redirects: u64,
+    /// The number of times that the connections to the node were reconnected
+    pub reconnects: u64,
+    /// The number of requests to the node that timed out
+    pub timeouts: u64,
+}
+
+#[derive(Debug, Default)]
+struct NodeCounters {
+    requests: AtomicU64,
+    moved_redirects: AtomicU64,
+    ask_redirects: AtomicU64,
+    reconnects: AtomicU64,
+    timeouts: AtomicU64,
+}
+
+impl From<&NodeCounters> for NodeStatistics {
+    fn from(counters: &NodeCounters) -> Self {
+        Self {
+            requests: counters.requests.load(Ordering::Relaxed),
+            moved_redirects: counters.moved_redirects.load(Ordering::Relaxed),
+            ask_redirects: counters.ask_redirects.load(Ordering::Relaxed),
+            reconnects: counters.reconnects.load(Ordering::Relaxed),
+            timeouts: counters.timeouts.load(Ordering::Relaxed),
+        }
+    }
+}
+
+static STATISTICS_ENABLED: AtomicBool = AtomicBool::new(false);
+
+lazy_static! {
+    static ref NODE_STATISTICS: DashMap<String, NodeCounters> = DashMap::new();
+}
+
+fn update_node(address: &str, update: impl FnOnce(&NodeCounters)) {
+    if !Telemetry::statistics_enabled() {
+        return;
+    }
+    // The entry is only allocated on the first request to the node, later requests only take a shard read lock.
+    match NODE_STATISTICS.get(address) {
+        Some(counters) => update(&counters),
+        None => update(&NODE_STATISTICS.entry(address.to_string()).or_default()),
+    }
+}
+
+impl Telemetry {
+    /// Enable or disable the collection of the request statistics. They aren't collected by default.
+    pub fn set_statistics_enabled(enabled: bool) {
+        STATISTICS_ENABLED.store(enabled, Ordering::Relaxed);
+    }
+
+    /// Return whether the request statistics are collected
+    pub fn statistics_enabled() -> bool {
+        STATISTICS_ENABLED.load(Ordering::Relaxed)
+    }
+
+    /// Record a request that was sent to the node at `address`, and whether it timed out
+    pub fn record_node_result(address: &str, timed_out: bool) {
+        update_node(address, |node| {
+            node.requests.fetch_add(1, Ordering::Relaxed);
+            if timed_out {
+                node.timeouts.fetch_add(1, Ordering::Relaxed);
+            }
+        });
+    }
+
+    /// Record a MOVED redirect from the node at `address`
+    pub fn record_node_moved_redirect(address: &str) {
+        update_node(address, |node| {
+            node.moved_redirects.fetch_add(1, Ordering::Relaxed);
+        });
+    }
+
+    /// Record an ASK redirect from the node at `address`
+    pub fn record_node_ask_redirect(address: &str) {
+        update_node(address, |node| {
+            node.ask_redirects.fetch_add(1, Ordering::Relaxed);
+        });
+    }
+
+    /// Record a reconnection to the node at `address`
+    pub fn record_node_reconnect(address: &str) {
+        update_node(address, |node| {
+            node.reconnects.fetch_add(1, Ordering::Relaxed);
+        });
+    }
+
+    /// Return the node statistics collected thus far, by the addresses of the nodes
+    pub fn node_statistics() -> BTreeMap<String, NodeStatistics> {
+        NODE_STATISTICS
+            .iter()
+            .map(|entry| (entry.key().clone(), entry.value().into()))
+            .collect()
+    }
+
+    /// Reset the node statistics collected thus far
+    pub fn reset_node_statistics() {
+        NODE_STATISTICS.clear();
+    }
+}
@@ -27,8 +27,13 @@ pub(crate) mod shared_client_tests {
     use std::collections::HashMap;
 
     use super::*;
-    use glide_core::client::{Client, DEFAULT_RESPONSE_TIMEOUT};
+    use glide_core::client::{Client, ConnectionError, DEFAULT_RESPONSE_TIMEOUT, TlsConfig};
     use glide_core::connection_request::ProtocolVersion;
+    use glide_core::request_statistics::{
+        RequestKind, RequestStatistics, record_request, request_statistics,
+        reset_request_statistics, set_request_statistics_enabled,
+    };
+    use glide_core::request_type::RequestType;
     use redis::cluster_routing::{SingleNodeRoutingInfo, SlotAddr};
     use redis::{
         FromRedisValue, InfoDict, Pipeline, PipelineRetryStrategy, RedisConnectionInfo, Value,
@@ -356,6 +361,97 @@ pub(crate) mod shared_client_tests {
         });
     }
 
+    #[test]
+    fn test_sentinel_is_rejected_in_cluster_mode() {
+        block_on_all(async {
+            let request = glide_core::client::ConnectionRequest {
+                cluster_mode_enabled: true,
+                sentinel: Some(glide_core::client::SentinelConfig {
+                    addresses: vec![glide_core::client::NodeAddress {
+                        host: "localhost".to_string(),
+                        port: 26379,
+                        unix_socket_path: None,
+                    }],
+                    master_name: "mymaster".to_string(),
+                    authentication_info: None,
+                }),
+                ..Default::default()
+            };
+            let Err(ConnectionError::Cluster(err)) = Client::new(request, None).await else {
+                panic!("Expected the client creation to fail");
+            };
+            assert_eq!(err.kind(), redis::ErrorKind::InvalidClientConfig);
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_update_tls_credentials_replaces_the_connections(
+        #[values(false, true)] use_cluster: bool,
+    ) {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    use_tls: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+            let routing = RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(Route::new(
+                get_slot(b"foo"),
+                SlotAddr::Master,
+            )));
+            let mut client_id_cmd = cmd("CLIENT");
+            client_id_cmd.arg("ID");
+            let client_id = test_basics
+                .client
+                .send_command(&client_id_cmd, Some(routing.clone()))
+                .await
+                .unwrap();
+
+            let result = test_basics
+                .client
+                .update_tls_credentials(TlsConfig::default(), true)
+                .await;
+            assert_eq!(result, Ok(Value::Okay));
+
+            // The connections are replaced in the background, using the new credentials.
+            let mut new_client_id = client_id.clone();
+            for _ in 0..50 {
+                tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+                new_client_id = test_basics
+                    .client
+                    .send_command(&client_id_cmd, Some(routing.clone()))
+                    .await
+                    .unwrap();
+                if new_client_id != client_id {
+                    break;
+                }
+            }
+            assert_ne!(new_client_id, client_id);
+            let key = generate_random_string(6);
+            send_set_and_get(test_basics.client.clone(), key.to_string()).await;
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_update_tls_credentials_fails_without_tls(#[values(false, true)] use_cluster: bool) {
+        block_on_all(async {
+            let mut test_basics =
+                setup_test_basics(use_cluster, TestConfiguration::default()).await;
+            let err = test_basics
+                .client
+                .update_tls_credentials(TlsConfig::default(), false)
+                .await
+                .unwrap_err();
+            assert_eq!(err.kind(), redis::ErrorKind::InvalidClientConfig);
+        });
+    }
+
     #[rstest]
     #[serial_test::serial]
     #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
@@ -417,6 +513,32 @@ pub(crate) mod shared_client_tests {
         });
     }
 
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_request_timeout_overrides_the_client_timeout(#[values(false, true)] use_cluster: bool) {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    ..Default::default()
+                },
+            )
+            .await;
+
+            // The request timeout replaces the timeout of the blocking command, which would block indefinitely.
+            let mut cmd = redis::Cmd::new();
+            cmd.arg("BLPOP").arg(generate_random_string(10)).arg(0);
+            let result = test_basics
+                .client
+                .send_command_with_timeout(&cmd, None, Some(50))
+                .await;
+            let err = result.unwrap_err();
+            assert!(err.is_timeout(), "{err}");
+        });
+    }
+
     #[rstest]
     #[serial_test::serial]
     #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
@@ -1465,6 +1587,78 @@ pub(crate) mod shared_client_tests {
         });
     }
 
+    #[test]
+    #[serial_test::serial]
+    fn test_client_request_statistics() {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics(false, TestConfiguration::default()).await;
+            let BackingServer::Standalone(Some(server)) = &test_basics.server else {
+                unreachable!()
+            };
+            let address = server.get_client_addr().to_string();
+            let key = generate_random_string(10);
+            reset_request_statistics();
+
+            // The statistics aren't collected until they're enabled.
+            let result = record_request(
+                RequestKind::Command(RequestType::Set),
+                test_basics
+                    .client
+                    .send_command(cmd("SET").arg(&key).arg("value"), None),
+            )
+            .await;
+            assert_eq!(result, Ok(Value::Okay));
+            assert_eq!(request_statistics(), RequestStatistics::default());
+
+            set_request_statistics_enabled(true);
+            let result = record_request(
+                RequestKind::Command(RequestType::Set),
+                test_basics
+                    .client
+                    .send_command(cmd("SET").arg(&key).arg("value"), None),
+            )
+            .await;
+            assert_eq!(result, Ok(Value::Okay));
+            let result = record_request(
+                RequestKind::Command(RequestType::Incr),
+                test_basics.client.send_command(cmd("INCR").arg(&key), None),
+            )
+            .await;
+            assert!(result.is_err());
+            let mut pipeline = redis::pipe();
+            pipeline.get(&key);
+            let result = record_request(
+                RequestKind::Pipeline,
+                test_basics.client.send_pipeline(
+                    &pipeline,
+                    None,
+                    true,
+                    None,
+                    PipelineRetryStrategy {
+                        retry_server_error: false,
+                        retry_connection_error: false,
+                    },
+                ),
+            )
+            .await;
+            assert!(result.is_ok());
+            set_request_statistics_enabled(false);
+
+            let statistics = request_statistics();
+            let incr = &statistics.commands["Incr"];
+            assert_eq!(incr.calls, 1);
+            assert_eq!(incr.errors.get("Unspecified"), Some(&1));
+            assert!(incr.latency_percentiles_usec["p99"] >= incr.latency_percentiles_usec["p50"]);
+            assert_eq!(statistics.commands["Set"].calls, 1);
+            assert!(statistics.commands["Set"].errors.is_empty());
+            assert_eq!(statistics.commands["Pipeline"].calls, 1);
+            assert_eq!(statistics.nodes[&address].requests, 3);
+
+            reset_request_statistics();
+            assert_eq!(request_statistics(), RequestStatistics::default());
+        });
+    }
+
     #[test]
     #[serial_test::serial]
     fn test_multi_key_no_args_in_cluster() {
@@ -1527,4 +1721,246 @@ pub(crate) mod shared_client_tests {
             );
         });
     }
+
+    #[derive(Debug)]
+    struct PingResponder;
+
+    impl glide_core::client::Middleware for PingResponder {
+        fn before(
+            &self,
+            request: &mut glide_core::client::MiddlewareRequest,
+        ) -> Option<redis::RedisResult<Value>> {
+            match request {
+                glide_core::client::MiddlewareRequest::Command { cmd, .. }
+                    if redis::cluster_routing::Routable::command(cmd).as_deref()
+                        == Some(b"PING".as_slice()) =>
+                {
+                    Some(Ok(Value::SimpleString("middleware".to_string())))
+                }
+                _ => None,
+            }
+        }
+
+        fn after(
+            &self,
+            request: &glide_core::client::MiddlewareRequest,
+            result: redis::RedisResult<Value>,
+        ) -> redis::RedisResult<Value> {
+            match (request, result) {
+                (glide_core::client::MiddlewareRequest::Batch { .. }, Ok(Value::Array(values))) => {
+                    Ok(Value::Int(values.len() as i64))
+                }
+                (_, result) => result,
+            }
+        }
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_middlewares_run_around_requests() {
+        block_on_all(async {
+            let configuration = TestConfiguration {
+                shared_server: true,
+                ..Default::default()
+            };
+            let mut request: glide_core::client::ConnectionRequest = create_connection_request(
+                &[get_shared_server_address(configuration.use_tls)],
+                &configuration,
+            )
+            .into();
+            request.middlewares.push(std::sync::Arc::new(PingResponder));
+            let mut client = Client::new(request, None).await.unwrap();
+
+            let result = client.send_command(&cmd("PING"), None).await;
+            assert_eq!(result, Ok(Value::SimpleString("middleware".to_string())));
+
+            let key = generate_random_string(10);
+            let result = client
+                .send_command(cmd("SET").arg(&key).arg("foo"), None)
+                .await;
+            assert_eq!(result, Ok(Value::Okay));
+
+            let mut pipeline = redis::pipe();
+            pipeline.get(&key).del(&key);
+            let result = client
+                .send_pipeline(
+                    &pipeline,
+                    None,
+                    false,
+                    None,
+                    PipelineRetryStrategy::default(),
+                )
+                .await;
+            assert_eq!(result, Ok(Value::Int(2)));
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_key_prefix_namespaces_keys() {
+        block_on_all(async {
+            let configuration = TestConfiguration {
+                shared_server: true,
+                ..Default::default()
+            };
+            let address = get_shared_server_address(configuration.use_tls);
+            let prefix = format!("{}:", generate_random_string(10));
+            let mut request: glide_core::client::ConnectionRequest =
+                create_connection_request(&[address.clone()], &configuration).into();
+            request.key_prefix = Some(prefix.clone().into_bytes());
+            let mut client = Client::new(request, None).await.unwrap();
+            let mut unprefixed_client = Client::new(
+                create_connection_request(&[address], &configuration).into(),
+                None,
+            )
+            .await
+            .unwrap();
+
+            let result = client
+                .send_command(cmd("MSET").arg("foo").arg("1").arg("bar").arg("2"), None)
+                .await;
+            assert_eq!(result, Ok(Value::Okay));
+            let result = unprefixed_client
+                .send_command(cmd("GET").arg(format!("{prefix}foo")), None)
+                .await;
+            assert_eq!(result, Ok(Value::BulkString(b"1".to_vec())));
+
+            let result = client.send_command(cmd("KEYS").arg("*"), None).await;
+            let mut keys: Vec<String> = redis::from_owned_redis_value(result.unwrap()).unwrap();
+            keys.sort();
+            assert_eq!(keys, ["bar", "foo"]);
+
+            let result = client
+                .send_command(cmd("RPUSH").arg("list").arg("value"), None)
+                .await;
+            assert_eq!(result, Ok(Value::Int(1)));
+            let result = client
+                .send_command(cmd("BLPOP").arg("list").arg(1), None)
+                .await;
+            assert_eq!(
+                result,
+                Ok(Value::Array(vec![
+                    Value::BulkString(b"list".to_vec()),
+                    Value::BulkString(b"value".to_vec()),
+                ]))
+            );
+
+            let result = client
+                .send_command(cmd("DEL").arg("foo").arg("bar"), None)
+                .await;
+            assert_eq!(result, Ok(Value::Int(2)));
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_command_policy_rejects_commands_before_sending_them() {
+        block_on_all(async {
+            let configuration = TestConfiguration {
+                shared_server: true,
+                ..Default::default()
+            };
+            let mut request: glide_core::client::ConnectionRequest = create_connection_request(
+                &[get_shared_server_address(configuration.use_tls)],
+                &configuration,
+            )
+            .into();
+            request.command_policy = Some(
+                glide_core::client::CommandPolicy::denylist()
+                    .command("FLUSHALL")
+                    .read_only(true),
+            );
+            let mut client = Client::new(request, None).await.unwrap();
+
+            let key = generate_random_string(10);
+            let result = client.send_command(cmd("GET").arg(&key), None).await;
+            assert_eq!(result, Ok(Value::Nil));
+
+            let err = client
+                .send_command(cmd("SET").arg(&key).arg("foo"), None)
+                .await
+                .unwrap_err();
+            assert_eq!(err.kind(), redis::ErrorKind::CommandNotAllowed);
+            let err = client
+                .send_command(&cmd("FLUSHALL"), None)
+                .await
+                .unwrap_err();
+            assert_eq!(err.kind(), redis::ErrorKind::CommandNotAllowed);
+
+            let mut transaction = redis::pipe();
+            transaction.atomic().get(&key).del(&key);
+            let err = client
+                .send_transaction(&transaction, None, None, false)
+                .await
+                .unwrap_err();
+            assert_eq!(err.kind(), redis::ErrorKind::CommandNotAllowed);
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_runtime_subscribe_and_unsubscribe(#[values(false, true)] use_cluster: bool) {
+        block_on_all(async {
+            let mut test_basics = setup_test_basics(
+                use_cluster,
+                TestConfiguration {
+                    shared_server: true,
+                    protocol: ProtocolVersion::RESP3,
+                    ..Default::default()
+                },
+            )
+            .await;
+            let channel = b"runtime-subscription-channel".to_vec();
+            let mut numsub = redis::cmd("PUBSUB");
+            numsub.arg("NUMSUB").arg(&channel);
+            let routing = RoutingInfo::SingleNode(SingleNodeRoutingInfo::SpecificNode(Route::new(
+                get_slot(&channel),
+                SlotAddr::Master,
+            )));
+            let subscribers = |reply: Value| {
+                let (_, count): (Vec<u8>, i64) = redis::from_owned_redis_value(reply).unwrap();
+                count
+            };
+
+            test_basics
+                .client
+                .subscribe(redis::PubSubSubscriptionKind::Exact, vec![channel.clone()])
+                .await
+                .unwrap();
+            let subscribed = Value::Map(vec![(
+                Value::Int(0),
+                Value::Set(vec![Value::BulkString(channel.clone())]),
+            )]);
+            assert_eq!(
+                test_basics.client.get_subscriptions().await.unwrap(),
+                Value::Array(vec![subscribed.clone(), subscribed])
+            );
+            let reply = test_basics
+                .client
+                .send_command(&numsub, Some(routing.clone()))
+                .await
+                .unwrap();
+            assert_eq!(subscribers(reply), 1);
+
+            test_basics
+                .client
+                .unsubscribe(redis::PubSubSubscriptionKind::Exact, vec![channel.clone()])
+                .await
+                .unwrap();
+            assert_eq!(
+                test_basics.client.get_subscriptions().await.unwrap(),
+                Value::Array(vec![Value::Map(vec![]), Value::Map(vec![])])
+            );
+            let reply = test_basics
+                .client
+                .send_command(&numsub, Some(routing))
+                .await
+                .unwrap();
+            assert_eq!(subscribers(reply), 0);
+        });
+    }
 }
@@ -1091,6 +1091,78 @@ mod socket_listener {
         );
     }
 
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_cancel_inflight_request() {
+        const BLPOP_CALLBACK_INDEX: u32 = 1;
+        const CANCEL_CALLBACK_INDEX: u32 = 2;
+        let mut test_basics =
+            setup_test_basics(Tls::NoTls, TestServer::Unique, RedisType::Standalone);
+        request_statistics::reset_request_statistics();
+        request_statistics::set_request_statistics_enabled(true);
+
+        let mut buffer = Vec::with_capacity(100);
+        write_blpop(
+            &mut buffer,
+            &mut test_basics.socket,
+            BLPOP_CALLBACK_INDEX,
+            "nonexistingkeylist",
+            0,
+        );
+        let mut request = CommandRequest::new();
+        request.callback_idx = CANCEL_CALLBACK_INDEX;
+        let mut cancel_request = command_request::CancelRequest::new();
+        cancel_request.callback_idx = BLPOP_CALLBACK_INDEX;
+        request.command = Some(command_request::command_request::Command::CancelRequest(
+            cancel_request,
+        ));
+        buffer.clear();
+        write_request(&mut buffer, &mut test_basics.socket, request);
+
+        // Both responses might arrive in a single read.
+        buffer.clear();
+        let mut read_buffer = vec![0_u8; 300];
+        let mut responses = Vec::new();
+        while responses.len() < 2 {
+            if let Some((message_length, header_bytes)) = u32::decode_var(&buffer) {
+                let message_end = header_bytes + message_length as usize;
+                if buffer.len() >= message_end {
+                    responses.push(decode_response(
+                        &buffer,
+                        header_bytes,
+                        message_length as usize,
+                    ));
+                    buffer.drain(..message_end);
+                    continue;
+                }
+            }
+            let size = test_basics.socket.read(&mut read_buffer).unwrap();
+            buffer.extend_from_slice(&read_buffer[..size]);
+        }
+
+        assert_eq!(responses[0].callback_idx, BLPOP_CALLBACK_INDEX);
+        let error = responses[0].request_error();
+        assert_eq!(
+            error.type_.enum_value_or_default(),
+            glide_core::response::RequestErrorType::Cancelled
+        );
+        assert_eq!(&*error.message, "The request was cancelled");
+        assert_eq!(responses[1].callback_idx, CANCEL_CALLBACK_INDEX);
+        assert_eq!(
+            responses[1].constant_response(),
+            ConstantResponse::OK,
+            "Received {:?}",
+            responses[1]
+        );
+
+        request_statistics::set_request_statistics_enabled(false);
+        let blpop = &request_statistics::request_statistics().commands["BLPop"];
+        assert_eq!(blpop.calls, 1);
+        assert_eq!(blpop.errors.get("Cancelled"), Some(&1));
+        request_statistics::reset_request_statistics();
+    }
+
     #[rstest]
     #[serial_test::serial]
     #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
@@ -1417,4 +1489,51 @@ mod socket_listener {
             ResponseType::RequestError,
         );
     }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_CLUSTER_TEST_TIMEOUT)]
+    fn test_socket_update_tls_credentials(
+        #[values(Tls::NoTls, Tls::UseTls)] use_tls: Tls,
+        #[values(RedisType::Cluster, RedisType::Standalone)] use_cluster: RedisType,
+    ) {
+        let mut test_basics = setup_test_basics(use_tls, TestServer::Shared, use_cluster);
+        const CALLBACK1_INDEX: u32 = 100;
+        const CALLBACK2_INDEX: u32 = 101;
+
+        let mut request = CommandRequest::new();
+        request.callback_idx = CALLBACK1_INDEX;
+        request.command = Some(
+            command_request::command_request::Command::UpdateTlsCredentials(
+                command_request::UpdateTlsCredentials::new(),
+            ),
+        );
+        let mut buffer = Vec::with_capacity(APPROX_RESP_HEADER_LEN);
+        write_request(&mut buffer, &mut test_basics.socket, request);
+
+        // Only a client that uses TLS can replace its TLS credentials.
+        match use_tls {
+            Tls::UseTls => {
+                assert_ok_response(&mut buffer, &mut test_basics.socket, CALLBACK1_INDEX);
+            }
+            Tls::NoTls => {
+                assert_error_response(
+                    &mut buffer,
+                    &mut test_basics.socket,
+                    CALLBACK1_INDEX,
+                    ResponseType::RequestError,
+                );
+            }
+        }
+
+        buffer.clear();
+        write_get(
+            &mut buffer,
+            &mut test_basics.socket,
+            CALLBACK2_INDEX,
+            generate_random_string(KEY_LENGTH).as_str(),
+            false,
+        );
+        assert_null_response(&mut buffer, &mut test_basics.socket, CALLBACK2_INDEX);
+    }
 }
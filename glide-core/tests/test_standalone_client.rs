@@ -312,6 +312,64 @@ mod standalone_client_tests {
         });
     }
 
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_read_from_replica_lowest_latency_falls_back_to_round_robin_without_samples() {
+        // The replicas' latency isn't measured until the first connection check, so the reads are spread between them.
+        test_read_from_replica(ReadFromReplicaTestConfig {
+            read_from: ReadFrom::LowestLatency,
+            expected_primary_reads: 0,
+            expected_replica_reads: vec![1, 1, 1],
+            ..Default::default()
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_read_from_replica_lowest_latency_reads_from_the_fastest_replica() {
+        let mut servers = create_primary_mock_with_replicas(1);
+        let mut slow_replica_responses = create_replica_response();
+        slow_replica_responses.remove("*1\r\n$4\r\nPING\r\n");
+        let slow_replica = ServerMock::new(slow_replica_responses);
+        // The connection check measures the replica's latency with a PING.
+        slow_replica.add_delayed_response(
+            &redis::cmd("PING"),
+            "+PONG\r\n".to_string(),
+            std::time::Duration::from_millis(100),
+        );
+        servers.insert(1, slow_replica);
+        let mut cmd = redis::cmd("GET");
+        cmd.arg("foo");
+        for _ in 0..3 {
+            servers[2].add_response(&cmd, "$-1\r\n".to_string());
+        }
+
+        let mut connection_request =
+            create_connection_request(get_mock_addresses(&servers).as_slice(), &Default::default());
+        connection_request.read_from = ReadFrom::LowestLatency.into();
+
+        block_on_all(async {
+            let mut client = StandaloneClient::create_client(connection_request.into(), None)
+                .await
+                .unwrap();
+            tokio::time::sleep(
+                glide_core::client::CONNECTION_CHECKS_INTERVAL
+                    + std::time::Duration::from_millis(500),
+            )
+            .await;
+            for _ in 0..3 {
+                assert_eq!(client.send_command(&cmd).await, Ok(Value::Nil));
+            }
+        });
+
+        assert_eq!(servers[0].get_number_of_received_commands(), 0);
+        // The slow replica only received the PING.
+        assert_eq!(servers[1].get_number_of_received_commands(), 1);
+        assert_eq!(servers[2].get_number_of_received_commands(), 3);
+    }
+
     // TODO - Current test falls back to PreferReplica when run, need to integrate the az here also
     #[rstest]
     #[serial_test::serial]
@@ -393,6 +451,282 @@ mod standalone_client_tests {
         });
     }
 
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_slow_read_is_hedged_to_another_node() {
+        let servers = create_primary_mock_with_replicas(1);
+        let mut cmd = redis::cmd("GET");
+        cmd.arg("foo");
+        let response_delay = std::time::Duration::from_secs(1);
+        servers[0].add_response(&cmd, "$7\r\nprimary\r\n".to_string());
+        servers[1].add_delayed_response(&cmd, "$7\r\nreplica\r\n".to_string(), response_delay);
+        servers[1].add_response(&cmd, "$7\r\nreplica\r\n".to_string());
+
+        let mut connection_request =
+            create_connection_request(get_mock_addresses(&servers).as_slice(), &Default::default());
+        connection_request.read_from = ReadFrom::PreferReplica.into();
+        let mut connection_request: glide_core::client::ConnectionRequest =
+            connection_request.into();
+        connection_request.read_hedging = Some(redis::HedgePolicy::Delay(
+            std::time::Duration::from_millis(10),
+        ));
+
+        block_on_all(async move {
+            let mut client = StandaloneClient::create_client(connection_request, None)
+                .await
+                .unwrap();
+
+            // The replica doesn't answer within the hedging delay, so the read is also sent to the primary.
+            let start = std::time::Instant::now();
+            let value = client.send_command(&cmd).await.unwrap();
+            assert_eq!(value, Value::BulkString(b"primary".to_vec()));
+            assert!(start.elapsed() < response_delay);
+
+            // The late response of the abandoned request isn't taken as the response of the next read.
+            tokio::time::sleep(response_delay).await;
+            let value = client.send_command(&cmd).await.unwrap();
+            assert_eq!(value, Value::BulkString(b"replica".to_vec()));
+        });
+        assert_eq!(servers[0].get_number_of_received_commands(), 1);
+        assert_eq!(servers[1].get_number_of_received_commands(), 2);
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_hedged_read_waits_for_the_hedge_when_the_first_response_fails() {
+        let servers = create_primary_mock_with_replicas(1);
+        let mut cmd = redis::cmd("GET");
+        cmd.arg("foo");
+        // The replica fails after the read was hedged to the primary, but before the primary answers.
+        servers[0].add_delayed_response(
+            &cmd,
+            "$7\r\nprimary\r\n".to_string(),
+            std::time::Duration::from_millis(200),
+        );
+        servers[1].add_delayed_response(
+            &cmd,
+            "-ERR replica failure\r\n".to_string(),
+            std::time::Duration::from_millis(50),
+        );
+
+        let mut connection_request =
+            create_connection_request(get_mock_addresses(&servers).as_slice(), &Default::default());
+        connection_request.read_from = ReadFrom::PreferReplica.into();
+        let mut connection_request: glide_core::client::ConnectionRequest =
+            connection_request.into();
+        connection_request.read_hedging = Some(redis::HedgePolicy::Delay(
+            std::time::Duration::from_millis(10),
+        ));
+
+        block_on_all(async move {
+            let mut client = StandaloneClient::create_client(connection_request, None)
+                .await
+                .unwrap();
+
+            let value = client.send_command(&cmd).await.unwrap();
+            assert_eq!(value, Value::BulkString(b"primary".to_vec()));
+        });
+        assert_eq!(servers[0].get_number_of_received_commands(), 1);
+        assert_eq!(servers[1].get_number_of_received_commands(), 1);
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_circuit_breaker_opens_when_the_node_does_not_respond() {
+        let servers = create_primary_mock_with_replicas(0);
+        let mut cmd = redis::cmd("GET");
+        cmd.arg("foo");
+        // The node answers long after the requests timed out.
+        servers[0].add_delayed_response(
+            &cmd,
+            "$3\r\nbar\r\n".to_string(),
+            std::time::Duration::from_secs(1),
+        );
+
+        let mut connection_request =
+            create_connection_request(get_mock_addresses(&servers).as_slice(), &Default::default());
+        connection_request.request_timeout = 50;
+        connection_request.circuit_breaker_threshold = 2;
+
+        block_on_all(async move {
+            let mut client = GlideClient::new(connection_request.into(), None)
+                .await
+                .unwrap();
+
+            for _ in 0..2 {
+                let err = client.send_command(&cmd, None).await.unwrap_err();
+                assert!(err.is_timeout(), "{err}");
+            }
+            // Both requests timed out, so the breaker opened, and the next request fails fast.
+            let err = client.send_command(&cmd, None).await.unwrap_err();
+            assert_eq!(err.kind(), redis::ErrorKind::CircuitBreakerOpen);
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_command_policy_rejects_loading_a_missing_script() {
+        let servers = create_primary_mock_with_replicas(0);
+        let hash = glide_core::scripts_container::add_script(b"return 1");
+        let mut evalsha = redis::cmd("EVALSHA");
+        evalsha.arg(&hash).arg(0);
+        servers[0].add_response(&evalsha, "-NOSCRIPT No matching script\r\n".to_string());
+
+        let mut connection_request: glide_core::client::ConnectionRequest =
+            create_connection_request(get_mock_addresses(&servers).as_slice(), &Default::default())
+                .into();
+        connection_request.command_policy =
+            Some(glide_core::client::CommandPolicy::denylist().command("SCRIPT LOAD"));
+
+        block_on_all(async move {
+            let mut client = GlideClient::new(connection_request, None).await.unwrap();
+            let err = client
+                .invoke_script(&hash, &vec![], &vec![], None, None)
+                .await
+                .unwrap_err();
+            assert_eq!(err.kind(), redis::ErrorKind::CommandNotAllowed);
+            assert_eq!(err.detail(), Some("SCRIPT LOAD"));
+            // Only the invocation was sent.
+            assert_eq!(servers[0].get_number_of_received_commands(), 1);
+            glide_core::scripts_container::remove_script(&hash);
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_request_statistics_record_timed_out_requests() {
+        use glide_core::request_statistics::{
+            RequestKind, record_request, request_statistics, reset_request_statistics,
+            set_request_statistics_enabled,
+        };
+
+        let servers = create_primary_mock_with_replicas(0);
+        let address = get_mock_addresses(&servers)[0].to_string();
+        let mut cmd = redis::cmd("GET");
+        cmd.arg("foo");
+        servers[0].add_delayed_response(
+            &cmd,
+            "$3\r\nbar\r\n".to_string(),
+            std::time::Duration::from_secs(1),
+        );
+
+        let mut connection_request =
+            create_connection_request(get_mock_addresses(&servers).as_slice(), &Default::default());
+        connection_request.request_timeout = 50;
+
+        block_on_all(async move {
+            let mut client = GlideClient::new(connection_request.into(), None)
+                .await
+                .unwrap();
+            reset_request_statistics();
+            set_request_statistics_enabled(true);
+            let err = record_request(
+                RequestKind::Command(glide_core::request_type::RequestType::Get),
+                client.send_command(&cmd, None),
+            )
+            .await
+            .unwrap_err();
+            set_request_statistics_enabled(false);
+            assert!(err.is_timeout(), "{err}");
+
+            // The request was dropped when it timed out, and is recorded as a timeout of its node as well.
+            let statistics = request_statistics();
+            assert_eq!(statistics.commands["Get"].calls, 1);
+            assert_eq!(statistics.commands["Get"].errors.get("Timeout"), Some(&1));
+            assert_eq!(statistics.nodes[&address].requests, 1);
+            assert_eq!(statistics.nodes[&address].timeouts, 1);
+            reset_request_statistics();
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_client_side_cache_reads_the_clients_own_writes() {
+        let servers = create_primary_mock_with_replicas(0);
+        let mut get = redis::cmd("GET");
+        get.arg("foo");
+        let mut set = redis::cmd("SET");
+        set.arg("foo").arg("new");
+        servers[0].add_response(&get, "$3\r\nold\r\n".to_string());
+        servers[0].add_response(&set, "+OK\r\n".to_string());
+        servers[0].add_response(&get, "$3\r\nnew\r\n".to_string());
+
+        let mut connection_request = create_connection_request(
+            get_mock_addresses(&servers).as_slice(),
+            &TestConfiguration {
+                protocol: ProtocolVersion::RESP3,
+                ..Default::default()
+            },
+        );
+        connection_request.client_side_cache =
+            protobuf::MessageField::some(glide_core::connection_request::ClientSideCache {
+                max_entries: 10,
+                ..Default::default()
+            });
+
+        block_on_all(async move {
+            let mut client = GlideClient::new(connection_request.into(), None)
+                .await
+                .unwrap();
+
+            for _ in 0..2 {
+                let value = client.send_command(&get, None).await.unwrap();
+                assert_eq!(value, Value::BulkString(b"old".to_vec()));
+            }
+            // The write removes the cached response right away, without waiting for the server's invalidation.
+            client.send_command(&set, None).await.unwrap();
+            let value = client.send_command(&get, None).await.unwrap();
+            assert_eq!(value, Value::BulkString(b"new".to_vec()));
+        });
+        assert_eq!(servers[0].get_number_of_received_commands(), 3);
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_only_idempotent_commands_are_retried_after_a_dropped_connection() {
+        let servers = create_primary_mock_with_replicas(0);
+        let mut get_cmd = redis::cmd("GET");
+        get_cmd.arg("foo");
+        let mut set_cmd = redis::cmd("SET");
+        set_cmd.arg("foo").arg("bar");
+        servers[0].add_connection_drop(&get_cmd);
+        servers[0].add_response(&get_cmd, "$3\r\nbar\r\n".to_string());
+        servers[0].add_connection_drop(&set_cmd);
+        servers[0].add_response(&get_cmd, "$3\r\nbar\r\n".to_string());
+
+        let connection_request =
+            create_connection_request(get_mock_addresses(&servers).as_slice(), &Default::default());
+        let mut connection_request: glide_core::client::ConnectionRequest =
+            connection_request.into();
+        connection_request.connection_error_retry =
+            Some(redis::cluster_routing::ConnectionErrorRetryPolicy::new(1));
+
+        block_on_all(async move {
+            let mut client = StandaloneClient::create_client(connection_request, None)
+                .await
+                .unwrap();
+
+            // The read is sent again once the connection is restored.
+            let value = client.send_command(&get_cmd).await.unwrap();
+            assert_eq!(value, Value::BulkString(b"bar".to_vec()));
+
+            // The write might have been applied before the connection dropped, so it isn't sent again.
+            let err = client.send_command(&set_cmd).await.unwrap_err();
+            assert!(err.is_connection_dropped(), "{err:?}");
+
+            let value = client.send_command(&get_cmd).await.unwrap();
+            assert_eq!(value, Value::BulkString(b"bar".to_vec()));
+        });
+        assert_eq!(servers[0].get_number_of_received_commands(), 4);
+    }
+
     #[rstest]
     #[serial_test::serial]
     #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
@@ -649,4 +983,152 @@ mod standalone_client_tests {
             );
         });
     }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_blocking_command_does_not_stall_other_requests_with_connection_pool() {
+        block_on_all(async move {
+            let config = TestConfiguration {
+                shared_server: false,
+                cluster_mode: ClusterMode::Disabled,
+                ..Default::default()
+            };
+            let mut test_basics = setup_test_basics_internal(&config).await;
+            let address = test_basics
+                .server
+                .as_ref()
+                .expect("Server shouldn't be None")
+                .get_client_addr();
+            let clients_before = get_connected_clients(&mut test_basics.client).await;
+
+            let mut connection_request = create_connection_request(&[address], &config);
+            connection_request.connections_per_node = 3;
+            let client = StandaloneClient::create_client(connection_request.into(), None)
+                .await
+                .unwrap();
+            assert_eq!(
+                get_connected_clients(&mut test_basics.client).await,
+                clients_before + 3
+            );
+
+            let mut blocking_client = client.clone();
+            let blocking_request = tokio::spawn(async move {
+                let mut blpop = redis::cmd("BLPOP");
+                blpop
+                    .arg("test_blocking_command_does_not_stall_other_requests")
+                    .arg(2);
+                blocking_client.send_command(&blpop).await
+            });
+            // Let the blocking command reach the server before sending the next request.
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+
+            let mut ping_client = client.clone();
+            let ping_result = tokio::time::timeout(
+                std::time::Duration::from_secs(1),
+                ping_client.send_command(&redis::cmd("PING")),
+            )
+            .await
+            .expect("PING was stalled by the blocking command");
+            assert_eq!(
+                ping_result.unwrap(),
+                Value::SimpleString("PONG".to_string())
+            );
+            assert!(!blocking_request.is_finished());
+            assert_eq!(blocking_request.await.unwrap().unwrap(), Value::Nil);
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_blocking_command_uses_a_dedicated_connection() {
+        block_on_all(async move {
+            let config = TestConfiguration {
+                shared_server: false,
+                cluster_mode: ClusterMode::Disabled,
+                ..Default::default()
+            };
+            let mut test_basics = setup_test_basics_internal(&config).await;
+            let address = test_basics
+                .server
+                .as_ref()
+                .expect("Server shouldn't be None")
+                .get_client_addr();
+            let clients_before = get_connected_clients(&mut test_basics.client).await;
+
+            let connection_request = create_connection_request(&[address], &config);
+            let client = StandaloneClient::create_client(connection_request.into(), None)
+                .await
+                .unwrap();
+            assert_eq!(
+                get_connected_clients(&mut test_basics.client).await,
+                clients_before + 1
+            );
+
+            // Concurrent blocking commands share the dedicated connection, which is created on first use.
+            let blocking_requests: Vec<_> = (0..2)
+                .map(|_| {
+                    let mut blocking_client = client.clone();
+                    tokio::spawn(async move {
+                        let mut blpop = redis::cmd("BLPOP");
+                        blpop
+                            .arg("test_blocking_command_uses_a_dedicated_connection")
+                            .arg(1);
+                        blocking_client.send_command(&blpop).await
+                    })
+                })
+                .collect();
+            tokio::time::sleep(std::time::Duration::from_millis(100)).await;
+            assert_eq!(
+                get_connected_clients(&mut test_basics.client).await,
+                clients_before + 2
+            );
+
+            let mut ping_client = client.clone();
+            let ping_result = tokio::time::timeout(
+                std::time::Duration::from_millis(500),
+                ping_client.send_command(&redis::cmd("PING")),
+            )
+            .await
+            .expect("PING was stalled by the blocking command");
+            assert_eq!(
+                ping_result.unwrap(),
+                Value::SimpleString("PONG".to_string())
+            );
+            for blocking_request in blocking_requests {
+                assert_eq!(blocking_request.await.unwrap().unwrap(), Value::Nil);
+            }
+        });
+    }
+
+    #[rstest]
+    #[serial_test::serial]
+    #[timeout(SHORT_STANDALONE_TEST_TIMEOUT)]
+    fn test_auto_protocol_falls_back_to_resp2_when_hello_is_rejected() {
+        let mut responses = create_primary_responses();
+        responses.insert(
+            "*2\r\n$5\r\nHELLO\r\n$1\r\n3\r\n".to_string(),
+            redis::parse_redis_value(b"-ERR unknown command 'HELLO'\r\n").unwrap(),
+        );
+        let server = ServerMock::new(responses);
+        let mut cmd = redis::cmd("GET");
+        cmd.arg("foo");
+        server.add_response(&cmd, "$3\r\nbar\r\n".to_string());
+
+        let mut connection_request =
+            create_connection_request(&server.get_addresses(), &Default::default());
+        connection_request.protocol = ProtocolVersion::AUTO.into();
+
+        block_on_all(async move {
+            let mut client = StandaloneClient::create_client(connection_request.into(), None)
+                .await
+                .expect("The client should connect over RESP2");
+            assert_eq!(
+                client.send_command(&cmd).await.unwrap(),
+                Value::BulkString(b"bar".to_vec())
+            );
+        });
+        assert_eq!(server.get_number_of_received_commands(), 1);
+    }
 }
@@ -13,11 +13,25 @@ use std::sync::{
     Arc,
     atomic::{AtomicU16, Ordering},
 };
+use std::time::Duration;
 use tokio::sync::mpsc::UnboundedSender;
 
 pub struct MockedRequest {
     pub expected_message: String,
     pub response: String,
+    /// How long the server waits before it responds, e.g. to simulate a slow node.
+    pub delay: Duration,
+    /// Whether the server closes the connection instead of responding, e.g. to simulate a connection dropped mid-request.
+    pub drop_connection: bool,
+}
+
+/// What the server did after reading the next message.
+#[derive(PartialEq)]
+enum MessageOutcome {
+    Responded,
+    /// The connection was dropped by request, and the server accepts the client's next connection.
+    ConnectionDropped,
+    Closed,
 }
 
 pub struct ServerMock {
@@ -58,6 +72,38 @@ fn read_from_socket(
     None
 }
 
+/// Splits a message into the RESP-encoded commands it contains, since pipelined commands are received in a single message.
+fn split_commands(message: &str) -> Vec<&str> {
+    let mut commands = Vec::new();
+    let mut rest = message;
+    while !rest.is_empty() {
+        let (command, remaining) = rest.split_at(command_length(rest).unwrap_or(rest.len()));
+        commands.push(command);
+        rest = remaining;
+    }
+    commands
+}
+
+/// Returns the length of the RESP-encoded command at the start of `message`, or `None` if it can't be parsed.
+fn command_length(message: &str) -> Option<usize> {
+    fn parse_header(message: &str, prefix: char) -> Option<(usize, usize)> {
+        let header_end = message.find("\r\n")?;
+        let value = message
+            .strip_prefix(prefix)?
+            .get(..header_end - 1)?
+            .parse()
+            .ok()?;
+        Some((value, header_end + 2))
+    }
+
+    let (arguments_count, mut position) = parse_header(message, '*')?;
+    for _ in 0..arguments_count {
+        let (argument_length, header_length) = parse_header(message.get(position..)?, '$')?;
+        position += header_length + argument_length + 2;
+    }
+    (position <= message.len()).then_some(position)
+}
+
 /// Escape and print a RESP message
 fn log_resp_message(msg: &str) {
     logger_core::log_info(
@@ -76,54 +122,55 @@ fn receive_and_respond_to_next_message(
     received_commands: &Arc<AtomicU16>,
     constant_responses: &HashMap<String, Value>,
     closing_signal: &Arc<ManualResetEvent>,
-) -> bool {
+) -> MessageOutcome {
     let mut buffer = vec![0; 1024];
     let size = match read_from_socket(&mut buffer, socket, closing_signal) {
         Some(size) => size,
         None => {
-            return false;
+            return MessageOutcome::Closed;
         }
     };
     let message = from_utf8(&buffer[..size]).unwrap().to_string();
     log_resp_message(&message);
 
-    let setinfo_count = message.matches("SETINFO").count();
-    if setinfo_count > 0 {
+    // The connection handshake is pipelined, so each of its commands is answered separately.
+    if message.contains("SETINFO") || message.contains("HELLO") {
         let mut buffer = Vec::new();
-        for _ in 0..setinfo_count {
-            super::encode_value(&Value::Okay, &mut buffer).unwrap();
+        for command in split_commands(&message) {
+            let response = match constant_responses.get(command) {
+                Some(response) => response.clone(),
+                None if command.contains("HELLO") => Value::Map(vec![
+                    (Value::BulkString(b"proto".to_vec()), Value::Int(3)),
+                    (
+                        Value::BulkString(b"role".to_vec()),
+                        Value::BulkString(b"master".to_vec()),
+                    ),
+                ]),
+                None => Value::Okay,
+            };
+            super::encode_value(&response, &mut buffer).unwrap();
         }
         socket.write_all(&buffer).unwrap();
-        return true;
-    }
-
-    if message.contains("HELLO") {
-        let mut buffer = Vec::new();
-        let response = Value::Map(vec![
-            (Value::BulkString(b"proto".to_vec()), Value::Int(3)),
-            (
-                Value::BulkString(b"role".to_vec()),
-                Value::BulkString(b"master".to_vec()),
-            ),
-        ]);
-        super::encode_value(&response, &mut buffer).unwrap();
-        socket.write_all(&buffer).unwrap();
-        return true;
+        return MessageOutcome::Responded;
     }
 
     if let Some(response) = constant_responses.get(&message) {
         let mut buffer = Vec::new();
         super::encode_value(response, &mut buffer).unwrap();
         socket.write_all(&buffer).unwrap();
-        return true;
+        return MessageOutcome::Responded;
     }
     let Ok(request) = receiver.try_recv() else {
         panic!("Received unexpected message: {message}");
     };
     received_commands.fetch_add(1, Ordering::AcqRel);
     assert_eq!(message, request.expected_message);
+    std::thread::sleep(request.delay);
+    if request.drop_connection {
+        return MessageOutcome::ConnectionDropped;
+    }
     socket.write_all(request.response.as_bytes()).unwrap();
-    true
+    MessageOutcome::Responded
 }
 
 pub trait Mock {
@@ -131,6 +178,11 @@ pub trait Mock {
 
     fn add_response(&self, request: &Cmd, response: String);
 
+    fn add_delayed_response(&self, request: &Cmd, response: String, delay: Duration);
+
+    /// Closes the connection when `request` is received, instead of responding to it.
+    fn add_connection_drop(&self, request: &Cmd);
+
     fn get_number_of_received_commands(&self) -> u16;
 }
 
@@ -158,19 +210,29 @@ impl ServerMock {
         let address_clone = address.clone();
         std::thread::spawn(move || {
             logger_core::log_info("Test", format!("ServerMock started on: {address_clone}"));
-            let mut socket: StdTcpStream = listener.accept().unwrap().0;
-            let _ = socket.set_read_timeout(Some(std::time::Duration::from_millis(10)));
+            loop {
+                let mut socket: StdTcpStream = listener.accept().unwrap().0;
+                let _ = socket.set_read_timeout(Some(std::time::Duration::from_millis(10)));
 
-            while receive_and_respond_to_next_message(
-                &mut receiver,
-                &mut socket,
-                &received_commands_clone,
-                &constant_responses,
-                &closing_signal_clone,
-            ) {}
+                let outcome = loop {
+                    match receive_and_respond_to_next_message(
+                        &mut receiver,
+                        &mut socket,
+                        &received_commands_clone,
+                        &constant_responses,
+                        &closing_signal_clone,
+                    ) {
+                        MessageOutcome::Responded => {}
+                        outcome => break outcome,
+                    }
+                };
 
-            // Terminate the connection
-            let _ = socket.shutdown(std::net::Shutdown::Both);
+                // Terminate the connection
+                let _ = socket.shutdown(std::net::Shutdown::Both);
+                if outcome != MessageOutcome::ConnectionDropped {
+                    break;
+                }
+            }
 
             // Now notify exit completed
             closing_completed_signal_clone.set();
@@ -203,10 +265,26 @@ impl Mock for ServerMock {
     }
 
     fn add_response(&self, request: &Cmd, response: String) {
+        self.add_delayed_response(request, response, Duration::ZERO);
+    }
+
+    fn add_delayed_response(&self, request: &Cmd, response: String, delay: Duration) {
         let expected_message = String::from_utf8(request.get_packed_command()).unwrap();
         let _ = self.request_sender.send(MockedRequest {
             expected_message,
             response,
+            delay,
+            drop_connection: false,
+        });
+    }
+
+    fn add_connection_drop(&self, request: &Cmd) {
+        let expected_message = String::from_utf8(request.get_packed_command()).unwrap();
+        let _ = self.request_sender.send(MockedRequest {
+            expected_message,
+            response: String::new(),
+            delay: Duration::ZERO,
+            drop_connection: true,
         });
     }
 
@@ -331,7 +331,10 @@ where
             }
             Ok(())
         }
-        Value::ServerError(ref err) => write!(writer, "server-error({err})\r\n"),
+        Value::ServerError(ref err) => match err.details() {
+            Some(details) => write!(writer, "-{} {details}\r\n", err.err_code()),
+            None => write!(writer, "-{}\r\n", err.err_code()),
+        },
     }
 }
 
@@ -512,7 +515,9 @@ pub fn get_address_info(address: &ConnectionAddr) -> NodeAddress {
             address_info.host = host.to_string().into();
             address_info.port = *port as u32;
         }
-        ConnectionAddr::Unix(_) => unreachable!("Unix connection not tested"),
+        ConnectionAddr::Unix(path) => {
+            address_info.unix_socket_path = path.to_string_lossy().to_string().into();
+        }
     }
     address_info
 }
@@ -558,6 +563,7 @@ fn convert_to_protobuf_protocol(
     match protocol {
         redis::ProtocolVersion::RESP2 => connection_request::ProtocolVersion::RESP2,
         redis::ProtocolVersion::RESP3 => connection_request::ProtocolVersion::RESP3,
+        redis::ProtocolVersion::Auto => connection_request::ProtocolVersion::AUTO,
     }
 }
 
@@ -345,6 +345,8 @@ func (client *baseClient) executeCommandWithRoute(
 		routeBytesPtr,
 		routeBytesCount,
 		C.uint64_t(spanPtr),
+		C._Bool(false),
+		C.uint(0),
 	)
 	client.mu.Unlock()
 	// Wait for result or context cancellation
@@ -8918,6 +8920,8 @@ func (client *baseClient) executeScriptWithRoute(
 		argsLengthsPtr,
 		routeBytesPtr,
 		routeBytesCount,
+		C._Bool(false),
+		C.uint(0),
 	)
 	client.mu.Unlock()
 
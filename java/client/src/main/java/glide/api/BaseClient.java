@@ -394,6 +394,34 @@ public abstract class BaseClient
         return StatisticsResolver.getStatistics();
     }
 
+    /**
+     * Return the statistics of the requests of this process, shared by all of its clients. The
+     * statistics are only collected after {@link #setRequestStatisticsEnabled} enabled them.
+     *
+     * @return Return a {@link Map} with the calls, errors by type and latency percentiles in
+     *     microseconds of each request kind, e.g. <code>Get</code> or <code>Transaction</code>,
+     *     under <code>commands</code>, and the requests, redirects, reconnects and timeouts of each
+     *     node address under <code>nodes</code>. The counters are nested maps of strings.
+     */
+    public Map<String, Object> getRequestStatistics() {
+        return StatisticsResolver.getRequestStatistics();
+    }
+
+    /**
+     * Enable or disable the collection of the statistics of the requests of this process. They
+     * aren't collected by default.
+     *
+     * @param enabled Whether the statistics are collected
+     */
+    public void setRequestStatisticsEnabled(boolean enabled) {
+        StatisticsResolver.setRequestStatisticsEnabled(enabled);
+    }
+
+    /** Reset the statistics of the requests of this process */
+    public void resetRequestStatistics() {
+        StatisticsResolver.resetRequestStatistics();
+    }
+
     /**
      * Return a next pubsub message if it is present.
      *
@@ -11,4 +11,17 @@ public class StatisticsResolver {
 
     /** Return the internal statistics Map object */
     public static native LinkedHashMap getStatistics();
+
+    /**
+     * Return the statistics of the requests of this process: the calls, errors and latency
+     * percentiles of each request kind, and the requests, redirects, reconnects and timeouts of each
+     * node
+     */
+    public static native LinkedHashMap getRequestStatistics();
+
+    /** Enable or disable the collection of the statistics of the requests of this process */
+    public static native void setRequestStatisticsEnabled(boolean enabled);
+
+    /** Reset the statistics of the requests of this process */
+    public static native void resetRequestStatistics();
 }
@@ -11,6 +11,9 @@ use glide_core::STREAM as TYPE_STREAM;
 use glide_core::STRING as TYPE_STRING;
 use glide_core::ZSET as TYPE_ZSET;
 use glide_core::client::FINISHED_SCAN_CURSOR;
+use glide_core::request_statistics::{
+    request_statistics, reset_request_statistics, set_request_statistics_enabled,
+};
 
 // Telemetry required for getStatistics
 use glide_core::Telemetry;
@@ -19,7 +22,7 @@ use bytes::Bytes;
 use jni::JNIEnv;
 use jni::errors::Error as JniError;
 use jni::objects::{JByteArray, JClass, JObject, JObjectArray, JString};
-use jni::sys::{jint, jlong, jsize};
+use jni::sys::{jboolean, jint, jlong, jsize};
 use redis::Value;
 use std::str::FromStr;
 use std::sync::Arc;
@@ -681,6 +684,135 @@ pub extern "system" fn Java_glide_ffi_resolvers_StatisticsResolver_getStatistics
     map
 }
 
+/// Returns a Java's `LinkedHashMap` of the `key` / `value` string pairs.
+fn strings_to_linked_hashmap<'a>(
+    env: &mut JNIEnv<'a>,
+    pairs: impl IntoIterator<Item = (String, String)>,
+) -> Option<JObject<'a>> {
+    let mut map = linked_hashmap::new_linked_hashmap(env)?;
+    for (key, value) in pairs {
+        linked_hashmap::put_strings(env, &mut map, &key, &value);
+    }
+    Some(map)
+}
+
+/// Returns a Java's `LinkedHashMap` representing the statistics of the requests of this process.
+///
+/// The map holds `commands`, the statistics of each request kind, and `nodes`, the statistics of each node,
+/// by its address. Each of them is a map of its counters, whose values are strings like in `getStatistics`.
+///
+/// This function is meant to be invoked by Java using JNI.
+///
+/// * `env`    - The JNI environment.
+/// * `_class`  - The class object. Not used.
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_glide_ffi_resolvers_StatisticsResolver_getRequestStatistics<'local>(
+    mut env: JNIEnv<'local>,
+    _class: JClass<'local>,
+) -> JObject<'local> {
+    fn request_statistics_to_map<'a>(env: &mut JNIEnv<'a>) -> Option<JObject<'a>> {
+        let statistics = request_statistics();
+        let mut commands = linked_hashmap::new_linked_hashmap(env)?;
+        for (name, command) in statistics.commands {
+            let mut command_map = strings_to_linked_hashmap(
+                env,
+                [
+                    ("calls".to_string(), command.calls.to_string()),
+                    ("usec".to_string(), command.usec.to_string()),
+                    (
+                        "usec_per_call".to_string(),
+                        command.usec_per_call.to_string(),
+                    ),
+                ],
+            )?;
+            let errors = strings_to_linked_hashmap(
+                env,
+                command
+                    .errors
+                    .into_iter()
+                    .map(|(error_type, count)| (error_type, count.to_string())),
+            )?;
+            linked_hashmap::put_object(env, &mut command_map, "errors", errors);
+            let latency_percentiles = strings_to_linked_hashmap(
+                env,
+                command
+                    .latency_percentiles_usec
+                    .into_iter()
+                    .map(|(percentile, usec)| (percentile, usec.to_string())),
+            )?;
+            linked_hashmap::put_object(
+                env,
+                &mut command_map,
+                "latency_percentiles_usec",
+                latency_percentiles,
+            );
+            linked_hashmap::put_object(env, &mut commands, &name, command_map);
+        }
+
+        let mut nodes = linked_hashmap::new_linked_hashmap(env)?;
+        for (address, node) in statistics.nodes {
+            let node_map = strings_to_linked_hashmap(
+                env,
+                [
+                    ("requests".to_string(), node.requests.to_string()),
+                    (
+                        "moved_redirects".to_string(),
+                        node.moved_redirects.to_string(),
+                    ),
+                    ("ask_redirects".to_string(), node.ask_redirects.to_string()),
+                    ("reconnects".to_string(), node.reconnects.to_string()),
+                    ("timeouts".to_string(), node.timeouts.to_string()),
+                ],
+            )?;
+            linked_hashmap::put_object(env, &mut nodes, &address, node_map);
+        }
+
+        let mut map = linked_hashmap::new_linked_hashmap(env)?;
+        linked_hashmap::put_object(env, &mut map, "commands", commands);
+        linked_hashmap::put_object(env, &mut map, "nodes", nodes);
+        Some(map)
+    }
+
+    match request_statistics_to_map(&mut env) {
+        Some(map) => map,
+        None => JObject::null(),
+    }
+}
+
+/// Enables or disables the collection of the request statistics of this process.
+///
+/// This function is meant to be invoked by Java using JNI.
+///
+/// * `_env`    - The JNI environment. Not used.
+/// * `_class`  - The class object. Not used.
+/// * `enabled` - Whether the statistics are collected.
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_glide_ffi_resolvers_StatisticsResolver_setRequestStatisticsEnabled<
+    'local,
+>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+    enabled: jboolean,
+) {
+    set_request_statistics_enabled(enabled != 0);
+}
+
+/// Resets the statistics of the requests of this process.
+///
+/// This function is meant to be invoked by Java using JNI.
+///
+/// * `_env`    - The JNI environment. Not used.
+/// * `_class`  - The class object. Not used.
+#[unsafe(no_mangle)]
+pub extern "system" fn Java_glide_ffi_resolvers_StatisticsResolver_resetRequestStatistics<
+    'local,
+>(
+    _env: JNIEnv<'local>,
+    _class: JClass<'local>,
+) {
+    reset_request_statistics();
+}
+
 #[unsafe(no_mangle)]
 pub extern "system" fn Java_glide_ffi_resolvers_OpenTelemetryResolver_initOpenTelemetry<'local>(
     mut env: JNIEnv<'local>,
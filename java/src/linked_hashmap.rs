@@ -30,6 +30,15 @@ pub fn put_strings<'a>(env: &mut JNIEnv<'a>, map: &mut JObject<'a>, key: &str, v
     put_objects(env, map, key, value)
 }
 
+/// Put `key` / `value` pair into the `map`, where `key` is of type `&str` and `value` is of type `JObject`,
+/// e.g. a nested map
+pub fn put_object<'a>(env: &mut JNIEnv<'a>, map: &mut JObject<'a>, key: &str, value: JObject<'a>) {
+    let Some(key) = string_to_jobject(env, key) else {
+        return;
+    };
+    put_objects(env, map, key, value)
+}
+
 /// Put `key` / `value` pair into the `map`, where both `key` and `value` are of type `JObject`
 pub fn put_objects<'a>(
     env: &mut JNIEnv<'a>,
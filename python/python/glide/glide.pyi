@@ -54,6 +54,9 @@ def value_from_pointer(pointer: int) -> TResult: ...
 def create_leaked_value(message: str) -> int: ...
 def create_leaked_bytes_vec(args_vec: List[bytes]) -> int: ...
 def get_statistics() -> dict: ...
+def get_request_statistics() -> dict: ...
+def set_request_statistics_enabled(enabled: bool) -> None: ...
+def reset_request_statistics() -> None: ...
 def py_init(level: Optional[Level], file_name: Optional[str]) -> Level: ...
 def py_log(log_level: Level, log_identifier: str, message: str) -> None: ...
 def create_otel_span(name: str) -> int: ...
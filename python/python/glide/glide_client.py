@@ -50,7 +50,10 @@ from .glide import (
     create_leaked_bytes_vec,
     create_otel_span,
     drop_otel_span,
+    get_request_statistics,
     get_statistics,
+    reset_request_statistics,
+    set_request_statistics_enabled,
     start_socket_listener_external,
     value_from_pointer,
 )
@@ -745,6 +748,30 @@ class BaseClient(CoreCommands):
     async def get_statistics(self) -> dict:
         return get_statistics()
 
+    async def get_request_statistics(self) -> dict:
+        """
+        Returns the statistics of the requests of this process, shared by all of its clients.
+        The statistics are only collected after `set_request_statistics_enabled` enabled them.
+
+        The dict holds "commands", the calls, errors by type, and latency percentiles in microseconds of each request kind,
+        e.g. "Get", "CustomCommand" or "Transaction", and "nodes", the requests, MOVED and ASK redirects, reconnects and
+        timeouts of each node, by its address.
+        """
+        return get_request_statistics()
+
+    async def set_request_statistics_enabled(self, enabled: bool) -> None:
+        """
+        Enables or disables the collection of the statistics of the requests of this process.
+        They aren't collected by default.
+        """
+        set_request_statistics_enabled(enabled)
+
+    async def reset_request_statistics(self) -> None:
+        """
+        Resets the statistics of the requests of this process.
+        """
+        reset_request_statistics()
+
     async def _update_connection_password(
         self, password: Optional[str], immediate_auth: bool
     ) -> TResult:
@@ -6,6 +6,7 @@ use glide_core::Telemetry;
 use glide_core::client::FINISHED_SCAN_CURSOR;
 use glide_core::client::get_or_init_runtime;
 use glide_core::errors::error_message;
+use glide_core::request_statistics;
 use glide_core::start_socket_listener;
 use glide_core::{
     DEFAULT_FLUSH_SIGNAL_INTERVAL_MS, DEFAULT_TRACE_SAMPLE_PERCENTAGE, GlideOpenTelemetry,
@@ -253,6 +254,9 @@ fn glide(_py: Python, m: &Bound<PyModule>) -> PyResult<()> {
     m.add_function(wrap_pyfunction!(create_leaked_value, m)?)?;
     m.add_function(wrap_pyfunction!(create_leaked_bytes_vec, m)?)?;
     m.add_function(wrap_pyfunction!(get_statistics, m)?)?;
+    m.add_function(wrap_pyfunction!(get_request_statistics, m)?)?;
+    m.add_function(wrap_pyfunction!(set_request_statistics_enabled, m)?)?;
+    m.add_function(wrap_pyfunction!(reset_request_statistics, m)?)?;
     m.add_function(wrap_pyfunction!(create_otel_span, m)?)?;
     m.add_function(wrap_pyfunction!(drop_otel_span, m)?)?;
     m.add_function(wrap_pyfunction!(init_opentelemetry, m)?)?;
@@ -289,6 +293,28 @@ fn glide(_py: Python, m: &Bound<PyModule>) -> PyResult<()> {
         })
     }
 
+    /// Returns the statistics of the requests of this process, per command and per node, as a dict.
+    #[pyfunction]
+    fn get_request_statistics(py: Python) -> PyResult<PyObject> {
+        let statistics = request_statistics::request_statistics().to_json();
+        Ok(py
+            .import("json")?
+            .call_method1("loads", (statistics,))?
+            .unbind())
+    }
+
+    /// Enables or disables the collection of the statistics of the requests of this process.
+    #[pyfunction]
+    fn set_request_statistics_enabled(enabled: bool) {
+        request_statistics::set_request_statistics_enabled(enabled);
+    }
+
+    /// Resets the statistics of the requests of this process.
+    #[pyfunction]
+    fn reset_request_statistics() {
+        request_statistics::reset_request_statistics();
+    }
+
     #[pyfunction]
     #[pyo3(signature = (level=None, file_name=None))]
     fn py_init(level: Option<Level>, file_name: Option<&str>) -> Level {